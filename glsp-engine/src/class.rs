@@ -1,6 +1,8 @@
 use super::code::GFn;
 use super::collections::{Arr, DequeAccess, DequeOps, Tab};
-use super::engine::{glsp, stock_syms::*, with_heap, with_vm, Guard, Sym, ToSym};
+use super::engine::{
+    glsp, stock_syms::*, with_heap, with_vm, Guard, RData, RRef, RRefMut, Sym, ToSym,
+};
 use super::error::GResult;
 use super::gc::{Allocate, Header, Raw, Root, Slot, Visitor};
 use super::iter::{GIter, GIterState};
@@ -8,6 +10,7 @@ use super::val::Val;
 use super::wrap::{CallableOps, FromVal, IntoCallArgs, IntoVal};
 use fnv::{FnvHashMap, FnvHashSet};
 use smallvec::SmallVec;
+use std::any::type_name;
 use std::cell::{RefCell, RefMut};
 use std::cmp::Ord;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -66,7 +69,9 @@ The `class` primitive type.
 
 Note that [`CallableOps`](trait.CallableOps.html) is implemented for `Root<Class>`, so an
 [`Obj`](struct.Obj.html) can be constructed from a `Class` using
-[`glsp::call`](fn.call.html).
+[`glsp::call`](fn.call.html) or [`CallableOps::invoke`](trait.CallableOps.html#method.invoke).
+The arguments are passed to the class' `init` method, and their count is checked against its
+parameter list.
 
 Classes are always stored on the garbage-collected heap, so they're normally represented by
 the type [`Root<Class>`](struct.Root.html).
@@ -284,7 +289,7 @@ impl Class {
         let sym = key.to_sym()?;
 
         match self.lookup(sym) {
-            Some(slot) => Ok(V::from_slot(&slot)?),
+            Some(slot) => V::from_slot(&slot).map_err(|err| err.prepend_path_key(&Val::Sym(sym))),
             None => {
                 bail!("attempted to access nonexistent const '{}'", sym)
             }
@@ -300,7 +305,10 @@ impl Class {
         let sym = key.to_sym()?;
 
         match self.lookup(sym) {
-            Some(slot) => Ok(Some(V::from_slot(&slot)?)),
+            Some(slot) => match V::from_slot(&slot) {
+                Ok(v) => Ok(Some(v)),
+                Err(err) => Err(err.prepend_path_key(&Val::Sym(sym))),
+            },
             None => Ok(None),
         }
     }
@@ -448,6 +456,34 @@ impl Class {
 
         glsp::arr_from_iter(self.is.iter().map(|mixin| Slot::Class(mixin.clone()))).unwrap()
     }
+
+    /**
+    Returns the names of all of the class' fields, constants and properties.
+
+    This includes names which are only bound while a particular state is enabled, and qualified
+    names like `Walking:speed`. The names are returned in an unspecified order.
+    */
+    pub fn field_names(&self) -> Vec<Sym> {
+        self.bindings
+            .iter()
+            .filter(|(_, binding)| !matches!(binding, Binding::Met(_)))
+            .map(|(&name, _)| name)
+            .collect()
+    }
+
+    /**
+    Returns the names of all of the class' methods.
+
+    This includes names which are only bound while a particular state is enabled, and qualified
+    names like `Walking:on-step`. The names are returned in an unspecified order.
+    */
+    pub fn method_names(&self) -> Vec<Sym> {
+        self.bindings
+            .iter()
+            .filter(|(_, binding)| matches!(binding, Binding::Met(_)))
+            .map(|(&name, _)| name)
+            .collect()
+    }
 }
 
 //vm.rs currently needs to be able to copy its callee to a Slot, so we can't implement CallableOps
@@ -620,6 +656,59 @@ impl Obj {
         Root::ptr_eq(&self.class.root(), class) || self.class.has_mixin(class)
     }
 
+    /**
+    Borrows the Rust struct which backs this object.
+
+    The object's fields are searched for an [`RData`](struct.RData.html) which stores a `T`.
+    The first match is borrowed using [`RData::try_borrow`](struct.RData.html#method.try_borrow).
+    This is useful for recovering a Rust struct's state from within an `rfn` which was bound
+    as one of the class' methods.
+
+    Returns an error if the object has been killed, if none of its fields store a `T`, or if
+    the matching `RData` is already mutably borrowed.
+    */
+    pub fn backing<T: 'static>(&self) -> GResult<RRef<T>> {
+        self.backing_rdata::<T>()?.try_borrow()
+    }
+
+    /**
+    Mutably borrows the Rust struct which backs this object.
+
+    Equivalent to [`backing`](#method.backing), except that the `RData` is borrowed using
+    [`RData::try_borrow_mut`](struct.RData.html#method.try_borrow_mut).
+    */
+    pub fn backing_mut<T: 'static>(&self) -> GResult<RRefMut<T>> {
+        self.backing_rdata::<T>()?.try_borrow_mut()
+    }
+
+    fn backing_rdata<T: 'static>(&self) -> GResult<Root<RData>> {
+        let storage_ref = self.storage.borrow();
+        let storage = match storage_ref.as_ref() {
+            Some(storage) => storage,
+            None => bail!("attempted to borrow the backing rdata of a killed obj"),
+        };
+
+        for field in &storage.fields {
+            if let Slot::RData(ref raw) = *field {
+                if raw.is::<T>() {
+                    return Ok(raw.root());
+                }
+            }
+        }
+
+        match self.class.name() {
+            Some(name) => bail!(
+                "obj of class {} is not backed by a {}",
+                name,
+                type_name::<T>()
+            ),
+            None => bail!(
+                "obj of anonymous class is not backed by a {}",
+                type_name::<T>()
+            ),
+        }
+    }
+
     //the common backing function for get(), call(), has(), etc.
     #[inline(always)]
     fn lookup(&self, key: Sym) -> Lookup {
@@ -838,6 +927,11 @@ impl Obj {
     /**
     Accesses the value of a field, constant or property.
 
+    If the name isn't bound, the error message will say so. If the value exists but can't be
+    converted to `V`, the error message will include the field's name, as in
+    `"at root.name: expected i32, received a str"`. To test whether a field exists without
+    accessing it, use [`has`](#method.has).
+
     Equivalent to [`[ob key]`](https://gamelisp.rs/std/access).
     */
     pub fn get<S: ToSym, V: FromVal>(&self, key: S) -> GResult<V> {
@@ -847,12 +941,16 @@ impl Obj {
         );
 
         let sym = key.to_sym()?;
-        match self.lookup(sym) {
-            Lookup::FieldOrConst(slot) => Ok(V::from_slot(&slot)?),
-            Lookup::PropGetter(getter) => self.invoke_method(&getter, &()),
+        let val: Val = match self.lookup(sym) {
+            Lookup::FieldOrConst(slot) => {
+                return V::from_slot(&slot).map_err(|err| err.prepend_path_key(&Val::Sym(sym)))
+            }
+            Lookup::PropGetter(getter) => self.invoke_method(&getter, &())?,
             Lookup::Met(..) => bail!("attempted to access method '{}' as a field", sym),
             Lookup::NotBound => bail!("attempted to access nonexistent field '{}'", sym),
-        }
+        };
+
+        V::from_val(&val).map_err(|err| err.prepend_path_key(&Val::Sym(sym)))
     }
 
     /**
@@ -862,10 +960,16 @@ impl Obj {
     */
     pub fn get_if_present<S: ToSym, V: FromVal>(&self, key: S) -> GResult<Option<V>> {
         if self.storage.borrow().is_some() {
-            match self.lookup(key.to_sym()?) {
-                Lookup::FieldOrConst(slot) => Ok(Some(V::from_slot(&slot)?)),
-                Lookup::PropGetter(getter) => Ok(Some(self.invoke_method(&getter, &())?)),
-                Lookup::Met(..) | Lookup::NotBound => Ok(None),
+            let sym = key.to_sym()?;
+            let val: Val = match self.lookup(sym) {
+                Lookup::FieldOrConst(slot) => slot.root(),
+                Lookup::PropGetter(getter) => self.invoke_method(&getter, &())?,
+                Lookup::Met(..) | Lookup::NotBound => return Ok(None),
+            };
+
+            match V::from_val(&val) {
+                Ok(v) => Ok(Some(v)),
+                Err(err) => Err(err.prepend_path_key(&Val::Sym(sym))),
             }
         } else {
             Ok(None)
@@ -977,6 +1081,21 @@ impl Obj {
         }
     }
 
+    /**
+    Invokes a method, discarding its return value.
+
+    This is equivalent to [`call`](#method.call), but it skips the conversion of the return
+    value, so there's no need to annotate its type.
+    */
+    pub fn call_unit<S, A>(&self, key: S, args: A) -> GResult<()>
+    where
+        S: ToSym,
+        A: IntoCallArgs,
+    {
+        self.call::<S, A, Val>(key, args)?;
+        Ok(())
+    }
+
     /**
     Invokes a method, if it exists.
 
@@ -1022,6 +1141,7 @@ impl Obj {
             let mut stacks = vm.stacks.borrow_mut();
             let starting_len = stacks.regs.len();
 
+            stacks.regs.reserve(args.arg_count() + 2);
             stacks.regs.push(Slot::Obj(
                 self.storage.borrow().as_ref().unwrap().raw_self.clone(),
             ));
@@ -1259,13 +1379,17 @@ impl Obj {
             drop(storage_ref);
             let _: Slot = self.invoke_method(&init_lookup, args)?;
         } else {
-            //if there's no initializer, check that we received zero args
+            //if there's no initializer, check that we received zero args. arg_count() is only
+            //a lower bound for some IntoCallArgs types, so we count the args which are produced
+            let mut slots = SmallVec::<[Slot; 8]>::new();
+            args.into_call_args(&mut slots)?;
+
             ensure!(
-                args.arg_count() == 0,
+                slots.is_empty(),
                 "too many arguments to state {}'s initializer: \
                     expected 0, received {}",
                 state_name,
-                args.arg_count()
+                slots.len()
             );
         }
 
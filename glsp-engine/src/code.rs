@@ -4,7 +4,7 @@ use super::error::{GError, GResult};
 use super::gc::{Allocate, Header, Raw, Root, Slot, Visitor};
 use super::transform::Predicate;
 use super::val::Val;
-use super::wrap::CallableOps;
+use super::wrap::{CallableOps, FromVal, IntoVal};
 use std::cell::{Cell, RefCell};
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Formatter};
@@ -205,6 +205,29 @@ pub enum CoroState {
     Poisoned,
 }
 
+/**
+The return value for the [`Coro::resume`](struct.Coro.html#method.resume) method.
+*/
+
+#[derive(Clone, Debug)]
+pub enum CoroStatus<Y, R> {
+    Yielded(Y),
+    Finished(R),
+}
+
+/**
+The return value for the [`glsp::run_coros_for`](fn.run_coros_for.html) function.
+*/
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RunOutcome {
+    //at least one coroutine was still runnable when the time budget ran out
+    BudgetExhausted,
+
+    //every coroutine is finished, poisoned or currently running
+    Idle,
+}
+
 #[derive(Copy, Clone)]
 pub(crate) enum PrivCoroState {
     Newborn,
@@ -286,6 +309,63 @@ impl Coro {
             PrivCoroState::Recycling => unreachable!(),
         }
     }
+
+    /**
+    Resumes a `newborn` or `paused` coroutine, converting the value which it yields or returns.
+
+    `arg` becomes the result of the `(yield)` form at which the coroutine is paused. A
+    `newborn` coroutine can't receive an argument, so in that case `arg` must be `()` or `#n`.
+
+    If the coroutine yields, its yielded value is converted to `Y`. If it returns, its return
+    value is converted to `R`. Resuming a coroutine which has already finished is an error.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let gfn = Root::<GFn>::from_val(&glsp::load_str("
+        (fn ()
+          (let received (yield 10))
+          received)
+    ")?)?;
+
+    let coro: Root<Coro> = glsp::call(&gfn, ())?;
+
+    match Coro::resume::<_, i32, i32>(&coro, ())? {
+        CoroStatus::Yielded(yielded) => assert_eq!(yielded, 10),
+        CoroStatus::Finished(_) => unreachable!(),
+    }
+
+    match Coro::resume::<_, i32, i32>(&coro, 20)? {
+        CoroStatus::Finished(result) => assert_eq!(result, 20),
+        CoroStatus::Yielded(_) => unreachable!(),
+    }
+
+    assert!(Coro::resume::<_, i32, i32>(&coro, ()).is_err());
+    #
+    # Ok(()) }).unwrap();
+    ```
+
+    Equivalent to [`(coro-run co arg)`](https://gamelisp.rs/std/coro-run).
+    */
+    pub fn resume<A, Y, R>(coro: &Root<Coro>, arg: A) -> GResult<CoroStatus<Y, R>>
+    where
+        A: IntoVal,
+        Y: FromVal,
+        R: FromVal,
+    {
+        let resume_arg = match (coro.state(), arg.into_val()?) {
+            (CoroState::Newborn, Val::Nil) => None,
+            (_, val) => Some(val),
+        };
+
+        let result = glsp::coro_run(coro, resume_arg)?;
+        match coro.state() {
+            CoroState::Finished => Ok(CoroStatus::Finished(R::from_val(&result)?)),
+            _ => Ok(CoroStatus::Yielded(Y::from_val(&result)?)),
+        }
+    }
 }
 
 impl Allocate for Bytecode {
@@ -2,7 +2,7 @@ use super::engine::{glsp, with_heap, Guard, Span};
 use super::error::{GError, GResult};
 use super::gc::{Allocate, Header, Root, Slot, Visitor};
 use super::iter::{GIter, GIterState};
-use super::val::Val;
+use super::val::{FreezeWalk, Val};
 use super::wrap::{FromVal, IntoVal};
 use fnv::FnvHashMap;
 use smallvec::SmallVec;
@@ -13,10 +13,11 @@ use std::convert::TryFrom;
 use std::default::Default;
 use std::fmt::{self, Debug};
 use std::hash::{Hash, Hasher};
-use std::iter::{repeat, FromIterator, FusedIterator};
+use std::iter::{once, repeat, FromIterator, FusedIterator};
 use std::marker::PhantomData;
 use std::mem::size_of;
 use std::ops::{Bound, RangeBounds};
+use std::rc::Rc;
 use std::{char, u16, u8};
 
 //-------------------------------------------------------------------------------------------------
@@ -30,6 +31,24 @@ The [`DequeAccess`](trait.DequeAccess.html) trait is generic over this trait. Th
 [`Arrs`](struct.Arr.html), [`Strs`](struct.Str.html) and [`Deques`](enum.Deque.html) to be
 indexed using any primitive integer type, including negative indexes.
 
+Negative indexes count backwards from the end of the deque, just like they do in GameLisp.
+Out-of-bounds indexes produce the same error message as they would when indexing a deque
+from a script.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+let arr = arr![10, 20, 30];
+
+assert_eq!(arr.get::<i32>(-1)?, 30);
+assert_eq!(arr.get::<i32>(0usize)?, 10);
+assert!(arr.get::<i32>(-4).is_err());
+#
+# Ok(()) }).unwrap();
+```
+
 This trait is [sealed]. It's not possible to implement this trait for your own types.
 
 [sealed]: https://rust-lang.github.io/api-guidelines/future-proofing.html#sealed-traits-protect-against-downstream-implementations-c-sealed
@@ -846,6 +865,12 @@ New arrays can be constructed using the [`arr!` macro](macro.arr.html) or variou
 functions, such as [`glsp::arr`](fn.arr.html) and [`glsp::arr_from_iter`](fn.arr_from_iter.html).
 Arrays are always stored on the garbage-collected heap, so they're normally represented by
 the type [`Root<Arr>`](struct.Root.html).
+
+An array's storage can be managed after construction using
+[`DequeOps::reserve`](trait.DequeOps.html#tymethod.reserve),
+[`DequeOps::shrink_to_fit`](trait.DequeOps.html#tymethod.shrink_to_fit) and
+[`DequeOps::capacity`](trait.DequeOps.html#tymethod.capacity). Any change in capacity is
+reported to the garbage collector, so that it can adjust its pacing.
 */
 
 pub struct Arr {
@@ -975,6 +1000,216 @@ impl Arr {
         Ok(true)
     }
 
+    /**
+    Converts each element to `T`, passes it to `f`, and collects the results into a new array.
+
+    The result is pre-allocated with the same length as this array. Returns an `Err` if any
+    conversion fails, or if `f` returns an `Err`. The array can't be mutated while `f` is
+    being called.
+
+    GameLisp data can't be shared between threads, so there's no parallel equivalent. When
+    the transform is expensive, consider converting the array into a Rust collection
+    first, using [`FromVal`](trait.FromVal.html).
+    */
+    pub fn map_collect<T, U, F>(&self, mut f: F) -> GResult<Root<Arr>>
+    where
+        T: FromVal,
+        U: IntoVal,
+        F: FnMut(T) -> GResult<U>,
+    {
+        let arr = glsp::arr_with_capacity(self.len());
+        for item in self.iter_to::<T>() {
+            arr.push(f(item?)?)?;
+        }
+
+        Ok(arr)
+    }
+
+    /**
+    Converts an array of records into a tuple of parallel `Vec`s, one per field.
+
+    Each element of the array must itself be an array with one element per field. For
+    example, an array of `(x y z)` triples can be converted into
+    `(Vec<f32>, Vec<f32>, Vec<f32>)`. Returns an `Err` if any record is not an array, has
+    the wrong number of fields, or contains a field which can't be converted.
+
+    The inverse operation is [`glsp::zip_fields`](fn.zip_fields.html).
+    */
+    pub fn unzip_fields<T: FieldColumns>(&self) -> GResult<T> {
+        T::unzip_fields(self)
+    }
+
+    /**
+    Converts each element to `T`, collecting the results into a `Vec`.
+
+    Equivalent to [`Vec::<T>::from_val`](trait.FromVal.html#tymethod.from_val). Returns an `Err`
+    if any conversion fails.
+    */
+    pub fn to_vec<T: FromVal>(&self) -> GResult<Vec<T>> {
+        let vec = self.borrow();
+
+        let mut result = Vec::with_capacity(vec.len());
+        for (i, slot) in vec.iter().enumerate() {
+            match T::from_slot(slot) {
+                Ok(item) => result.push(item),
+                Err(err) => return Err(err.prepend_path_index(i)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /**
+    Converts each element to `T`, writing the results to the start of `dst`.
+
+    Returns the number of elements written, which is equal to the array's length. Nothing is
+    allocated, and the array's storage is only borrowed once. This is useful for filling
+    vertex buffers or audio buffers from a script.
+
+    Returns an `Err` if the array is longer than `dst`, or if any conversion fails. When
+    conversion fails, some prefix of `dst` may already have been overwritten.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let arr = arr![0.5, 1.0, 1.5];
+
+    let mut samples = [0.0f32; 8];
+    let len = arr.copy_into(&mut samples)?;
+    assert!(samples[..len] == [0.5, 1.0, 1.5]);
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn copy_into<T: FromVal>(&self, dst: &mut [T]) -> GResult<usize> {
+        let vec = self.borrow();
+        ensure!(
+            vec.len() <= dst.len(),
+            "attempted to copy an arr of length {} into a slice of length {}",
+            vec.len(),
+            dst.len()
+        );
+
+        for (i, slot) in vec.iter().enumerate() {
+            match T::from_slot(slot) {
+                Ok(item) => dst[i] = item,
+                Err(err) => return Err(err.prepend_path_index(i)),
+            }
+        }
+
+        Ok(vec.len())
+    }
+
+    /**
+    Sorts the array in place, ordering its elements by a key.
+
+    `f` is called exactly once for each element, so it can be an expensive function, including
+    one which calls into GameLisp. The array isn't borrowed while `f` is running, and the
+    sorted elements are only written back if every call to `f` succeeds. If `f` returns an
+    `Err`, the array is left unchanged.
+
+    For a comparison function rather than a key function, use
+    [`sort_by`](trait.DequeOps.html#tymethod.sort_by).
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let arr = arr!["ccc", "a", "bb"];
+    arr.sort_by_key(|val| Ok(Root::<Str>::from_val(val)?.len()))?;
+    assert!(arr.get::<String>(0)? == "a");
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn sort_by_key<K, F>(&self, mut f: F) -> GResult<()>
+    where
+        K: Ord,
+        F: FnMut(&Val) -> GResult<K>,
+    {
+        let vals: SmallVec<[Val; 64]> = self.iter().collect();
+
+        let mut keyed = Vec::with_capacity(vals.len());
+        for val in vals {
+            keyed.push((f(&val)?, val));
+        }
+
+        keyed.sort_by(|(k0, _), (k1, _)| k0.cmp(k1));
+
+        ensure!(
+            keyed.len() == self.len(),
+            "the arr's length was changed during sort_by_key"
+        );
+
+        for (i, (_, val)) in keyed.into_iter().enumerate() {
+            self.set(i, val)?;
+        }
+
+        Ok(())
+    }
+
+    //the elements are pushed onto the FreezeWalk, rather than being processed recursively
+    pub(crate) fn deep_freeze_impl(&self, walk: &mut FreezeWalk) {
+        if walk.visit(self) {
+            self.freeze();
+            for element in self.iter() {
+                walk.push(element);
+            }
+        }
+    }
+
+    pub(crate) fn is_deep_frozen_impl(&self, walk: &mut FreezeWalk) -> bool {
+        if !walk.visit(self) {
+            return true;
+        }
+
+        if !self.header.frozen() {
+            return false;
+        }
+
+        for element in self.iter() {
+            walk.push(element);
+        }
+
+        true
+    }
+
+    /**
+    Appends the contents of a Rust iterator to the end of the array, returning the number of
+    elements appended.
+
+    This is similar to [`extend`](trait.DequeOps.html#tymethod.extend), but it's faster for
+    large iterators: the array's storage is only borrowed once, rather than once per element.
+
+    All elements are converted before the array is modified. If
+    [type conversion](trait.IntoVal.html) fails for any element, an `Err` is returned and the
+    array is left unchanged.
+    */
+    pub fn extend_from_val_iter<I>(&self, source: I) -> GResult<usize>
+    where
+        I: IntoIterator,
+        I::Item: IntoVal,
+    {
+        //we don't leave a borrow_mut() outstanding while converting elements, because into_slot()
+        //may execute arbitrary user code which needs access to the Arr
+        let mut slots = SmallVec::<[Slot; 16]>::new();
+        for item in source {
+            slots.push(item.into_slot()?);
+        }
+
+        let count = slots.len();
+        self.borrow_mut_with_capacity_guard(|vec| {
+            vec.reserve(count);
+            for slot in slots {
+                self.write_barrier_slot(&slot);
+                vec.push_back(slot);
+            }
+
+            Ok(count)
+        })
+    }
+
     /**
     Creates an indexing iterator for this collection.
 
@@ -1065,21 +1300,19 @@ impl DequeOps for Arr {
         self.header.freeze()
     }
 
-    //todo: handle reference cycles
     fn deep_freeze(&self) {
-        self.freeze();
-        for element in self.iter() {
-            element.deep_freeze();
-        }
+        let mut walk = FreezeWalk::new();
+        self.deep_freeze_impl(&mut walk);
+        walk.deep_freeze_pending();
     }
 
     fn is_frozen(&self) -> bool {
         self.header.frozen()
     }
 
-    //todo: handle reference cycles
     fn is_deep_frozen(&self) -> bool {
-        self.header.frozen() && self.iter().all(|val| val.is_deep_frozen())
+        let mut walk = FreezeWalk::new();
+        self.is_deep_frozen_impl(&mut walk) && walk.is_deep_frozen_pending()
     }
 
     fn can_mutate(&self) -> bool {
@@ -1393,6 +1626,128 @@ impl<I: DequeIndex, R: DequeRange<I> + Debug> DequeAccessRange<I, R> for Arr {
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// FieldColumns
+//-------------------------------------------------------------------------------------------------
+
+/**
+A tuple of `Vec`s which can store an array of records in columnar form.
+
+Used by [`Arr::unzip_fields`](struct.Arr.html#method.unzip_fields) and
+[`glsp::zip_fields`](fn.zip_fields.html). This trait is implemented for tuples of up to
+twelve `Vec`s, where each element type implements both [`FromVal`](trait.FromVal.html) and
+[`IntoVal`](trait.IntoVal.html).
+
+This trait is [sealed]. It's not possible to implement this trait for your own types.
+
+[sealed]: https://rust-lang.github.io/api-guidelines/future-proofing.html#sealed-traits-protect-against-downstream-implementations-c-sealed
+*/
+
+pub trait FieldColumns: Sized + field_columns_private::Sealed {
+    #[doc(hidden)]
+    fn unzip_fields(arr: &Arr) -> GResult<Self>;
+
+    #[doc(hidden)]
+    fn zip_fields(self) -> GResult<Root<Arr>>;
+}
+
+mod field_columns_private {
+    use crate::wrap::{FromVal, IntoVal};
+
+    pub trait Sealed {}
+
+    macro_rules! impl_sealed_field_columns {
+        ($($t:ident),+) => (
+            impl<$($t: FromVal + IntoVal),+> Sealed for ($(Vec<$t>,)+) {}
+        );
+    }
+
+    impl_sealed_field_columns!(A);
+    impl_sealed_field_columns!(A, B);
+    impl_sealed_field_columns!(A, B, C);
+    impl_sealed_field_columns!(A, B, C, D);
+    impl_sealed_field_columns!(A, B, C, D, E);
+    impl_sealed_field_columns!(A, B, C, D, E, F);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G, H);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G, H, I);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G, H, I, J);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G, H, I, J, K);
+    impl_sealed_field_columns!(A, B, C, D, E, F, G, H, I, J, K, L);
+}
+
+macro_rules! impl_field_columns {
+    ($len:literal: $($t:ident $i:tt),+) => (
+        impl<$($t),+> FieldColumns for ($(Vec<$t>,)+)
+        where
+            $($t: FromVal + IntoVal),+
+        {
+            fn unzip_fields(arr: &Arr) -> GResult<Self> {
+                let mut columns = ($(Vec::<$t>::with_capacity(arr.len()),)+);
+
+                for (record_i, record) in arr.borrow().iter().enumerate() {
+                    match *record {
+                        Slot::Arr(ref record) => {
+                            ensure!(record.len() == $len,
+                                    "record {} has {} fields, expected {}",
+                                    record_i, record.len(), $len);
+
+                            let fields = record.borrow();
+                            $(
+                                match $t::from_slot(&fields[$i]) {
+                                    Ok(field) => columns.$i.push(field),
+                                    Err(err) => {
+                                        return Err(err.prepend_path_index($i)
+                                                      .prepend_path_index(record_i))
+                                    }
+                                }
+                            )+
+                        }
+                        ref record => bail!("record {} is {}, expected an arr",
+                                            record_i, record.a_type_name())
+                    }
+                }
+
+                Ok(columns)
+            }
+
+            fn zip_fields(self) -> GResult<Root<Arr>> {
+                let len = self.0.len();
+                $(
+                    ensure!(self.$i.len() == len,
+                            "column {} has {} elements, expected {}",
+                            $i, self.$i.len(), len);
+                )+
+
+                let arr = glsp::arr_with_capacity(len);
+                let mut iters = ($(self.$i.into_iter(),)+);
+                for _ in 0..len {
+                    let record = glsp::arr_with_capacity($len);
+                    $(
+                        record.push(iters.$i.next().unwrap())?;
+                    )+
+                    arr.push(record)?;
+                }
+
+                Ok(arr)
+            }
+        }
+    );
+}
+
+impl_field_columns!( 1: A 0);
+impl_field_columns!( 2: A 0, B 1);
+impl_field_columns!( 3: A 0, B 1, C 2);
+impl_field_columns!( 4: A 0, B 1, C 2, D 3);
+impl_field_columns!( 5: A 0, B 1, C 2, D 3, E 4);
+impl_field_columns!( 6: A 0, B 1, C 2, D 3, E 4, F 5);
+impl_field_columns!( 7: A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_field_columns!( 8: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_field_columns!( 9: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_field_columns!(10: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_field_columns!(11: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_field_columns!(12: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
 //-------------------------------------------------------------------------------------------------
 // arr!, try_arr!, Splay
 //-------------------------------------------------------------------------------------------------
@@ -1880,11 +2235,45 @@ New strings can be constructed using the [`str!` macro](macro.str.html) or vario
 functions, such as [`glsp::str`](fn.str.html) and [`glsp::str_from_iter`](fn.str_from_iter.html).
 Strings are always stored on the garbage-collected heap, so they're normally represented by
 the type [`Root<Str>`](struct.Root.html).
+
+`Root<Str>` implements [`fmt::Write`](https://doc.rust-lang.org/std/fmt/trait.Write.html),
+so formatted text can be appended to a string in place, without first allocating a Rust
+`String`. Writing fails if the string is frozen or currently borrowed.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+# use std::fmt::Write;
+#
+# Engine::new().run(|| {
+let mut st = glsp::str();
+write!(st, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+assert!(st.to_string() == "1 + 2 = 3");
+# Ok(()) }).unwrap();
+```
 */
 
 pub struct Str {
     header: Header,
     storage: RefCell<StrStorage>,
+    cache: RefCell<Option<Box<StrCache>>>,
+}
+
+//lazily-constructed copies of the str's contents in other formats, which are discarded whenever
+//the str is mutated. see Str::to_shared_rust_str() and Str::as_bytes(). most strs are never
+//converted, so the cache is boxed to keep it from bloating every str.
+#[derive(Default)]
+struct StrCache {
+    utf8: Option<Rc<String>>,
+    bytes: Option<Rc<Vec<u8>>>,
+}
+
+impl StrCache {
+    fn memory_usage(&self) -> usize {
+        let utf8_usage = self.utf8.as_ref().map_or(0, |utf8| utf8.capacity());
+        let bytes_usage = self.bytes.as_ref().map_or(0, |bytes| bytes.capacity());
+        size_of::<StrCache>() + utf8_usage + bytes_usage
+    }
 }
 
 impl Allocate for Str {
@@ -1901,11 +2290,7 @@ impl Allocate for Str {
     }
 
     fn owned_memory_usage(&self) -> usize {
-        match *self.borrow() {
-            StrStorage::Str1(ref vec) => (vec.capacity() + 1),
-            StrStorage::Str2(ref vec) => (vec.capacity() + 1) * 2,
-            StrStorage::Str4(ref vec) => (vec.capacity() + 1) * 4,
-        }
+        self.storage_memory_usage() + self.cache_memory_usage()
     }
 }
 
@@ -1935,6 +2320,7 @@ impl Str {
         Str {
             header: Header::new(),
             storage: RefCell::new(StrStorage::Str1(VecDeque::new())),
+            cache: RefCell::new(None),
         }
     }
 
@@ -1951,6 +2337,7 @@ impl Str {
         Str {
             header: Header::new(),
             storage: RefCell::new(storage),
+            cache: RefCell::new(None),
         }
     }
 
@@ -1975,6 +2362,7 @@ impl Str {
         Ok(Str {
             header: Header::new(),
             storage: RefCell::new(storage),
+            cache: RefCell::new(None),
         })
     }
 
@@ -1982,6 +2370,7 @@ impl Str {
         Str {
             header: Header::new(),
             storage: RefCell::new(StrStorage::Str1(VecDeque::with_capacity(capacity))),
+            cache: RefCell::new(None),
         }
     }
 
@@ -1994,6 +2383,48 @@ impl Str {
         }
     }
 
+    /*
+    returns a utf-8 copy of the str's contents. the copy is cached, so repeated calls are cheap
+    until the str is next mutated. this is used to avoid copying large strs when they're passed
+    to an rfn as a &str argument.
+    */
+    pub(crate) fn to_shared_rust_str(&self) -> Rc<String> {
+        if let Some(ref cache) = *self.cache.borrow() {
+            if let Some(ref utf8) = cache.utf8 {
+                return Rc::clone(utf8);
+            }
+        }
+
+        let utf8 = Rc::new(self.to_rust_string());
+        self.update_cache(|cache| cache.utf8 = Some(Rc::clone(&utf8)));
+        utf8
+    }
+
+    //the cache can't be updated while the result of as_bytes() is borrowed, in which case
+    //this is a no-op
+    fn update_cache<F: FnOnce(&mut StrCache)>(&self, f: F) {
+        if let Ok(mut cache) = self.cache.try_borrow_mut() {
+            let prev_usage = cache.as_ref().map_or(0, |cache| cache.memory_usage());
+
+            let cache = cache.get_or_insert_with(Default::default);
+            f(cache);
+
+            self.memory_usage_barrier(prev_usage, cache.memory_usage());
+        }
+    }
+
+    fn storage_memory_usage(&self) -> usize {
+        match *self.borrow() {
+            StrStorage::Str1(ref vec) => (vec.capacity() + 1),
+            StrStorage::Str2(ref vec) => (vec.capacity() + 1) * 2,
+            StrStorage::Str4(ref vec) => (vec.capacity() + 1) * 4,
+        }
+    }
+
+    fn cache_memory_usage(&self) -> usize {
+        self.cache.borrow().as_ref().map_or(0, |cache| cache.memory_usage())
+    }
+
     pub(crate) fn to_escaped_string(&self) -> String {
         with_str_storage!(&*self.borrow(), vec, (), {
             let mut builder = String::new();
@@ -2068,6 +2499,100 @@ impl Str {
         })
     }
 
+    /**
+    Borrows this string's contents as a byte slice, with one byte per character.
+
+    This is useful when a string is used to store binary data. Each character is converted to
+    the byte with the same value, which is the Latin-1 encoding. It will fail if the string
+    contains any characters above `'\u{ff}'`. The same encoding is used when a string is
+    passed to an `RFn` as a `&[u8]` argument, or converted into a [`Blob`](struct.Blob.html).
+
+    The bytes are copied into a buffer which is cached until the next time the string is
+    mutated. While the byte slice is borrowed, any attempt to mutate the string will fail.
+    */
+    pub fn as_bytes(&self) -> GResult<Ref<[u8]>> {
+        self.to_shared_bytes()?;
+
+        //if to_shared_bytes() couldn't update the cache, it's because it's already borrowed
+        //by as_bytes(), in which case it already contains the bytes
+        Ok(Ref::map(self.cache.borrow(), |cache| {
+            &cache.as_ref().unwrap().bytes.as_ref().unwrap()[..]
+        }))
+    }
+
+    //like as_bytes(), but the result doesn't borrow the str
+    pub(crate) fn to_shared_bytes(&self) -> GResult<Rc<Vec<u8>>> {
+        if let Some(ref cache) = *self.cache.borrow() {
+            if let Some(ref bytes) = cache.bytes {
+                return Ok(Rc::clone(bytes));
+            }
+        }
+
+        let bytes = with_str_storage!(&*self.borrow(), vec, (), {
+            let mut bytes = Vec::with_capacity(vec.len());
+            for ch in vec.iter().map(|ch| ch.into_char()) {
+                ensure!(
+                    (ch as u32) <= 0xff,
+                    "attempted to borrow a str as bytes, but it contains the character {:?}",
+                    ch
+                );
+
+                bytes.push(ch as u8);
+            }
+
+            bytes
+        });
+
+        let bytes = Rc::new(bytes);
+        self.update_cache(|cache| cache.bytes = Some(Rc::clone(&bytes)));
+        Ok(bytes)
+    }
+
+    /**
+    Returns the number of characters in this string.
+
+    This is the same as [`len`](trait.DequeOps.html#tymethod.len). It's provided as a reminder
+    that strings are indexed by character, not by byte: the character count may differ from
+    the length of the string's utf-8 encoding.
+    */
+    pub fn char_len(&self) -> usize {
+        self.borrow().len()
+    }
+
+    /**
+    Returns the character at index `i`.
+
+    Returns an `Err` if `i` is out of bounds, with the same error message as
+    [`[st i]`](https://gamelisp.rs/std/access).
+    */
+    pub fn char_at(&self, i: usize) -> GResult<char> {
+        with_str_storage!(&*self.borrow(), vec, (), {
+            ensure!(
+                i < vec.len(),
+                "out-of-bounds arr access: len is {}, index is {}",
+                vec.len(),
+                i
+            );
+
+            Ok(vec[i].into_char())
+        })
+    }
+
+    /**
+    Creates an iterator over this string's characters.
+
+    Unlike [`iter`](trait.DequeOps.html#method.iter), which borrows the string once for each
+    character, this iterator borrows the string's storage once, for its entire lifetime.
+    While the iterator exists, any attempt to mutate the string will fail.
+    */
+    pub fn chars(&self) -> StrChars {
+        StrChars {
+            storage: self.borrow(),
+            start_index: 0,
+            past_the_end_index: self.char_len(),
+        }
+    }
+
     fn borrow(&self) -> Ref<StrStorage> {
         self.storage.borrow()
     }
@@ -2075,7 +2600,16 @@ impl Str {
     fn borrow_mut(&self) -> GResult<RefMut<StrStorage>> {
         ensure!(!self.header.frozen(), "attempted to mutate a frozen str");
         match self.storage.try_borrow_mut() {
-            Ok(ref_mut) => Ok(ref_mut),
+            Ok(ref_mut) => match self.cache.try_borrow_mut() {
+                Ok(mut cache) => {
+                    if let Some(cache) = cache.take() {
+                        self.memory_usage_barrier(cache.memory_usage(), 0);
+                    }
+
+                    Ok(ref_mut)
+                }
+                Err(_) => bail!("attempted to mutate a borrowed str"),
+            },
             Err(_) => bail!("attempted to mutate a borrowed str"),
         }
     }
@@ -2088,9 +2622,10 @@ impl Str {
     where
         F: FnOnce(&mut RefMut<StrStorage>) -> GResult<R>,
     {
-        let prev_usage = self.owned_memory_usage();
+        //borrow_mut() discards the cache, and emits its own memory_usage_barrier for it
+        let prev_usage = self.storage_memory_usage();
         let _guard = Guard::new(|| {
-            let cur_usage = self.owned_memory_usage();
+            let cur_usage = self.storage_memory_usage();
             if prev_usage != cur_usage {
                 self.memory_usage_barrier(prev_usage, cur_usage);
             }
@@ -2115,6 +2650,52 @@ impl fmt::Write for Root<Str> {
     }
 }
 
+/**
+An iterator over a string's characters.
+
+Created by [`Str::chars`](struct.Str.html#method.chars).
+*/
+pub struct StrChars<'a> {
+    storage: Ref<'a, StrStorage>,
+    start_index: usize,
+    past_the_end_index: usize,
+}
+
+impl<'a> Iterator for StrChars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.start_index < self.past_the_end_index {
+            let i = self.start_index;
+            self.start_index += 1;
+            Some(with_str_storage!(&*self.storage, vec, (), vec[i].into_char()))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.past_the_end_index - self.start_index;
+        (size, Some(size))
+    }
+}
+
+impl<'a> DoubleEndedIterator for StrChars<'a> {
+    fn next_back(&mut self) -> Option<char> {
+        if self.start_index < self.past_the_end_index {
+            self.past_the_end_index -= 1;
+            let i = self.past_the_end_index;
+            Some(with_str_storage!(&*self.storage, vec, (), vec[i].into_char()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for StrChars<'a> {}
+
+impl<'a> FusedIterator for StrChars<'a> {}
+
 impl PartialEq<Str> for Str {
     fn eq(&self, other: &Str) -> bool {
         self.iter().eq(other.iter())
@@ -2166,7 +2747,9 @@ impl DequeOps for Str {
     }
 
     fn can_mutate(&self) -> bool {
-        (!self.header.frozen()) && self.storage.try_borrow_mut().is_ok()
+        (!self.header.frozen())
+            && self.storage.try_borrow_mut().is_ok()
+            && self.cache.try_borrow_mut().is_ok()
     }
 
     fn sort(&self) -> GResult<()> {
@@ -3102,6 +3685,10 @@ New tables can be constructed using the [`tab!` macro](macro.tab.html) or variou
 functions, such as [`glsp::tab`](fn.tab.html) and [`glsp::tab_from_iter`](fn.tab_from_iter.html).
 Tables are always stored on the garbage-collected heap, so they're normally represented by
 the type [`Root<Tab>`](struct.Root.html).
+
+A table's storage can be managed after construction using [`reserve`](#method.reserve),
+[`shrink_to_fit`](#method.shrink_to_fit) and [`capacity`](#method.capacity), just like
+an [array](struct.Arr.html).
 */
 
 pub struct Tab {
@@ -3239,6 +3826,79 @@ macro_rules! tab {
     );
 }
 
+//the total order used by Tab::sorted_iter. the sort would be unpredictable if this were
+//inconsistent, so every pair of keys is either ordered or considered equivalent: we only fall
+//back to Ordering::Equal for two values of a type which we can't inspect, like two objs
+fn sorted_iter_cmp(v0: &Val, v1: &Val) -> Ordering {
+    //the order of groups, and then the order of types within a group
+    fn rank(val: &Val) -> (u8, u8) {
+        match *val {
+            Val::Int(_) => (0, 0),
+            Val::Flo(_) => (0, 1),
+            Val::Char(_) => (1, 0),
+            Val::Sym(_) => (1, 1),
+            Val::Str(_) => (1, 2),
+            Val::Bool(_) => (2, 0),
+            Val::Arr(_) => (3, 0),
+            _ => (4, 0),
+        }
+    }
+
+    //every i32 and f32 can be represented exactly as an f64. nan sorts after every number
+    fn number(val: &Val) -> Option<f64> {
+        match *val {
+            Val::Int(i) => Some(i as f64),
+            Val::Flo(f) if f.is_nan() => None,
+            Val::Flo(f) => Some(f as f64),
+            _ => unreachable!(),
+        }
+    }
+
+    //chars, syms and strs are compared char-by-char, without allocating. this gives the same
+    //order as comparing their utf-8 bytes.
+    fn text_cmp(v0: &Val, v1: &Val) -> Ordering {
+        match *v0 {
+            Val::Char(ch) => text_cmp_with(once(ch), v1),
+            Val::Sym(sym) => text_cmp_with(sym.name().chars(), v1),
+            Val::Str(ref st) => text_cmp_with(st.chars(), v1),
+            _ => unreachable!(),
+        }
+    }
+
+    fn text_cmp_with<I: Iterator<Item = char>>(chars0: I, v1: &Val) -> Ordering {
+        match *v1 {
+            Val::Char(ch) => chars0.cmp(once(ch)),
+            Val::Sym(sym) => chars0.cmp(sym.name().chars()),
+            Val::Str(ref st) => chars0.cmp(st.chars()),
+            _ => unreachable!(),
+        }
+    }
+
+    let (group0, kind0) = rank(v0);
+    let (group1, kind1) = rank(v1);
+
+    let ordering = group0.cmp(&group1).then_with(|| match (v0, v1) {
+        _ if group0 == 0 => match (number(v0), number(v1)) {
+            (Some(n0), Some(n1)) => n0.partial_cmp(&n1).unwrap(),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        _ if group0 == 1 => text_cmp(v0, v1),
+        (&Val::Bool(b0), &Val::Bool(b1)) => b0.cmp(&b1),
+        (&Val::Arr(ref a0), &Val::Arr(ref a1)) => a0
+            .iter()
+            .zip(a1.iter())
+            .map(|(e0, e1)| sorted_iter_cmp(&e0, &e1))
+            .find(|&ordering| ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+            .then(a0.len().cmp(&a1.len())),
+        _ => v0.type_name().cmp(v1.type_name()),
+    });
+
+    ordering.then(kind0.cmp(&kind1))
+}
+
 impl Tab {
     pub(crate) fn new() -> Tab {
         Tab {
@@ -3447,6 +4107,36 @@ impl Tab {
         Ok(self.borrow().contains_key(&key))
     }
 
+    /**
+    Returns the [entry](struct.TabEntry.html) for the given key, which can be used to insert or
+    update its value.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let counts = glsp::tab();
+    for word in &["fire", "ice", "fire"] {
+        counts
+            .entry(*word)?
+            .and_modify(|n| -> GResult<i32> { Ok(i32::from_val(&n)? + 1) })?
+            .or_insert(1)?;
+    }
+
+    assert_eq!(counts.get::<_, i32>("fire")?, 2);
+    assert_eq!(counts.get::<_, i32>("ice")?, 1);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn entry<K: IntoVal>(&self, key: K) -> GResult<TabEntry> {
+        Ok(TabEntry {
+            tab: self,
+            key: key.into_val()?,
+        })
+    }
+
     /**
     Removes a key/value pair from the table, without returning it.
 
@@ -3577,6 +4267,74 @@ impl Tab {
         TabEntries(self.borrow())
     }
 
+    /**
+    Returns an iterator over the table's `(key, value)` pairs, sorted by key.
+
+    The iteration order of [`entries`](#method.entries) depends on the hash of each key, so
+    it may change from one run to the next. This method produces a deterministic order
+    instead, which is useful for serialization and golden-file testing.
+
+    Numeric keys come first, in ascending order. Ints and flos are compared exactly, so
+    `16777217` sorts after `16777216.0`. When an int and a flo are numerically equal, the int
+    comes first, and NaN comes after every other number.
+
+    Numbers are followed by chars, syms and strs, sorted by their text. When two of those keys
+    have the same text, chars come before syms, and syms come before strs. Then come `#f` and
+    `#t`, followed by arrs, which are compared element-by-element using this same ordering, with
+    shorter arrs sorting before longer arrs which they prefix. Any other keys come last, grouped
+    by type. Tables don't record their insertion order, so the relative order of two keys which
+    can't be compared (such as two objs) is unspecified.
+
+    The entries are copied and sorted up front, so this method is much slower than `entries`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let tab = tab! {
+        (sym!("b"), 2),
+        (sym!("a"), 1),
+        (10, 3),
+    };
+
+    let keys: Vec<Val> = tab.sorted_iter().map(|(key, _)| key).collect();
+    assert!(keys[0] == Val::Int(10));
+    assert!(keys[1] == Val::Sym(sym!("a")));
+
+    //arrs of mixed types are still totally ordered
+    let tab = tab! {
+        (arr![1, "b"], 0),
+        (arr![1, 2.5], 0),
+        (arr![1], 0),
+        (arr![1, sym!("b")], 0),
+        (arr![16777216.0], 0),
+        (arr![16777217], 0),
+    };
+
+    let keys: Vec<Val> = tab.sorted_iter().map(|(key, _)| key).collect();
+    let expected = [
+        arr![1],
+        arr![1, 2.5],
+        arr![1, sym!("b")],
+        arr![1, "b"],
+        arr![16777216.0],
+        arr![16777217],
+    ];
+
+    for (key, expected) in keys.iter().zip(&expected) {
+        assert!(glsp::deep_eq(key, &Val::Arr(expected.clone())));
+    }
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn sorted_iter(&self) -> std::vec::IntoIter<(Val, Val)> {
+        let mut entries: Vec<(Val, Val)> = self.entries().iter().collect();
+        entries.sort_by(|(k0, _), (k1, _)| sorted_iter_cmp(k0, k1));
+        entries.into_iter()
+    }
+
     /**
     Returns a [`Root<GIter>`](struct.GIter.html) which iterates over the table's keys.
 
@@ -3651,12 +4409,19 @@ impl Tab {
     Equivalent to [`(deep-freeze! t)`](https://gamelisp.rs/std/deep-freeze-mut).
     */
 
-    //todo: handle reference cycles
     pub fn deep_freeze(&self) {
-        self.freeze();
-        for (key, value) in self.entries().iter() {
-            key.deep_freeze();
-            value.deep_freeze();
+        let mut walk = FreezeWalk::new();
+        self.deep_freeze_impl(&mut walk);
+        walk.deep_freeze_pending();
+    }
+
+    pub(crate) fn deep_freeze_impl(&self, walk: &mut FreezeWalk) {
+        if walk.visit(self) {
+            self.freeze();
+            for (key, value) in self.entries().iter() {
+                walk.push(key);
+                walk.push(value);
+            }
         }
     }
 
@@ -3665,14 +4430,27 @@ impl Tab {
         self.header.frozen()
     }
 
-    //todo: handle reference cycles
     ///Returns `true` if the table and all of its contents have been frozen.
     pub fn is_deep_frozen(&self) -> bool {
-        self.is_frozen()
-            && self
-                .entries()
-                .iter()
-                .all(|(key, value)| key.is_deep_frozen() && value.is_deep_frozen())
+        let mut walk = FreezeWalk::new();
+        self.is_deep_frozen_impl(&mut walk) && walk.is_deep_frozen_pending()
+    }
+
+    pub(crate) fn is_deep_frozen_impl(&self, walk: &mut FreezeWalk) -> bool {
+        if !walk.visit(self) {
+            return true;
+        }
+
+        if !self.is_frozen() {
+            return false;
+        }
+
+        for (key, value) in self.entries().iter() {
+            walk.push(key);
+            walk.push(value);
+        }
+
+        true
     }
 
     /**
@@ -3694,6 +4472,104 @@ impl PartialEq<Tab> for Tab {
 
 impl Eq for Tab {}
 
+/**
+A single key in a table, which may be vacant or occupied.
+
+Created by [`Tab::entry`](struct.Tab.html#method.entry). The API is similar to
+[`std::collections::hash_map::Entry`][0].
+
+Each method looks up the key when it's called, rather than when the entry is created, so
+chaining `and_modify` and `or_insert` performs two separate lookups. Any closure passed to an
+entry's method is called while the table is not borrowed, and its result is converted into a
+value before the table is mutated, so the closure may access the table.
+
+[0]: https://doc.rust-lang.org/std/collections/hash_map/enum.Entry.html
+*/
+
+pub struct TabEntry<'a> {
+    tab: &'a Tab,
+    key: Val,
+}
+
+impl<'a> TabEntry<'a> {
+    ///Returns the entry's key.
+    pub fn key(&self) -> &Val {
+        &self.key
+    }
+
+    /**
+    Inserts `default` if the entry is vacant. Returns the entry's value.
+
+    Fails if the table is frozen or already borrowed.
+    */
+    pub fn or_insert<V: IntoVal>(self, default: V) -> GResult<Val> {
+        self.or_insert_with(|| default)
+    }
+
+    /**
+    Calls `f` and inserts its result if the entry is vacant. Returns the entry's value.
+
+    Fails if the table is frozen or already borrowed.
+    */
+    pub fn or_insert_with<V, F>(self, f: F) -> GResult<Val>
+    where
+        V: IntoVal,
+        F: FnOnce() -> V,
+    {
+        let tab = self.tab;
+        let key = Slot::from_val(&self.key);
+
+        if let Some(value) = tab.borrow().get(&key) {
+            return Ok(value.root());
+        }
+
+        let value = f().into_slot()?;
+
+        //`f` may have inserted the key itself, in which case its value takes precedence
+        tab.borrow_mut_with_capacity_guard(|map| match map.entry(key) {
+            hash_map::Entry::Occupied(entry) => Ok(entry.get().root()),
+            hash_map::Entry::Vacant(entry) => {
+                tab.write_barrier_slot(entry.key());
+                tab.write_barrier_slot(&value);
+                Ok(entry.insert(value).root())
+            }
+        })
+    }
+
+    /**
+    Replaces the entry's value with the result of `f`, if the entry is occupied.
+
+    Fails if the table is frozen or already borrowed.
+    */
+    pub fn and_modify<V, F>(self, f: F) -> GResult<TabEntry<'a>>
+    where
+        V: IntoVal,
+        F: FnOnce(Val) -> V,
+    {
+        let tab = self.tab;
+        let key = Slot::from_val(&self.key);
+
+        let prev_value = match tab.borrow().get(&key) {
+            Some(prev_value) => prev_value.root(),
+            None => return Ok(self),
+        };
+
+        let value = f(prev_value).into_slot()?;
+
+        //`f` may have removed the key, in which case the entry stays vacant
+        tab.borrow_mut_with_capacity_guard(|map| {
+            if let Some(dst_value) = map.get_mut(&key) {
+                tab.write_barrier_slot(&value);
+                *dst_value = value;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(self)
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // TabEntries, IterTab, IterKeys, IterValues
 //-------------------------------------------------------------------------------------------------
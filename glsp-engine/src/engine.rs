@@ -1,19 +1,21 @@
 use self::stock_syms::*;
 use super::class::{Class, Obj};
-use super::code::{Coro, GFn};
-use super::collections::{Arr, DequeAccess, DequeOps, IntoElement, Str, Tab};
-use super::error::GResult;
+use super::code::{Coro, CoroState, GFn, RunOutcome};
+use super::collections::{Arr, DequeAccess, DequeOps, FieldColumns, IntoElement, Str, Tab};
+use super::error::{GError, GResult};
 use super::eval::{Env, EnvMode, Expander, Expansion};
-use super::gc::{Allocate, Gc, GcVisitor, Header, Heap, Raw, Root, Slot, Visitor};
-use super::iter::{GIter, GIterState, Iterable, IterableOps, RawCallable};
-use super::parse::Parser;
+use super::gc::{Allocate, Gc, GcStats, GcVisitor, Header, Heap, Raw, Root, Slot, Visitor};
+use super::iter::{FromFn, GIter, GIterState, Iterable, IterableOps, RawCallable};
+use super::parse::{Parser, SpanTable};
+use super::snapshot::{self, HeapSerializer};
 use super::transform::{known_ops, KnownOp};
 use super::val::{Num, Val};
 use super::vm::{Frame, GlspApiName, Vm};
 use super::wrap::{
-    wrap, Callable, CallableOps, FromVal, IntoCallArgs, IntoVal, WrappedCall, Wrapper,
+    wrap, ArgDefaults, ArgType, Callable, CallableOps, FnMutCell, FromVal, IntoCallArgs, IntoVal,
+    WithDefaults, WrappedCall, Wrapper,
 };
-use super::{eval, lex};
+use super::{eval, lex, val};
 use fnv::FnvHashMap;
 use owning_ref::OwningHandle;
 use smallvec::SmallVec;
@@ -27,13 +29,13 @@ use std::collections::{
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter, Pointer};
 use std::io::{self, stderr, stdout, Write};
-use std::marker::PhantomData;
+use std::marker::{PhantomData, Unsize};
 use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{fmt, fs, mem, str, u32};
 
 #[cfg(feature = "compiler")]
@@ -45,6 +47,11 @@ use super::{
     compile::{Action, Recording},
 };
 
+#[cfg(feature = "serde")]
+use super::serde::{ValDeserializer, ValSerializer};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
 //-------------------------------------------------------------------------------------------------
 // ACTIVE_ENGINE
 //-------------------------------------------------------------------------------------------------
@@ -117,6 +124,18 @@ pub(crate) fn with_heap<R, F: FnOnce(&Heap) -> R>(f: F) -> R {
     })
 }
 
+//we take some steps to prevent a user-defined `trace` callback from interfering with the Heap;
+//see gc.md for more details
+pub(crate) fn call_trace_callback<F: FnOnce()>(f: F) {
+    with_heap(|heap| {
+        let _root_storage = heap.root_storage.borrow();
+        ACTIVE_ENGINE.with(|active_engine_ref_cell| {
+            let _active_engine = active_engine_ref_cell.borrow();
+            panic::catch_unwind(AssertUnwindSafe(f)).ok();
+        });
+    });
+}
+
 #[inline(always)]
 pub(crate) fn with_vm<R, F: FnOnce(&Vm) -> R>(f: F) -> R {
     ACTIVE_ENGINE.with(|ref_cell| {
@@ -139,6 +158,28 @@ pub(crate) fn with_known_ops<R, F: FnOnce(&HashMap<Sym, KnownOp>) -> R>(f: F) ->
     })
 }
 
+//strips the module path from each path segment in a type_name, so that
+//"alloc::vec::Vec<my_crate::Foo>" becomes "Vec<Foo>"
+pub(crate) fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut path_start = 0;
+
+    let mut chars = name.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            short.truncate(path_start);
+        } else {
+            short.push(ch);
+            if !(ch.is_alphanumeric() || ch == '_') {
+                path_start = short.len();
+            }
+        }
+    }
+
+    short
+}
+
 #[doc(hidden)]
 pub fn with_lazy_val<R, FInit, FUse>(key: &str, f_init: FInit, f_use: FUse) -> R
 where
@@ -366,6 +407,8 @@ impl Drop for Engine {
                 engine.lazy_storage.borrow_mut().clear();
                 engine.syms.borrow_mut().clear();
                 engine.rclasses.borrow_mut().clear();
+                engine.rfn_interceptor.borrow_mut().take();
+                engine.rfn_after_hook.borrow_mut().take();
                 engine.vm.clear();
                 engine.heap.clear();
             });
@@ -391,6 +434,7 @@ struct EngineStorage {
 
     syms: RefCell<Vec<SymEntry>>,
     syms_map: RefCell<HashMap<Rc<str>, Sym>>,
+    static_syms: RefCell<FnvHashMap<(usize, usize), Sym>>, //keyed by a &'static str's ptr and len
     gensym_counter: Cell<u32>,
     gensym_seed: RefCell<Option<String>>,
 
@@ -404,8 +448,18 @@ struct EngineStorage {
     rclasses: RefCell<HashMap<TypeId, Rc<RClass>>>,
     rclass_names: RefCell<HashSet<Sym>>,
 
+    //maps (trait object type, RefCell<concrete type>) to a function which takes the concrete
+    //value from an rdata and returns it as a Box<Box<dyn Trait>>
+    trait_impls: RefCell<HashMap<(TypeId, TypeId), TakeTraitImpl>>,
+
+    rfn_interceptor: RefCell<Option<Rc<dyn Fn(Option<Sym>, &[Val]) -> GResult<()>>>>,
+    in_rfn_interceptor: Cell<bool>,
+    in_rfn_after_hook: Cell<bool>,
+    rfn_after_hook: RefCell<Option<Rc<dyn Fn(Option<Sym>, Result<&Val, &GError>)>>>,
+
     in_expander: RefCell<Option<(Option<Sym>, Span, Rc<Env>)>>,
     errors_verbose: Cell<bool>,
+    flo_to_int_coercion: Cell<bool>,
 
     rglobals: RefCell<HashMap<TypeId, Rc<dyn Any>>>,
     rglobals_ordering: RefCell<Vec<TypeId>>,
@@ -420,6 +474,8 @@ struct EngineStorage {
     known_ops: HashMap<Sym, KnownOp>,
 }
 
+type TakeTraitImpl = fn(&RData) -> GResult<Box<dyn Any>>;
+
 struct SymEntry {
     name: Rc<str>,
     kind: SymKind,
@@ -470,6 +526,7 @@ impl Engine {
 
             syms: RefCell::new(syms),
             syms_map: RefCell::new(syms_map),
+            static_syms: RefCell::new(FnvHashMap::default()),
             gensym_counter: Cell::new(0),
             gensym_seed: RefCell::new(None),
 
@@ -482,9 +539,16 @@ impl Engine {
 
             rclasses: RefCell::new(HashMap::new()),
             rclass_names: RefCell::new(HashSet::new()),
+            trait_impls: RefCell::new(HashMap::new()),
+
+            rfn_interceptor: RefCell::new(None),
+            in_rfn_interceptor: Cell::new(false),
+            in_rfn_after_hook: Cell::new(false),
+            rfn_after_hook: RefCell::new(None),
 
             in_expander: RefCell::new(None),
             errors_verbose: Cell::new(true),
+            flo_to_int_coercion: Cell::new(false),
 
             rglobals: RefCell::new(HashMap::new()),
             rglobals_ordering: RefCell::new(Vec::new()),
@@ -574,6 +638,9 @@ impl Sym {
     Returns the name of the symbol.
 
     For gensyms, the name is the same as its printed representation, e.g. `#<gs:tag:8>`.
+
+    The name is shared with the symbol table, so this only increments a reference count; it
+    doesn't copy the string.
     */
     pub fn name(&self) -> Rc<str> {
         with_engine(|engine| Rc::clone(&engine.syms.borrow()[self.0 as usize].name))
@@ -589,8 +656,36 @@ impl Sym {
         self.kind() == SymKind::Gensym
     }
 
-    //only public so that it can be used internally by the backquote!() macro
-    #[doc(hidden)]
+    /**
+    Returns `true` if this symbol's name starts with a colon, like `:key`.
+
+    GameLisp doesn't attach any special meaning to these symbols, but they're a common
+    convention for naming flags and keys. A lone `:` is not considered to be a keyword.
+    Gensyms are never keywords.
+    */
+    pub fn is_keyword(&self) -> bool {
+        !self.is_gensym() && {
+            let name = self.name();
+            name.len() > 1 && name.starts_with(':')
+        }
+    }
+
+    /**
+    Returns `true` if this symbol names one of GameLisp's special forms, like `if` or `fn`.
+
+    Reserved symbols can't be bound as global or local variables, although they can still
+    be bound as macros.
+    */
+    pub fn is_reserved(&self) -> bool {
+        self.kind() == SymKind::StockSpecial
+    }
+
+    /**
+    Returns this symbol's classification.
+
+    The classification is assigned when the symbol is first interned, so this is cheaper
+    than inspecting its name.
+    */
     pub fn kind(&self) -> SymKind {
         with_engine(|engine| engine.syms.borrow()[self.0 as usize].kind)
     }
@@ -699,13 +794,26 @@ impl<'a> ToSym for Str {
     }
 }
 
-#[doc(hidden)]
-#[derive(Clone, Copy, PartialEq)]
+/**
+The return value for the [`Sym::kind`](struct.Sym.html#method.kind) method.
+*/
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SymKind {
+    ///Any symbol which isn't described by one of the other variants.
     Normal,
+
+    ///The name of a special form, like `if` or `fn`. These symbols are
+    ///[reserved](struct.Sym.html#method.is_reserved).
     StockSpecial,
+
+    ///A symbol with a special meaning to the standard library, like `&name`, `ok` or `at`.
     StockKeyword,
+
+    ///The name of a built-in function which the compiler may inline, like `+` or `arr`.
     StockTransform,
+
+    ///A symbol constructed by [`glsp::gensym`](fn.gensym.html).
     Gensym,
 }
 
@@ -752,6 +860,27 @@ impl RFn {
     pub(crate) fn set_name(&self, new_name: Option<Sym>) {
         self.name.set(new_name)
     }
+
+    /**
+    Returns the kind of each of this function's parameters, in order.
+
+    This is captured when the `RFn` is created. It can be used to introspect the function's
+    signature: for example, to generate documentation for a binding.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let rfn = glsp::rfn(&|a: i32, b: Option<i32>, rest: Rest<i32>| ());
+    assert!(rfn.arg_types() == &[ArgType::Normal, ArgType::Option, ArgType::Rest]);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn arg_types(&self) -> &[ArgType] {
+        self.wrapped_fn.arg_types()
+    }
 }
 
 impl CallableOps for Root<RFn> {
@@ -760,6 +889,11 @@ impl CallableOps for Root<RFn> {
         Ok(glsp::call_rfn(self, arg_count)?.root())
     }
 
+    #[inline(always)]
+    fn receive_call_unit(&self, arg_count: usize) -> GResult<()> {
+        glsp::call_rfn(self, arg_count).map(|_| ())
+    }
+
     fn name(&self) -> Option<Sym> {
         self.name.get()
     }
@@ -776,6 +910,11 @@ impl CallableOps for Raw<RFn> {
         Ok(glsp::call_rfn(&self.root(), arg_count)?.root())
     }
 
+    #[inline(always)]
+    fn receive_call_unit(&self, arg_count: usize) -> GResult<()> {
+        glsp::call_rfn(&self.root(), arg_count).map(|_| ())
+    }
+
     fn name(&self) -> Option<Sym> {
         self.name.get()
     }
@@ -786,6 +925,52 @@ impl CallableOps for Raw<RFn> {
     }
 }
 
+/**
+Binds a Rust function to a global variable, deriving the global's name from the function's name.
+
+`bind_rfn!(f)` takes the final segment of the path `f`, replaces each underscore with a
+hyphen, and then calls [`glsp::bind_rfn`](fn.bind_rfn.html). For example, `light_sea_green`
+is bound to `light-sea-green`, and `Graphics::draw_rect` is bound to `draw-rect`.
+
+To override the derived name, pass it as the first argument: `bind_rfn!("name", f)`. This is
+necessary for generic functions with explicit type arguments, and for names which contain
+characters other than hyphens, such as `rect?` or `set-width!`.
+
+Evaluates to a `GResult<Root<RFn>>`.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+fn light_sea_green() -> (u8, u8, u8) {
+    (32, 178, 170)
+}
+
+fn is_rect(val: Val) -> bool {
+    val.is_arr()
+}
+
+bind_rfn!(light_sea_green)?;
+bind_rfn!("rect?", is_rect)?;
+# Ok(()) }).unwrap();
+```
+*/
+
+#[macro_export]
+macro_rules! bind_rfn {
+    ($f:path) => (
+        {
+            let path = stringify!($f);
+            let name = path.rsplit("::").next().unwrap().trim().replace('_', "-");
+            $crate::bind_rfn(&name[..], &$f)
+        }
+    );
+    ($name:expr, $f:path) => (
+        $crate::bind_rfn($name, &$f)
+    );
+}
+
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub struct Filename(NonZeroU32);
 
@@ -982,6 +1167,27 @@ pub trait RGlobal: 'static + Sized {
     }
 }
 
+/**
+A marker trait for trait object types which can be produced from an `RData`.
+
+Implementing `RTrait` for a trait object type, such as `dyn Drawable`, enables
+[`glsp::register_trait_impl`](fn.register_trait_impl.html) for that type, and makes `Box<T>`
+implement [`FromVal`](trait.FromVal.html).
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+trait Drawable {
+    fn draw(&self);
+}
+
+impl RTrait for dyn Drawable { }
+```
+*/
+
+pub trait RTrait: 'static {}
+
 /**
 A reference to [global data](trait.RGlobal.html).
 
@@ -1344,6 +1550,19 @@ By default, `rdata` are opaque; GameLisp scripts can't access their fields or ca
 Optionally, you can use [`RClassBuilder`](struct.RClassBuilder.html) to configure an `rdata`
 so that it behaves more like a GameLisp `obj`, with properties and methods which can be invoked
 by GameLisp scripts.
+
+An `rdata` can't be associated with a GameLisp [`Class`](struct.Class.html). If you'd like to
+extend an `rdata` with methods written in GameLisp, define a class which stores the `rdata` in
+one of its fields, and forwards method calls to it where necessary.
+*/
+
+/*
+we considered adding glsp::rdata_with_class(), which would dispatch method calls on an rdata
+to the methods of a GameLisp Class, passing the rdata as `self`. however, a class's methods are
+compiled against the field layout of its objs: (@field) reads from a slot in the obj's field
+storage, and (@method) looks up a binding in the obj's class, with state-dependent dispatch.
+passing an rdata as `self` would cause almost every method to fail, so in practice the only
+coherent design is composition, as described above.
 */
 
 pub struct RData {
@@ -1355,6 +1574,9 @@ pub struct RData {
     storage: RefCell<Option<Rc<dyn Any>>>,
     pub(crate) rclass: Option<Rc<RClass>>,
 
+    //type-erased operations on `storage`, shared by every RData which stores a T
+    vtable: &'static RDataVtable,
+
     //just like Obj, we need this field so that we can generate a `self` argument when
     //rdata.call() is invoked from rust code
     raw_self: Cell<Option<Raw<RData>>>,
@@ -1377,19 +1599,7 @@ impl Allocate for RData {
 
             if let Some(rclass) = self.rclass.as_ref() {
                 if let Some(trace) = rclass.trace.as_ref() {
-                    //we take some steps to prevent the user-defined `trace` callback from
-                    //interfering with the Heap; see gc.md for more details
-                    with_heap(|heap| {
-                        let _root_storage = heap.root_storage.borrow();
-                        ACTIVE_ENGINE.with(|active_engine_ref_cell| {
-                            let _active_engine = active_engine_ref_cell.borrow();
-
-                            panic::catch_unwind(AssertUnwindSafe(|| {
-                                trace(&*rc_any, &mut gc_visitor);
-                            }))
-                            .ok();
-                        });
-                    });
+                    call_trace_callback(|| trace(&*rc_any, &mut gc_visitor));
                 }
             }
         }
@@ -1445,12 +1655,120 @@ impl<T> DerefMut for RRefMut<T> {
     }
 }
 
+/**
+A shared reference to an [`RData`](struct.RData.html), with its type erased.
+
+Created using [`RData::as_any`](struct.RData.html#method.as_any).
+*/
+pub struct RAnyRef(Box<dyn ErasedRef>);
+
+impl Deref for RAnyRef {
+    type Target = dyn Any;
+
+    fn deref(&self) -> &dyn Any {
+        self.0.get()
+    }
+}
+
+/**
+A mutable reference to an [`RData`](struct.RData.html), with its type erased.
+
+Created using [`RData::as_any_mut`](struct.RData.html#method.as_any_mut).
+*/
+pub struct RAnyRefMut(Box<dyn ErasedRefMut>);
+
+impl Deref for RAnyRefMut {
+    type Target = dyn Any;
+
+    fn deref(&self) -> &dyn Any {
+        self.0.get()
+    }
+}
+
+impl DerefMut for RAnyRefMut {
+    fn deref_mut(&mut self) -> &mut dyn Any {
+        self.0.get_mut()
+    }
+}
+
+//OwningHandle can't borrow an unsized RefCell<dyn Any>, so RAnyRef and RAnyRefMut are
+//implemented by boxing an RRef<T> or RRefMut<T>, and erasing its type using these traits
+trait ErasedRef {
+    fn get(&self) -> &dyn Any;
+}
+
+impl<T: 'static> ErasedRef for RRef<T> {
+    fn get(&self) -> &dyn Any {
+        &**self
+    }
+}
+
+trait ErasedRefMut {
+    fn get(&self) -> &dyn Any;
+    fn get_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> ErasedRefMut for RRefMut<T> {
+    fn get(&self) -> &dyn Any {
+        &**self
+    }
+
+    fn get_mut(&mut self) -> &mut dyn Any {
+        &mut **self
+    }
+}
+
+type UnsizeStorage = fn(Rc<dyn Any>) -> Rc<RefCell<dyn Any>>;
+
+struct RDataVtable {
+    //converts `storage` from an Rc<RefCell<T>> to an Rc<RefCell<dyn Any>>, for is_borrowed()
+    unsize: UnsizeStorage,
+
+    //type-erased borrows of `storage`, for as_any() and as_any_mut(). they return None if
+    //the RefCell<T> is already borrowed
+    borrow_any: fn(Rc<dyn Any>) -> Option<RAnyRef>,
+    borrow_any_mut: fn(Rc<dyn Any>) -> Option<RAnyRefMut>,
+}
+
+struct RDataVtableFor<T>(PhantomData<T>);
+
+impl<T: 'static> RDataVtableFor<T> {
+    const VTABLE: RDataVtable = RDataVtable {
+        unsize: Self::unsize,
+        borrow_any: Self::borrow_any,
+        borrow_any_mut: Self::borrow_any_mut,
+    };
+
+    fn unsize(rc: Rc<dyn Any>) -> Rc<RefCell<dyn Any>> {
+        Rc::downcast::<RefCell<T>>(rc).ok().unwrap()
+    }
+
+    fn borrow_any(rc: Rc<dyn Any>) -> Option<RAnyRef> {
+        let rc = Rc::downcast::<RefCell<T>>(rc).ok().unwrap();
+        if rc.try_borrow().is_ok() {
+            Some(RAnyRef(Box::new(RRef::<T>(OwningHandle::new(rc)))))
+        } else {
+            None
+        }
+    }
+
+    fn borrow_any_mut(rc: Rc<dyn Any>) -> Option<RAnyRefMut> {
+        let rc = Rc::downcast::<RefCell<T>>(rc).ok().unwrap();
+        if rc.try_borrow_mut().is_ok() {
+            Some(RAnyRefMut(Box::new(RRefMut::<T>(OwningHandle::new_mut(rc)))))
+        } else {
+            None
+        }
+    }
+}
+
 impl RData {
     pub(crate) fn new<T: 'static>(rdata: T, rclass: Option<Rc<RClass>>) -> RData {
         RData {
             header: Header::new(),
             storage: RefCell::new(Some(Rc::new(RefCell::new(rdata)))),
             rclass,
+            vtable: &RDataVtableFor::<T>::VTABLE,
             raw_self: Cell::new(None),
         }
     }
@@ -1468,6 +1786,11 @@ impl RData {
         self.rclass.clone()
     }
 
+    //the TypeId of the stored RefCell<T>, or None if the value has been freed
+    pub(crate) fn storage_type_id(&self) -> Option<TypeId> {
+        self.storage.borrow().as_ref().map(|rc_any| (**rc_any).type_id())
+    }
+
     /**
     Returns `true` if this `RData` is currently storing a value of type `T`.
 
@@ -1480,6 +1803,33 @@ impl RData {
         }
     }
 
+    /**
+    Returns `true` if the value being stored by this `RData` is currently borrowed, either
+    mutably or immutably.
+
+    When this method returns `false`, [`try_borrow_mut`](#method.try_borrow_mut) will not fail
+    due to a borrow conflict. If the value has been freed, returns `false`.
+    */
+    pub fn is_borrowed(&self) -> bool {
+        match *self.storage.borrow() {
+            Some(ref rc) => (self.vtable.unsize)(Rc::clone(rc)).try_borrow_mut().is_err(),
+            None => false,
+        }
+    }
+
+    /**
+    Returns `true` if the value being stored by this `RData` is currently mutably borrowed.
+
+    When this method returns `false`, [`try_borrow`](#method.try_borrow) will not fail due to
+    a borrow conflict. If the value has been freed, returns `false`.
+    */
+    pub fn is_borrowed_mut(&self) -> bool {
+        match *self.storage.borrow() {
+            Some(ref rc) => (self.vtable.unsize)(Rc::clone(rc)).try_borrow().is_err(),
+            None => false,
+        }
+    }
+
     /**
     Returns a shared reference to the value being stored by this `RData`.
 
@@ -1572,13 +1922,51 @@ impl RData {
         }
     }
 
+    /**
+    Returns a type-erased shared reference to the value being stored by this `RData`.
+
+    This enables the full [`Any`](https://doc.rust-lang.org/std/any/trait.Any.html) API,
+    including `downcast_ref` and `type_id`, for code which manages `RData` generically.
+
+    Returns an `Err` if the value has been freed, or if it's currently mutably borrowed.
+    */
+    pub fn as_any(&self) -> GResult<RAnyRef> {
+        let rc = match *self.storage.borrow() {
+            Some(ref rc) => Rc::clone(rc),
+            None => bail!("as_any failed: attempted to access a freed RData"),
+        };
+
+        match (self.vtable.borrow_any)(rc) {
+            Some(any_ref) => Ok(any_ref),
+            None => bail!("as_any failed: value is mutably borrowed"),
+        }
+    }
+
+    /**
+    Returns a type-erased mutable reference to the value being stored by this `RData`.
+
+    Returns an `Err` if the value has been freed, or if it's currently borrowed.
+    */
+    pub fn as_any_mut(&self) -> GResult<RAnyRefMut> {
+        let rc = match *self.storage.borrow() {
+            Some(ref rc) => Rc::clone(rc),
+            None => bail!("as_any_mut failed: attempted to access a freed RData"),
+        };
+
+        match (self.vtable.borrow_any_mut)(rc) {
+            Some(any_ref) => Ok(any_ref),
+            None => bail!("as_any_mut failed: value is currently borrowed"),
+        }
+    }
+
     /**
     Takes the value stored in this `RData` and returns it.
 
     Any future attempts to access the value will gracefully fail.
 
     Returns an `Err` if the `RData` is not storing a value of type `T`; if its value has already
-    been freed; or if it's currently borrowed.
+    been taken or freed; or if it's currently borrowed. When an `Err` is returned, the stored
+    value is left untouched.
     */
     pub fn take<T: 'static>(&self) -> GResult<T> {
         //freeing the stored data changes our owned_memory_usage(), so we need to write-barrier it.
@@ -1587,15 +1975,15 @@ impl RData {
         match self.storage.try_borrow_mut() {
             Ok(mut borrow_mut) => match *borrow_mut {
                 Some(ref rc) if Rc::strong_count(rc) == 1 => {
+                    //the type check must come first, so that a mismatch leaves the value intact
+                    if !rc.is::<RefCell<T>>() {
+                        bail!("type mismatch when calling take::<{}>()", type_name::<T>())
+                    }
+
                     let rc_any = (*borrow_mut).take().unwrap();
                     drop(borrow_mut);
 
-                    let rc = match Rc::downcast::<RefCell<T>>(rc_any) {
-                        Ok(rc) => rc,
-                        Err(_) => {
-                            bail!("type mismatch when calling take::<{}>()", type_name::<T>())
-                        }
-                    };
+                    let rc = Rc::downcast::<RefCell<T>>(rc_any).ok().unwrap();
 
                     let cur_usage = self.owned_memory_usage();
                     with_heap(|heap| heap.memory_usage_barrier(self, prev_usage, cur_usage));
@@ -1824,6 +2212,7 @@ impl RData {
                 let mut stacks = vm.stacks.borrow_mut();
                 let starting_len = stacks.regs.len();
 
+                stacks.regs.reserve(args.arg_count() + 1);
                 stacks.regs.push(Slot::RData(self.raw_self()));
                 args.into_call_args(&mut stacks.regs)?;
 
@@ -2030,6 +2419,43 @@ impl<T: 'static> RRoot<T> {
     pub fn take(&self) -> GResult<T> {
         self.0.take()
     }
+
+    /**
+    Returns `true` if the `RData` is currently storing a value of type `U`.
+
+    Each `RData` stores a single concrete type, so this is only `true` when `U` is `T`, or
+    when `T` and `U` are the same type under different names. It returns `false` if the
+    `RData`'s value has been taken or freed.
+    */
+    pub fn is<U: 'static>(&self) -> bool {
+        self.0.is::<U>()
+    }
+
+    /**
+    Converts this `RRoot<T>` into an `RRoot<U>` which refers to the same `RData`.
+
+    Returns `None` if the `RData` isn't storing a value of type `U`. There's no notion of
+    subtyping between Rust types, so this only succeeds on an exact type match; it's mostly
+    useful when an `RRoot` was obtained from generic code, or when its value may have been
+    taken or freed.
+    */
+    pub fn downcast<U: 'static>(&self) -> Option<RRoot<U>> {
+        if self.0.is::<U>() {
+            Some(RRoot(self.0.clone(), PhantomData))
+        } else {
+            None
+        }
+    }
+
+    /**
+    Returns the name of the type `T`, for use in diagnostics.
+
+    This is the [`type_name`](https://doc.rust-lang.org/std/any/fn.type_name.html) of `T`,
+    so its exact format is unspecified.
+    */
+    pub fn type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
 }
 
 /**
@@ -2114,80 +2540,192 @@ pub mod glsp {
         glsp::sym_impl(name, SymKind::Normal)
     }
 
-    fn sym_impl(name: &str, kind: SymKind) -> GResult<Sym> {
-        with_engine(|engine| {
-            let mut syms_map = engine.syms_map.borrow_mut();
-            if let Some(sym) = syms_map.get(name) {
-                Ok(*sym)
-            } else {
-                let name = Rc::<str>::from(name);
-
-                let mut syms = engine.syms.borrow_mut();
-                syms.push(SymEntry {
-                    name: name.clone(),
-                    kind,
-                    bound_global: None,
-                    bound_macro: None,
-                });
-
-                //we panic rather than returning an Err here, becuase we consider running out of
-                //Syms to be an unrecoverable error, similar to out-of-memory in a Rust program
-                assert!(
-                    syms.len() - 1 <= MAX_SYM as usize,
-                    "program requires more than {} unique symbols",
-                    MAX_SYM + 1
-                );
+    /**
+    Converts a `&'static str` into a symbol, caching the result.
 
-                let sym = Sym((syms.len() - 1) as u32, PhantomData);
-                syms_map.insert(name, sym);
+    The cache is keyed by the string's address and length, so repeated calls with the same
+    string literal only perform a cheap pointer lookup, rather than hashing the string's
+    contents. This makes it suitable for symbols which are used on hot code paths, like the
+    names of methods which are called every frame.
 
-                Ok(sym)
-            }
-        })
-    }
+    Each `Runtime` has its own cache, so the resulting `Sym` is always valid for the active
+    `Runtime`.
 
-    /** Equivalent to [`(valid-sym-str? st)`](https://gamelisp.rs/std/valid-sym-str-p). */
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let update = glsp::intern_static("update")?;
+    assert!(update == glsp::sym("update")?);
+    assert!(update == glsp::intern_static("update")?);
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn intern_static(name: &'static str) -> GResult<Sym> {
+        let key = (name.as_ptr() as usize, name.len());
 
-    pub fn is_valid_sym(st: &str) -> bool {
-        //one or more of the valid sym chars, optionally with a '#' suffix
-        let mut rev_iter = st.chars().rev();
-        match rev_iter.next() {
-            None => false,
-            Some('#') => st.len() > 1 && rev_iter.all(lex::is_valid_sym_char),
-            Some(last_char) => {
-                lex::is_valid_sym_char(last_char) && rev_iter.all(lex::is_valid_sym_char)
-            }
+        let cached = with_engine(|engine| engine.static_syms.borrow().get(&key).copied());
+        if let Some(sym) = cached {
+            return Ok(sym);
         }
-    }
 
-    /** Equivalent to [`(valid-sym-char? ch)`](https://gamelisp.rs/std/valid-sym-char-p). */
+        let sym = glsp::sym(name)?;
+        with_engine(|engine| engine.static_syms.borrow_mut().insert(key, sym));
 
-    #[inline]
-    pub fn is_valid_sym_char(ch: char) -> bool {
-        lex::is_valid_sym_char(ch)
+        Ok(sym)
     }
 
     /**
-    Equivalent to [`(representable-sym-str? st)`](https://gamelisp.rs/std/representable-sym-str-p).
-    */
+    Converts a sequence of strings into symbols.
 
-    pub fn is_representable_sym(st: &str) -> bool {
-        if !is_valid_sym(st) {
-            false
-        } else {
-            sym(st).unwrap().is_representable()
-        }
-    }
+    Equivalent to calling [`glsp::sym`](fn.sym.html) for each string, but more efficient when
+    interning a large number of symbols at once.
 
-    /** Equivalent to [`(gensym)`](https://gamelisp.rs/std/gensym). */
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let syms = glsp::syms(vec!["north", "east", "south", "west"])?;
+    assert!(syms[2] == glsp::sym("south")?);
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn syms<'a, I: IntoIterator<Item = &'a str>>(names: I) -> GResult<Vec<Sym>> {
+        with_engine(|engine| {
+            let mut syms_map = engine.syms_map.borrow_mut();
+            let mut syms = engine.syms.borrow_mut();
 
-    pub fn gensym() -> Sym {
-        glsp::gensym_impl(None)
+            names
+                .into_iter()
+                .map(|name| {
+                    ensure!(glsp::is_valid_sym(name), "invalid sym '{}'", name);
+                    Ok(intern_sym(&mut syms_map, &mut syms, name, SymKind::Normal))
+                })
+                .collect()
+        })
     }
 
-    /** Equivalent to [`(gensym tag)`](https://gamelisp.rs/std/gensym). */
+    /**
+    Returns the symbol with the given name, if it has already been interned.
 
-    pub fn gensym_with_tag(tag: &str) -> GResult<Sym> {
+    Unlike [`glsp::sym`](fn.sym.html), this never allocates a new symbol. Returns `None` if
+    `name` is not a valid symbol.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    assert!(glsp::sym_if_interned("never-interned-before").is_none());
+
+    let sym = glsp::sym("never-interned-before")?;
+    assert!(glsp::sym_if_interned("never-interned-before") == Some(sym));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn sym_if_interned(name: &str) -> Option<Sym> {
+        if !glsp::is_valid_sym(name) {
+            return None;
+        }
+
+        with_engine(|engine| engine.syms_map.borrow().get(name).copied())
+    }
+
+    fn sym_impl(name: &str, kind: SymKind) -> GResult<Sym> {
+        with_engine(|engine| {
+            let mut syms_map = engine.syms_map.borrow_mut();
+            let mut syms = engine.syms.borrow_mut();
+            Ok(intern_sym(&mut syms_map, &mut syms, name, kind))
+        })
+    }
+
+    fn intern_sym(
+        syms_map: &mut HashMap<Rc<str>, Sym>,
+        syms: &mut Vec<SymEntry>,
+        name: &str,
+        kind: SymKind,
+    ) -> Sym {
+        if let Some(sym) = syms_map.get(name) {
+            *sym
+        } else {
+            let name = Rc::<str>::from(name);
+
+            syms.push(SymEntry {
+                name: name.clone(),
+                kind,
+                bound_global: None,
+                bound_macro: None,
+            });
+
+            //we panic rather than returning an Err here, becuase we consider running out of
+            //Syms to be an unrecoverable error, similar to out-of-memory in a Rust program
+            assert!(
+                syms.len() - 1 <= MAX_SYM as usize,
+                "program requires more than {} unique symbols",
+                MAX_SYM + 1
+            );
+
+            let sym = Sym((syms.len() - 1) as u32, PhantomData);
+            syms_map.insert(name, sym);
+
+            sym
+        }
+    }
+
+    //if `name` is the name of a stock symbol, returns that name with a 'static lifetime
+    pub(crate) fn stock_sym_name(name: &str) -> Option<&'static str> {
+        with_engine(|engine| match engine.syms_map.borrow().get(name) {
+            Some(sym) if sym.0 < StockSym::STOCK_SYM_COUNT as u32 => {
+                Some(STOCK_SYMS[sym.0 as usize].0)
+            }
+            _ => None,
+        })
+    }
+
+    /** Equivalent to [`(valid-sym-str? st)`](https://gamelisp.rs/std/valid-sym-str-p). */
+
+    pub fn is_valid_sym(st: &str) -> bool {
+        //one or more of the valid sym chars, optionally with a '#' suffix
+        let mut rev_iter = st.chars().rev();
+        match rev_iter.next() {
+            None => false,
+            Some('#') => st.len() > 1 && rev_iter.all(lex::is_valid_sym_char),
+            Some(last_char) => {
+                lex::is_valid_sym_char(last_char) && rev_iter.all(lex::is_valid_sym_char)
+            }
+        }
+    }
+
+    /** Equivalent to [`(valid-sym-char? ch)`](https://gamelisp.rs/std/valid-sym-char-p). */
+
+    #[inline]
+    pub fn is_valid_sym_char(ch: char) -> bool {
+        lex::is_valid_sym_char(ch)
+    }
+
+    /**
+    Equivalent to [`(representable-sym-str? st)`](https://gamelisp.rs/std/representable-sym-str-p).
+    */
+
+    pub fn is_representable_sym(st: &str) -> bool {
+        if !is_valid_sym(st) {
+            false
+        } else {
+            sym(st).unwrap().is_representable()
+        }
+    }
+
+    /** Equivalent to [`(gensym)`](https://gamelisp.rs/std/gensym). */
+
+    pub fn gensym() -> Sym {
+        glsp::gensym_impl(None)
+    }
+
+    /** Equivalent to [`(gensym tag)`](https://gamelisp.rs/std/gensym). */
+
+    pub fn gensym_with_tag(tag: &str) -> GResult<Sym> {
         ensure!(
             glsp::is_valid_sym(tag) && !tag.ends_with('#'),
             "invalid gensym tag '{}': tags should be a valid sym without a trailing '#'",
@@ -2701,21 +3239,33 @@ pub mod glsp {
     [`glsp::add_rglobal`](fn.add_rglobal.html).
 
     Returns `Err` if no global is registered for the type `T`, or if a global exists but
-    it's currently borrowed.
+    it's currently borrowed. In the latter case, the global remains registered.
+
+    This can be used to run Rust-side teardown for a global before the `Runtime` is dropped.
+    Once a global has been taken, any attempt to borrow it will fail, until another global of
+    the same type is registered.
     */
     pub fn take_rglobal<T: RGlobal>() -> GResult<T> {
         with_engine(|engine| {
-            let rc = match engine.rglobals.borrow_mut().remove(&TypeId::of::<T>()) {
-                Some(rc) => rc.downcast::<RefCell<T>>().unwrap(),
-                None => bail!("attempted to take nonexistent global {}", type_name::<T>()),
-            };
+            let mut rglobals = engine.rglobals.borrow_mut();
 
-            let rglobal = match Rc::try_unwrap(rc) {
-                Ok(ref_cell) => ref_cell.into_inner(),
-                Err(_) => bail!(
+            //check for outstanding borrows before removing the global, so that a failed
+            //take_rglobal() doesn't cause the global to be silently dropped
+            match rglobals.get(&TypeId::of::<T>()) {
+                Some(rc) => ensure!(
+                    Rc::strong_count(rc) == 1,
                     "called take_rglobal for {}, which is currently borrowed",
                     type_name::<T>()
                 ),
+                None => bail!("attempted to take nonexistent global {}", type_name::<T>()),
+            }
+
+            let rc = rglobals.remove(&TypeId::of::<T>()).unwrap();
+            drop(rglobals);
+
+            let rglobal = match Rc::try_unwrap(rc.downcast::<RefCell<T>>().unwrap()) {
+                Ok(ref_cell) => ref_cell.into_inner(),
+                Err(_) => unreachable!(),
             };
 
             engine
@@ -2727,6 +3277,72 @@ pub mod glsp {
         })
     }
 
+    /**
+    Borrows global data for the duration of a closure.
+
+    Returns `Err`, rather than panicking, if no global is registered for the type `T`, or if
+    the global is currently mutably borrowed.
+    */
+    pub fn with_rglobal<T, R, F>(f: F) -> GResult<R>
+    where
+        T: RGlobal,
+        F: FnOnce(&T) -> R,
+    {
+        let rglobal = glsp::try_rglobal::<T>()?;
+        Ok(f(&rglobal))
+    }
+
+    /**
+    Mutably borrows global data for the duration of a closure.
+
+    Returns `Err`, rather than panicking, if no global is registered for the type `T`, or if
+    the global is currently borrowed. This can be used to detect re-entrant calls: for example,
+    when an `rfn` which receives a `&mut T` argument calls back into GameLisp, and GameLisp
+    calls another function which requires the same global.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    struct Score(u32);
+    impl RGlobal for Score { }
+
+    # Engine::new().run(|| {
+    glsp::add_rglobal(Score(0));
+
+    glsp::with_rglobal_mut(|score: &mut Score| {
+        score.0 += 10;
+        assert!(glsp::is_rglobal_borrowed::<Score>());
+        assert!(glsp::with_rglobal_mut(|_: &mut Score| ()).is_err());
+    })?;
+
+    assert_eq!(glsp::with_rglobal(|score: &Score| score.0)?, 10);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn with_rglobal_mut<T, R, F>(f: F) -> GResult<R>
+    where
+        T: RGlobal,
+        F: FnOnce(&mut T) -> R,
+    {
+        let mut rglobal = glsp::try_rglobal_mut::<T>()?;
+        Ok(f(&mut rglobal))
+    }
+
+    /**
+    Returns `true` if global data of type `T` is currently borrowed, either mutably or
+    immutably.
+
+    Returns `false` if no global is registered for the type `T`.
+    */
+    pub fn is_rglobal_borrowed<T: RGlobal>() -> bool {
+        with_engine(|engine| match engine.rglobals.borrow().get(&TypeId::of::<T>()) {
+            Some(rc) => rc.downcast_ref::<RefCell<T>>().unwrap().try_borrow_mut().is_err(),
+            None => false,
+        })
+    }
+
     pub(crate) fn rglobal<T: RGlobal>() -> RGlobalRef<T> {
         match glsp::try_rglobal::<T>() {
             Ok(rglobal_ref) => rglobal_ref,
@@ -2818,6 +3434,106 @@ pub mod glsp {
         })
     }
 
+    /**
+    Registers the Rust type `C` as an implementor of the trait object type `T`.
+
+    `T` must implement the marker trait [`RTrait`](trait.RTrait.html). After registering, an
+    `RData` which stores a `C` can be converted into a `Box<T>` using
+    [`FromVal`](trait.FromVal.html). This is the dynamic-dispatch counterpart to
+    [`RData::take`](struct.RData.html#method.take): the conversion takes the value out of the
+    `RData`, so any future attempts to access the `RData` will fail.
+
+    Converting an `RData` whose type hasn't been registered for `T` will return an `Err`.
+    [`Val::is::<Box<T>>()`](enum.Val.html#method.is) only consults the registry, so it never
+    takes the `RData`'s value.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    trait Drawable {
+        fn draw(&self) -> String;
+    }
+
+    impl RTrait for dyn Drawable { }
+
+    struct Sprite;
+
+    impl Drawable for Sprite {
+        fn draw(&self) -> String {
+            "sprite".to_string()
+        }
+    }
+
+    glsp::register_trait_impl::<dyn Drawable, Sprite>();
+
+    let val = Val::RData(glsp::rdata(Sprite));
+    assert!(val.is::<Box<dyn Drawable>>());
+
+    let drawable = Box::<dyn Drawable>::from_val(&val)?;
+    assert_eq!(drawable.draw(), "sprite");
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn register_trait_impl<T, C>()
+    where
+        T: ?Sized + RTrait,
+        C: Unsize<T> + 'static,
+    {
+        fn take<T: ?Sized + 'static, C: Unsize<T> + 'static>(
+            rdata: &RData,
+        ) -> GResult<Box<dyn Any>> {
+            let concrete: Box<C> = Box::new(rdata.take::<C>()?);
+            let boxed: Box<T> = concrete;
+            Ok(Box::new(boxed))
+        }
+
+        with_engine(|engine| {
+            let key = (TypeId::of::<T>(), TypeId::of::<RefCell<C>>());
+            engine.trait_impls.borrow_mut().insert(key, take::<T, C>);
+        })
+    }
+
+    fn trait_impl<T: ?Sized + RTrait>(type_id: TypeId) -> Option<TakeTraitImpl> {
+        with_engine(|engine| {
+            let trait_impls = engine.trait_impls.borrow();
+            trait_impls.get(&(TypeId::of::<T>(), type_id)).copied()
+        })
+    }
+
+    //tests whether take_trait_impl would find a registered conversion, without taking anything
+    pub(crate) fn has_trait_impl<T: ?Sized + RTrait>(rdata: &RData) -> bool {
+        match rdata.storage_type_id() {
+            Some(type_id) => trait_impl::<T>(type_id).is_some(),
+            None => false,
+        }
+    }
+
+    pub(crate) fn take_trait_impl<T: ?Sized + RTrait>(rdata: &RData) -> GResult<Box<T>> {
+        let take = match rdata.storage_type_id() {
+            Some(type_id) => trait_impl::<T>(type_id),
+            None => bail!("attempted to convert a freed RData into a trait object"),
+        };
+
+        match take {
+            Some(take) => Ok(*take(rdata)?.downcast::<Box<T>>().ok().unwrap()),
+            None => {
+                let rdata_name = match rdata.rclass() {
+                    Some(rclass) => format!("type {}", rclass.name),
+                    None => "an unregistered type".to_string(),
+                };
+
+                bail!(
+                    "rdata of {} does not implement {}",
+                    rdata_name,
+                    short_type_name(type_name::<T>())
+                )
+            }
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // rfns
     //---------------------------------------------------------------------------------------------
@@ -2829,6 +3545,9 @@ pub mod glsp {
     value. In particular:
 
     - The return value may be any type which implements [`IntoVal`](trait.IntoVal.html).
+      Fallible functions should usually return [`GResult<R>`](type.GResult.html): when they
+      return an `Err`, the `GError` is propagated to the caller unchanged. This includes the
+      [`macro_no_op!`](macro.macro_no_op.html) signal.
 
     - Parameters may be any type which implements [`FromVal`](trait.FromVal.html).
 
@@ -2851,6 +3570,19 @@ pub mod glsp {
               `Runtime`'s global storage, for the duration of the function call, by calling
               `T::borrow()` or `T::borrow_mut()`.
 
+            - As another exception, `&mut String` and `&mut Vec<T>` also accept a mutable
+              string or array. The collection is copied into a temporary `String` or `Vec<T>`,
+              and once the function returns, the original collection's contents are overwritten
+              with the temporary's contents. This copies the entire collection twice on every
+              call, so for large collections, prefer accepting a `&Str` or `&Arr` instead.
+              Passing in a frozen string or array is an error.
+
+            - Similarly, `&mut [T]` accepts a mutable array. Its elements are converted into
+              a temporary buffer, and once the function returns, each element is converted
+              back and written into the original array. This has the same two-way copying cost
+              as `&mut Vec<T>`. It's an error to pass in anything other than a mutable array,
+              or to resize the array while the function is running.
+
         - References to unsized types will be constructed on the stack and then borrowed.
           `&[T]` is converted from an array. `&str`, `&OsStr`, `&CStr` and `&Path` are
           converted from strings.
@@ -2919,7 +3651,99 @@ pub mod glsp {
     }
 
     /**
-    Binds a Rust function to a global variable.
+    Creates a GameLisp value which represents a Rust function, with default argument values.
+
+    `defaults` should be a tuple of closures, each of which returns a value which implements
+    [`IntoVal`](trait.IntoVal.html). The closures provide default values for the function's
+    trailing parameters: when one of those arguments is missing, or `#n`, the corresponding
+    closure is invoked to produce its value. The closures are invoked separately for each call,
+    so mutable defaults like arrays won't be shared between calls.
+
+    Returns an `Err` if there are more closures than parameters, or if the function has a
+    [`Rest<T>`](struct.Rest.html) parameter.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    fn spawn_enemy(kind: Sym, health: i32, speed: f32) {
+        // ...
+    }
+
+    let rfn = glsp::rfn_with_defaults(&spawn_enemy, (|| 100, || 1.5_f32))?;
+    glsp::bind_global("spawn-enemy", rfn)?;
+
+    glsp::load_str("
+        (spawn-enemy 'goblin)
+        (spawn-enemy 'troll 500)
+        (spawn-enemy 'bat #n 4.0)
+    ")?;
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn rfn_with_defaults<ArgsWithTag, Ret, F, D>(f: F, defaults: D) -> GResult<Root<RFn>>
+    where
+        Wrapper<ArgsWithTag, Ret, F>: WrappedCall + 'static,
+        D: ArgDefaults,
+    {
+        let wrapped_fn = WithDefaults::new(wrap(f), defaults.into_arg_defaults())?;
+        Ok(glsp::rfn_from_wrapped_call(None, Box::new(wrapped_fn)))
+    }
+
+    /**
+    Creates a GameLisp value which represents a stateful Rust function.
+
+    This is equivalent to [`glsp::rfn`](fn.rfn.html), except that `f` may be an `FnMut`
+    closure which mutates its captured state, such as an accumulator or a cache. The closure
+    is stored in a `RefCell`.
+
+    If the function is called reentrantly (for example, if it calls a GameLisp function which
+    calls it again), the inner call will fail with the error "rfn called reentrantly", rather
+    than panicking.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let mut total = 0;
+    let rfn = glsp::rfn_mut(Box::new(move |i: i32| {
+        total += i;
+        total
+    }));
+
+    let _: i32 = glsp::call(&rfn, (10,))?;
+    let total: i32 = glsp::call(&rfn, (5,))?;
+    assert_eq!(total, 15);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+
+    pub fn rfn_mut<ArgsWithTag, Ret, F>(f: F) -> Root<RFn>
+    where
+        Wrapper<ArgsWithTag, Ret, FnMutCell<F>>: WrappedCall + 'static,
+    {
+        glsp::rfn(FnMutCell::new(f))
+    }
+
+    //constructs an rfn from a custom WrappedCall, rather than a wrapped Rust function
+    pub(crate) fn rfn_from_wrapped_call(
+        name: Option<Sym>,
+        wrapped_fn: Box<dyn WrappedCall>,
+    ) -> Root<RFn> {
+        glsp::alloc(RFn {
+            header: Header::new(),
+
+            name: Cell::new(name),
+            wrapped_fn,
+        })
+    }
+
+    /**
+    Binds a Rust function to a global variable, returning the newly-created `RFn`.
 
     GameLisp will perform automatic conversions for the function's parameters and return
     value. See [`glsp::rfn`](fn.rfn.html) and [`glsp::named_rfn`](fn.named_rfn.html)
@@ -2938,13 +3762,16 @@ pub mod glsp {
     # 
     let sym = name.to_sym()?;
     let rfn = glsp::named_rfn(sym, &f);
-    glsp::bind_global(sym, rfn)
-    # 
+    glsp::bind_global(sym, &rfn)?;
+    # Ok(())
     # }).unwrap();
     ```
+
+    ...followed by returning `Ok(rfn)`. The returned `Root<RFn>` can be called directly from
+    Rust using [`glsp::call`](fn.call.html), without needing to look up the global variable.
     */
 
-    pub fn bind_rfn<S: ToSym, ArgsWithTag, Ret, F>(name: S, f: F) -> GResult<()>
+    pub fn bind_rfn<S: ToSym, ArgsWithTag, Ret, F>(name: S, f: F) -> GResult<Root<RFn>>
     where
         Wrapper<ArgsWithTag, Ret, F>: WrappedCall + 'static,
     {
@@ -2952,7 +3779,22 @@ pub mod glsp {
 
         let rfn = glsp::named_rfn(sym, f);
 
-        glsp::bind_global(sym, rfn)
+        glsp::bind_global(sym, &rfn)?;
+        Ok(rfn)
+    }
+
+    /**
+    Binds a stateful Rust function to a global variable, returning the newly-created `RFn`.
+
+    This is equivalent to [`glsp::bind_rfn`](fn.bind_rfn.html), except that `f` may be an
+    `FnMut` closure. See [`glsp::rfn_mut`](fn.rfn_mut.html) for the details.
+    */
+
+    pub fn bind_rfn_mut<S: ToSym, ArgsWithTag, Ret, F>(name: S, f: F) -> GResult<Root<RFn>>
+    where
+        Wrapper<ArgsWithTag, Ret, FnMutCell<F>>: WrappedCall + 'static,
+    {
+        glsp::bind_rfn(name, FnMutCell::new(f))
     }
 
     /**
@@ -2961,6 +3803,29 @@ pub mod glsp {
     GameLisp will perform automatic conversions for the function's parameters and return
     value. See [`glsp::rfn`](fn.rfn.html) for the details.
 
+    The function receives the macro's unexpanded argument forms, and returns the form which
+    should replace the macro invocation. It may decline to expand the form by invoking
+    [`macro_no_op!`](macro.macro_no_op.html), in which case its return type must be
+    `GResult<T>`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    //(square x) expands to (* x x), unless x is a literal int
+    fn square(x: Val) -> GResult<Val> {
+        match x {
+            Val::Int(i) => Ok(Val::Int(i * i)),
+            Val::Sym(_) => Ok(Val::Arr(arr![glsp::sym("*")?, x.clone(), x])),
+            _ => macro_no_op!(),
+        }
+    }
+
+    glsp::bind_rfn_macro("square", &square)?;
+    # Ok(()) }).unwrap();
+    ```
+
     `glsp::bind_rfn_macro(name, &f)` is equivalent to:
 
     ```
@@ -2973,12 +3838,15 @@ pub mod glsp {
     # Engine::new().run(|| {
     let sym = name.to_sym()?;
     let rfn = glsp::named_rfn(sym, &f);
-    glsp::bind_macro(sym, Expander::RFn(rfn))
+    glsp::bind_macro(sym, Expander::RFn(rfn.clone()))?;
+    # Ok(())
     # }).unwrap();
     ```
+
+    ...followed by returning `Ok(rfn)`, like [`glsp::bind_rfn`](fn.bind_rfn.html).
     */
 
-    pub fn bind_rfn_macro<S: ToSym, ArgsWithTag, Ret, F>(name: S, f: F) -> GResult<()>
+    pub fn bind_rfn_macro<S: ToSym, ArgsWithTag, Ret, F>(name: S, f: F) -> GResult<Root<RFn>>
     where
         Wrapper<ArgsWithTag, Ret, F>: WrappedCall + 'static,
     {
@@ -2986,7 +3854,8 @@ pub mod glsp {
 
         let rfn = glsp::named_rfn(sym, f);
 
-        glsp::bind_macro(sym, Expander::RFn(rfn))
+        glsp::bind_macro(sym, Expander::RFn(rfn.clone()))?;
+        Ok(rfn)
     }
 
     pub(crate) fn call_rfn(rfn: &RFn, arg_count: usize) -> GResult<Slot> {
@@ -2997,52 +3866,224 @@ pub mod glsp {
             rfn. we only pop the regs after the call returns, so that they remain rooted.
             */
 
-            let stacks = engine.vm.stacks.borrow();
-            let base_reg = stacks.regs.len() - arg_count;
+            let base_reg = engine.vm.stacks.borrow().regs.len() - arg_count;
+            glsp::call_rfn_impl(engine, rfn, base_reg)
+        })
+    }
 
+    fn call_rfn_impl(engine: &EngineStorage, rfn: &RFn, base_reg: usize) -> GResult<Slot> {
+        //the outer Result is Err when the interceptor vetoes the call
+        let result = panic::catch_unwind(AssertUnwindSafe(|| -> Result<GResult<Slot>, GError> {
+            //the interceptor is cloned out of its RefCell, so that it can call rfns itself.
+            //those nested calls are not intercepted.
+            let interceptor = match engine.in_rfn_interceptor.get() {
+                true => None,
+                false => engine.rfn_interceptor.borrow().clone(),
+            };
+
+            if let Some(interceptor) = interceptor {
+                let args: SmallVec<[Val; 8]> = engine.vm.stacks.borrow().regs[base_reg..]
+                    .iter()
+                    .map(Slot::root)
+                    .collect();
+
+                engine.in_rfn_interceptor.set(true);
+                let _guard = Guard::new(|| engine.in_rfn_interceptor.set(false));
+
+                interceptor(rfn.name.get(), &args)?;
+            }
+
+            let stacks = engine.vm.stacks.borrow();
             let regs = Ref::map(stacks, |stacks| &stacks.regs[base_reg..]);
+            Ok(rfn.wrapped_fn.wrapped_call(regs))
+        }));
 
-            let result =
-                panic::catch_unwind(AssertUnwindSafe(|| rfn.wrapped_fn.wrapped_call(regs)));
+        //we previously used a Guard for this cleanup, but in practice the
+        //above code should never panic
+        let mut stacks = engine.vm.stacks.borrow_mut();
+        stacks.regs.truncate(base_reg);
+        drop(stacks);
 
-            //we previously used a Guard for this cleanup, but in practice the
-            //above code should never panic
-            let mut stacks = engine.vm.stacks.borrow_mut();
-            stacks.regs.truncate(base_reg);
-            drop(stacks);
+        /*
+        for the time being, we don't go through the rigmarole of trying to set a custom panic
+        hook. it's a global resource, and managing that would be annoying. instead, we allow
+        the normal panic hook to print its usual message, and we convert the caught panic
+        into a generic message without any details.
+        */
 
-            /*
-            for the time being, we don't go through the rigmarole of trying to set a custom panic
-            hook. it's a global resource, and managing that would be annoying. instead, we allow
-            the normal panic hook to print its usual message, and we convert the caught panic
-            into a generic message without any details.
-            */
+        let result = match result {
+            Ok(Err(veto)) => return Err(veto),
+            Ok(Ok(Ok(slot))) => Ok(slot),
+            Ok(Ok(Err(err))) => Err(err.with_arg_rfn(rfn.name.get())),
+            Err(payload) => Err(glsp::rfn_panic_error(rfn, "", payload)),
+        };
 
-            match result {
-                Ok(glsp_result) => glsp_result,
-                Err(payload) => {
-                    #[cold]
-                    fn handle_error(rfn: &RFn, payload: Box<dyn Any + Send>) -> GResult<Slot> {
-                        let rfn_description = match rfn.name.get() {
-                            Some(sym) => format!("rfn ({})", sym),
-                            None => format!("anonymous rfn"),
-                        };
-
-                        if let Some(msg) = payload.downcast_ref::<&str>() {
-                            bail!("{} panicked, '{}'", rfn_description, msg)
-                        } else if let Some(msg) = payload.downcast_ref::<String>() {
-                            bail!("{} panicked, '{}'", rfn_description, msg)
-                        } else {
-                            bail!("{} panicked", rfn_description)
-                        }
-                    }
+        //like the interceptor, the after-hook is cloned out of its RefCell, and any rfns which
+        //it calls don't invoke it again
+        let after_hook = match engine.in_rfn_after_hook.get() {
+            true => None,
+            false => engine.rfn_after_hook.borrow().clone(),
+        };
+
+        if let Some(after_hook) = after_hook {
+            let hook_result = panic::catch_unwind(AssertUnwindSafe(|| {
+                engine.in_rfn_after_hook.set(true);
+                let _guard = Guard::new(|| engine.in_rfn_after_hook.set(false));
 
-                    handle_error(rfn, payload)
+                match result {
+                    Ok(ref slot) => after_hook(rfn.name.get(), Ok(&slot.root())),
+                    Err(ref err) => after_hook(rfn.name.get(), Err(err)),
                 }
+            }));
+
+            if let Err(payload) = hook_result {
+                return Err(glsp::rfn_panic_error(rfn, "the after-hook for ", payload));
             }
+        }
+
+        result
+    }
+
+    #[cold]
+    fn rfn_panic_error(rfn: &RFn, prefix: &str, payload: Box<dyn Any + Send>) -> GError {
+        let rfn_description = match rfn.name.get() {
+            Some(sym) => format!("{}rfn ({})", prefix, sym),
+            None => format!("{}anonymous rfn", prefix),
+        };
+
+        if let Some(msg) = payload.downcast_ref::<&str>() {
+            error!("{} panicked, '{}'", rfn_description, msg)
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            error!("{} panicked, '{}'", rfn_description, msg)
+        } else {
+            error!("{} panicked", rfn_description)
+        }
+    }
+
+    /**
+    Registers a callback which is invoked just before every call to an [`RFn`](struct.RFn.html).
+
+    The callback receives the `RFn`'s name (if any) and its arguments. If it returns
+    an `Err`, the `RFn` is not called, and the error is returned to the caller instead. This
+    can be used to implement logging, profiling or capability checks for an entire API, without
+    needing to wrap each `RFn` individually.
+
+    Passing `None` removes the current callback.
+
+    Any `RFn`s called by the callback itself are not intercepted. If the callback panics, the
+    panic is converted into an `Err`, just like a panicking `RFn`.
+
+    When no callback has been registered, the only cost is a single branch per `RFn` call.
+    When a callback is registered, each `RFn` call must also root all of its arguments, which
+    roughly doubles the overhead of calling a trivial `RFn`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    glsp::set_rfn_interceptor(Some(Box::new(|name, args| {
+        prn!("calling {:?} with {} arguments", name, args.len());
+        Ok(())
+    })));
+    # Ok(()) }).unwrap();
+    ```
+    */
+
+    #[allow(clippy::type_complexity)]
+    pub fn set_rfn_interceptor(
+        interceptor: Option<Box<dyn Fn(Option<Sym>, &[Val]) -> GResult<()>>>,
+    ) {
+        with_engine(|engine| {
+            *engine.rfn_interceptor.borrow_mut() = interceptor.map(Rc::from);
         })
     }
 
+    /**
+    Registers a callback which is invoked just after every call to an [`RFn`](struct.RFn.html).
+
+    The callback receives the `RFn`'s name (if any) and the result of the call. It's
+    invoked even when the `RFn` returns an `Err` or panics, but not when the call is
+    vetoed by an [interceptor](fn.set_rfn_interceptor.html).
+
+    Passing `None` removes the current callback. The performance cost is similar to
+    [`glsp::set_rfn_interceptor`](fn.set_rfn_interceptor.html).
+
+    Any `RFn`s called by the callback itself don't invoke the callback again. If the callback
+    panics, the panic is converted into an `Err`, which replaces the result of the call.
+    */
+
+    #[allow(clippy::type_complexity)]
+    pub fn set_rfn_after_hook(
+        after_hook: Option<Box<dyn Fn(Option<Sym>, Result<&Val, &GError>)>>,
+    ) {
+        with_engine(|engine| {
+            *engine.rfn_after_hook.borrow_mut() = after_hook.map(Rc::from);
+        })
+    }
+
+    /**
+    Checks an argument count against a callable's arity, returning the same error as an
+    [`RFn`](struct.RFn.html) which received too few or too many arguments.
+
+    `max` should be `None` for a variadic callable. When `name` is provided, it's included
+    in the error message.
+
+    This is useful for hand-written callables which need to report arity errors consistently
+    with the functions generated by [`glsp::rfn`](fn.rfn.html).
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    assert!(glsp::check_arity(2, 1, Some(3), None).is_ok());
+    assert!(glsp::check_arity(0, 1, Some(3), None).is_err());
+    assert!(glsp::check_arity(100, 1, None, Some(sym!("sum"))).is_ok());
+    # Ok(()) }).unwrap();
+    ```
+    */
+
+    #[inline]
+    pub fn check_arity(
+        received: usize,
+        min: usize,
+        max: Option<usize>,
+        name: Option<Sym>,
+    ) -> GResult<()> {
+        #[cold]
+        #[inline(never)]
+        fn arity_error(
+            received: usize,
+            min: usize,
+            max: Option<usize>,
+            name: Option<Sym>,
+        ) -> GError {
+            let prefix = match name {
+                Some(name) => format!(" passed to rfn ({})", name),
+                None => String::new(),
+            };
+
+            if received < min {
+                error!(
+                    "too few arguments{}: received {}, expected at least {}",
+                    prefix, received, min
+                )
+            } else {
+                error!(
+                    "too many arguments{}: received {}, expected no more than {}",
+                    prefix,
+                    received,
+                    max.unwrap()
+                )
+            }
+        }
+
+        if received < min || max.map_or(false, |max| received > max) {
+            Err(arity_error(received, min, max, name))
+        } else {
+            Ok(())
+        }
+    }
+
     //---------------------------------------------------------------------------------------------
     // parsing and printing
     //---------------------------------------------------------------------------------------------
@@ -3067,7 +4108,30 @@ pub mod glsp {
         Ok(None)
     }
 
-    /** Equivalent to [`(parse-all text filename)`](https://gamelisp.rs/std/parse-all). */
+    /**
+    Equivalent to [`(parse-all text filename)`](https://gamelisp.rs/std/parse-all).
+
+    Only the reader is invoked: macros are not expanded, and nothing is evaluated. This makes
+    `parse_all` suitable for tools like linters and formatters, which need to inspect a source
+    file without running it.
+
+    When `filename` is `Some`, each arr produced by the reader records the file and line number
+    from which it was read, and any syntax error will report the same information.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let forms = glsp::parse_all("(def x 10)\n(prn (+ x 1))\n", Some("example.glsp"))?;
+    assert_eq!(forms.len(), 2);
+    assert!(glsp::global::<_, Val>("x").is_err());
+
+    assert!(glsp::parse_all("(def x 10\n", Some("example.glsp")).is_err());
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
 
     pub fn parse_all(mut text: &str, filename: Option<&str>) -> GResult<Vec<Val>> {
         let file_id = filename.map(|path| glsp::filename(path));
@@ -3087,6 +4151,56 @@ pub mod glsp {
         Ok(results)
     }
 
+    /**
+    Parses a string into forms, recording the source location of each form.
+
+    This is equivalent to [`glsp::parse_all`](fn.parse_all.html), but it also returns a
+    [`SpanTable`](struct.SpanTable.html) which maps each form produced by the reader to its
+    byte range, line and column within `text`. This is useful for tools which need to point
+    at precise locations in the source, like formatters and linters.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let (forms, spans) = glsp::parse_with_spans("(a b)\n  (c d)\n", None)?;
+
+    let second = spans.form_span(1).unwrap();
+    assert_eq!((second.start, second.end), (8, 13));
+    assert_eq!((second.line, second.column), (2, 3));
+
+    let arr = Root::<Arr>::from_val(&forms[1])?;
+    let d = spans.element_span(&arr, 1).unwrap();
+    assert_eq!((d.start, d.end), (11, 12));
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+
+    pub fn parse_with_spans(
+        mut text: &str,
+        filename: Option<&str>,
+    ) -> GResult<(Vec<Val>, SpanTable)> {
+        let file_id = filename.map(|path| glsp::filename(path));
+
+        glsp::push_frame(Frame::GlspApi(GlspApiName::ParseAll, file_id));
+        let _guard = Guard::new(glsp::pop_frame);
+
+        let mut parser = Parser::new(file_id);
+        parser.record_spans();
+
+        let mut results = Vec::new();
+        while !text.is_empty() {
+            if let Some(form) = parser.parse(&mut text)? {
+                results.push(form);
+            }
+        }
+
+        parser.ensure_finished()?;
+        Ok((results, parser.take_spans().unwrap()))
+    }
+
     /** Equivalent to [`(parse-1 text filename)`](https://gamelisp.rs/std/parse-1). */
 
     pub fn parse_1(mut text: &str, filename: Option<&str>) -> GResult<Val> {
@@ -3167,6 +4281,62 @@ pub mod glsp {
         })
     }
 
+    /**
+    Temporarily replaces the [`pr_writer`](fn.set_pr_writer.html) while `f` is running.
+
+    The previous writer is restored when `f` returns, even if it panics. This is useful for
+    capturing a script's output in a test, or for displaying it in an editor panel.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # use std::{cell::RefCell, io::{self, Write}, rc::Rc};
+    #
+    # Engine::new().run(|| {
+    #[derive(Clone, Default)]
+    struct Capture(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let capture = Capture::default();
+    glsp::with_pr_writer(Box::new(capture.clone()), || {
+        prn!("hello, world");
+    });
+
+    assert_eq!(&capture.0.borrow()[..], b"hello, world\n");
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+
+    pub fn with_pr_writer<R, F: FnOnce() -> R>(pr_writer: Box<dyn Write>, f: F) -> R {
+        let prev = with_engine(|engine| engine.pr_writer.replace(pr_writer));
+        let _guard = Guard::new(move || glsp::set_pr_writer(prev));
+
+        f()
+    }
+
+    /**
+    Temporarily replaces the [`epr_writer`](fn.set_epr_writer.html) while `f` is running.
+
+    The previous writer is restored when `f` returns, even if it panics.
+    */
+
+    pub fn with_epr_writer<R, F: FnOnce() -> R>(epr_writer: Box<dyn Write>, f: F) -> R {
+        let prev = with_engine(|engine| engine.epr_writer.replace(epr_writer));
+        let _guard = Guard::new(move || glsp::set_epr_writer(prev));
+
+        f()
+    }
+
     //---------------------------------------------------------------------------------------------
     // spans and stack-tracing
     //---------------------------------------------------------------------------------------------
@@ -3379,6 +4549,71 @@ pub mod glsp {
         with_engine(|engine| engine.errors_verbose.get())
     }
 
+    /**
+    Enables or disables the conversion of integral flos into Rust integers.
+
+    By default, converting a `Val::Flo` into a Rust integer type like `i32` or `u8` using
+    [`FromVal`](trait.FromVal.html) is an error, even when the flo has no fractional part.
+    When this setting is enabled, a flo like `10.0` can be converted into the integer `10`.
+    Flos with a fractional part, like `10.5`, and flos which are out of range for the
+    target type, like `1e30`, are still rejected.
+
+    When the program begins, this setting is disabled.
+    */
+    pub fn set_flo_to_int_coercion(enabled: bool) {
+        with_engine(|engine| engine.flo_to_int_coercion.set(enabled))
+    }
+
+    pub(crate) fn flo_to_int_coercion() -> bool {
+        with_engine(|engine| engine.flo_to_int_coercion.get())
+    }
+
+    /**
+    Constructs a type-mismatch error for a failed conversion to the Rust type `T`.
+
+    The error message has the same format as the errors produced by the built-in
+    [`FromVal`](trait.FromVal.html) implementations: `"expected T, received a val"`. If `T`
+    has been registered using [`RClassBuilder`](struct.RClassBuilder.html), its `RClass` name
+    is used. Otherwise, `T` is named by its
+    [`type_name`](https://doc.rust-lang.org/std/any/fn.type_name.html), with any module
+    paths removed.
+
+    ```
+    # #![feature(min_specialization)]
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    struct Meters(f32);
+
+    impl FromVal for Meters {
+        fn from_val(val: &Val) -> GResult<Meters> {
+            match *val {
+                Val::Flo(f) => Ok(Meters(f)),
+                ref val => Err(glsp::type_error::<Meters>(val)),
+            }
+        }
+    }
+    #
+    # Engine::new().run(|| {
+    let err = Meters::from_val(&Val::Int(5)).err().unwrap();
+    assert!(err.to_string().contains("expected Meters, received an int"));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn type_error<T: 'static>(val: &Val) -> GError {
+        let rclass_name = with_engine(|engine| {
+            let rclasses = engine.rclasses.borrow();
+            rclasses.get(&TypeId::of::<T>()).map(|rclass| rclass.name)
+        });
+
+        let name = match rclass_name {
+            Some(name) => name.to_string(),
+            None => short_type_name(type_name::<T>()),
+        };
+
+        error!("expected {}, received {}", name, val.a_type_name())
+    }
+
     //---------------------------------------------------------------------------------------------
     // allocation
     //---------------------------------------------------------------------------------------------
@@ -3415,58 +4650,246 @@ pub mod glsp {
                 //gfn/rfn call, if any, within the innermost expander - or the callsite of
                 //the expander itself, otherwise.
 
-                //possible future extension (todo) would be to add a type of Span which refers
-                //to a line of rust code, rather than a line of glsp code. couldn't use this
-                //for glsp::call, but we could use it for macros like arr![] and backquote!().
+                //possible future extension (todo) would be to add a type of Span which refers
+                //to a line of rust code, rather than a line of glsp code. couldn't use this
+                //for glsp::call, but we could use it for macros like arr![] and backquote!().
+
+                engine.vm.expander_cur_span()
+            } else {
+                Span::default()
+            }
+        })
+    }
+
+    ///Constructs an empty [array](struct.Arr.html).
+    pub fn arr() -> Root<Arr> {
+        let arr = with_heap(|heap| heap.recycler.arr());
+        arr.set_span(glsp::new_arr_span(None));
+        arr
+    }
+
+    ///Constructs an empty [array](struct.Arr.html) with space for at least `capacity` elements.
+    pub fn arr_with_capacity(capacity: usize) -> Root<Arr> {
+        let arr = with_heap(|heap| heap.recycler.arr_with_capacity(capacity));
+        arr.set_span(glsp::new_arr_span(None));
+        arr
+    }
+
+    /**
+    Constructs an [array](struct.Arr.html) which contains `reps` repetitions of `elem`.
+
+    Returns an `Err` if [type conversion](trait.IntoVal.html) fails.
+    */
+    pub fn arr_from_elem<T>(elem: T, reps: usize) -> GResult<Root<Arr>>
+    where
+        T: Clone + IntoVal,
+    {
+        let arr = with_heap(|heap| heap.recycler.arr_from_elem(elem, reps))?;
+        arr.set_span(glsp::new_arr_span(None));
+        Ok(arr)
+    }
+
+    /**
+    Constructs an [array](struct.Arr.html) from the contents of a Rust iterator.
+
+    Returns an `Err` if [type conversion](trait.IntoVal.html) fails for any element.
+    */
+    pub fn arr_from_iter<T>(iter: T) -> GResult<Root<Arr>>
+    where
+        T: IntoIterator,
+        T::Item: IntoVal,
+    {
+        let arr = with_heap(|heap| heap.recycler.arr_from_iter(iter))?;
+        arr.set_span(glsp::new_arr_span(None));
+        Ok(arr)
+    }
+
+    /**
+    Constructs an [array](struct.Arr.html) of records from a tuple of parallel `Vec`s.
+
+    Each `Vec` provides one field of every record. For example, three `Vec<f32>`s of
+    length `n` will produce an array of `n` three-element arrays. Returns an `Err` if the
+    `Vec`s don't all have the same length.
+
+    The inverse operation is [`Arr::unzip_fields`](struct.Arr.html#method.unzip_fields).
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let names = vec!["goblin".to_string(), "troll".to_string()];
+    let hit_points = vec![10_i32, 40_i32];
+
+    let records = glsp::zip_fields((names.clone(), hit_points.clone()))?;
+    assert_eq!(records.len(), 2);
+
+    let (names2, hit_points2): (Vec<String>, Vec<i32>) = records.unzip_fields()?;
+    assert_eq!(names2, names);
+    assert_eq!(hit_points2, hit_points);
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn zip_fields<T: FieldColumns>(columns: T) -> GResult<Root<Arr>> {
+        columns.zip_fields()
+    }
+
+    /**
+    Converts any type which implements `serde::Serialize` into a GameLisp value.
+
+    Structs are converted to [tables](struct.Tab.html) whose keys are the field names, as
+    symbols. Sequences and tuples are converted to [arrays](struct.Arr.html), and maps are
+    converted to tables. Unit enum variants are converted to their name, as a symbol; other
+    enum variants are converted to an array which starts with the variant's name, followed by
+    its payload.
+
+    Floating-point numbers are converted to `Val::Flo`, so an `f64` is truncated to an `f32`.
+    Integers which are outside the range of an `i32` trigger an error, just like their
+    [`IntoVal`](trait.IntoVal.html) implementations.
+
+    This function is only available when the `"serde"` feature is enabled.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # use std::collections::HashMap;
+    #
+    # Engine::new().run(|| {
+    let val = glsp::to_val(&vec![(1, 'a'), (2, 'b')])?;
+    assert_eq!(val.to_string(), "((1 \\a) (2 \\b))");
+
+    let mut settings = HashMap::new();
+    settings.insert("volume", Some(0.5));
+    settings.insert("brightness", None);
+
+    let val = glsp::to_val(&settings)?;
+    let tab = Root::<Tab>::from_val(&val)?;
+    assert_eq!(tab.get::<_, f32>("volume")?, 0.5);
+    assert!(tab.get::<_, Val>("brightness")?.is_nil());
+
+    let err = glsp::to_val(&[1, u64::MAX]).unwrap_err();
+    assert!(err.to_string().contains("outside the range of an i32"));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    #[cfg(feature = "serde")]
+    pub fn to_val<T: Serialize + ?Sized>(value: &T) -> GResult<Val> {
+        value.serialize(ValSerializer)
+    }
+
+    /**
+    Converts a GameLisp value into any type which implements `serde::Deserialize`.
+
+    This accepts the same representation which is produced by [`glsp::to_val`](fn.to_val.html).
+    [Tables](struct.Tab.html) can be deserialized into structs, with symbol keys matching the
+    struct's field names. [Arrays](struct.Arr.html) can be deserialized into sequences and
+    tuples, and symbols can be deserialized into unit enum variants. `#n`, or a missing field,
+    is deserialized as `None`.
 
-                engine.vm.expander_cur_span()
-            } else {
-                Span::default()
-            }
-        })
-    }
+    When the conversion fails, the error message includes the path to the value which couldn't
+    be converted, like `"at root.player.speed: expected f32, received a str"`.
 
-    ///Constructs an empty [array](struct.Arr.html).
-    pub fn arr() -> Root<Arr> {
-        let arr = with_heap(|heap| heap.recycler.arr());
-        arr.set_span(glsp::new_arr_span(None));
-        arr
-    }
+    This function is only available when the `"serde"` feature is enabled.
 
-    ///Constructs an empty [array](struct.Arr.html) with space for at least `capacity` elements.
-    pub fn arr_with_capacity(capacity: usize) -> Root<Arr> {
-        let arr = with_heap(|heap| heap.recycler.arr_with_capacity(capacity));
-        arr.set_span(glsp::new_arr_span(None));
-        arr
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # use std::collections::HashMap;
+    #
+    # Engine::new().run(|| {
+    let val = glsp::to_val(&(1, "two", 3.5))?;
+    let tuple: (i32, String, f32) = glsp::from_val(&val)?;
+    assert_eq!(tuple, (1, "two".to_string(), 3.5));
+
+    let mut scores = HashMap::new();
+    scores.insert("alice".to_string(), 10);
+    scores.insert("bob".to_string(), 7);
+    let val = glsp::to_val(&scores)?;
+    assert_eq!(glsp::from_val::<HashMap<String, i32>>(&val)?, scores);
+
+    let nested = Some(vec![Some(1), None]);
+    let val = glsp::to_val(&nested)?;
+    assert_eq!(glsp::from_val::<Option<Vec<Option<i32>>>>(&val)?, nested);
+
+    let val = Val::Arr(arr![1, "fast"]);
+    let err = glsp::from_val::<(i32, f32)>(&val).unwrap_err();
+    assert!(err.to_string().contains("at root[1]: expected f32, received a str"));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    #[cfg(feature = "serde")]
+    pub fn from_val<T: DeserializeOwned>(val: &Val) -> GResult<T> {
+        T::deserialize(ValDeserializer(val.clone()))
     }
 
     /**
-    Constructs an [array](struct.Arr.html) which contains `reps` repetitions of `elem`.
+    Serializes a value, and every value reachable from it, into a byte buffer.
 
-    Returns an `Err` if [type conversion](trait.IntoVal.html) fails.
+    Shared references and reference cycles are preserved: when the buffer is passed to
+    [`glsp::deserialize_heap`](fn.deserialize_heap.html), an array which appeared twice in the
+    original graph will appear twice in the new graph, as the same array.
+
+    Nil, numbers, chars, bools, symbols, [arrays](struct.Arr.html),
+    [strings](struct.Str.html) and [tables](struct.Tab.html) are supported. Each gensym is
+    replaced with a fresh gensym when the buffer is deserialized. Attempting to serialize
+    any other type of value is an error. [Objects](struct.Obj.html) and
+    [Rust data](struct.RData.html) can be supported by using
+    [`glsp::serialize_heap_with`](fn.serialize_heap_with.html) instead.
+
+    The buffer is only intended to be read by the same version of GameLisp which wrote it.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let shared = arr![1, 2, 3];
+    let outer = arr![shared.clone(), shared];
+
+    let bytes = glsp::serialize_heap(&Val::Arr(outer))?;
+    let copy = Root::<Arr>::from_val(&glsp::deserialize_heap(&bytes)?)?;
+
+    let first: Root<Arr> = copy.get(0)?;
+    let second: Root<Arr> = copy.get(1)?;
+    assert!(Root::ptr_eq(&first, &second));
+    # Ok(()) }).unwrap();
+    ```
     */
-    pub fn arr_from_elem<T>(elem: T, reps: usize) -> GResult<Root<Arr>>
+    pub fn serialize_heap(root: &Val) -> GResult<Vec<u8>> {
+        snapshot::serialize_heap(root, &mut ())
+    }
+
+    /**
+    Equivalent to [`glsp::serialize_heap`](fn.serialize_heap.html), but objects and rdata are
+    converted into serializable values by the given [`HeapSerializer`](trait.HeapSerializer.html).
+    */
+    pub fn serialize_heap_with<H>(root: &Val, hooks: &mut H) -> GResult<Vec<u8>>
     where
-        T: Clone + IntoVal,
+        H: HeapSerializer + ?Sized,
     {
-        let arr = with_heap(|heap| heap.recycler.arr_from_elem(elem, reps))?;
-        arr.set_span(glsp::new_arr_span(None));
-        Ok(arr)
+        snapshot::serialize_heap(root, hooks)
     }
 
     /**
-    Constructs an [array](struct.Arr.html) from the contents of a Rust iterator.
+    Reconstructs a value which was serialized by [`glsp::serialize_heap`](fn.serialize_heap.html).
 
-    Returns an `Err` if [type conversion](trait.IntoVal.html) fails for any element.
+    All of the arrays, strings and tables in the result are newly allocated. Those which were
+    [frozen](https://gamelisp.rs/std/freeze-mut) when they were serialized will be frozen again.
+    Returns an error if the buffer is malformed.
     */
-    pub fn arr_from_iter<T>(iter: T) -> GResult<Root<Arr>>
+    pub fn deserialize_heap(bytes: &[u8]) -> GResult<Val> {
+        snapshot::deserialize_heap(bytes, &mut ())
+    }
+
+    /**
+    Equivalent to [`glsp::deserialize_heap`](fn.deserialize_heap.html), but objects and rdata are
+    reconstructed by the given [`HeapSerializer`](trait.HeapSerializer.html).
+    */
+    pub fn deserialize_heap_with<H>(bytes: &[u8], hooks: &mut H) -> GResult<Val>
     where
-        T: IntoIterator,
-        T::Item: IntoVal,
+        H: HeapSerializer + ?Sized,
     {
-        let arr = with_heap(|heap| heap.recycler.arr_from_iter(iter))?;
-        arr.set_span(glsp::new_arr_span(None));
-        Ok(arr)
+        snapshot::deserialize_heap(bytes, hooks)
     }
 
     ///Constructs an empty [string](struct.Str.html).
@@ -3651,6 +5074,155 @@ pub mod glsp {
         )))
     }
 
+    /**
+    Constructs an iterator which produces each of its items by calling a Rust closure.
+
+    Each time the iterator is advanced, `f` is called with a mutable reference to `state`. The
+    iterator will finish when `f` returns `Ok(None)`. Its [`len`](struct.GIter.html#method.len)
+    is always `GIterLen::Unknown`. This is the lazy counterpart to
+    [`glsp::arr_from_iter`](fn.arr_from_iter.html), so it may produce an infinite sequence.
+
+    When the iterator is cloned, using [`(clone it)`](https://gamelisp.rs/std/clone) or
+    [`GIter::shallow_clone`](struct.GIter.html#method.shallow_clone), the clone receives its own
+    copy of `state`, so the two iterators can be advanced independently.
+
+    The garbage collector can't see inside `state`, so it shouldn't own any `Root`s: they would
+    keep their objects alive for as long as the iterator exists, even if they refer back to the
+    iterator itself. If `state` needs to point to heap-allocated objects, store
+    [`Gc`](struct.Gc.html) pointers and use
+    [`glsp::giter_from_fn_with_trace`](fn.giter_from_fn_with_trace.html) instead.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    let mut squares = glsp::giter_from_fn(0, |n: &mut i32| {
+        *n += 1;
+        Ok(if *n <= 3 { Some(Val::Int(*n * *n)) } else { None })
+    });
+
+    assert_eq!(i32::from_val(&squares.next().unwrap()?)?, 1);
+
+    let copy = squares.shallow_clone();
+    let rest = squares.collect::<GResult<Vec<Val>>>()?;
+    let copy_rest = copy.collect::<GResult<Vec<Val>>>()?;
+    assert_eq!(rest.len(), 2);
+    assert_eq!(copy_rest.len(), 2);
+    assert_eq!(i32::from_val(&copy_rest[1])?, 9);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn giter_from_fn<S, F>(state: S, f: F) -> Root<GIter>
+    where
+        S: Clone + 'static,
+        F: Fn(&mut S) -> GResult<Option<Val>> + 'static,
+    {
+        glsp::giter_from_fn_with_trace(state, |_, _| (), f)
+    }
+
+    /**
+    Equivalent to [`glsp::giter_from_fn`](fn.giter_from_fn.html), but `state` may own
+    [`Gc`](struct.Gc.html) pointers.
+
+    `trace` has the same role and restrictions as the callback passed to
+    [`RClassBuilder::trace`](struct.RClassBuilder.html#method.trace): it should pass each `Gc`,
+    `GcVal` and `RGc` owned by `state` to the [`GcVisitor`](struct.GcVisitor.html), and do
+    nothing else. Those objects will be kept alive for as long as the iterator can reach them.
+
+    There's no need to call [`glsp::write_barrier`](fn.write_barrier.html). The write barrier is
+    invoked automatically each time `f` is called.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    #[derive(Clone)]
+    struct Backwards {
+        arr: Gc<Arr>,
+        i: usize,
+    }
+
+    let arr = arr![10, 20, 30];
+    let backwards = glsp::giter_from_fn_with_trace(
+        Backwards { arr: arr.downgrade(), i: arr.len() },
+        |state, visitor| visitor.visit(&state.arr),
+        |state| {
+            if state.i == 0 {
+                return Ok(None);
+            }
+
+            state.i -= 1;
+            let arr = state.arr.upgrade().unwrap();
+            Ok(Some(arr.get(state.i)?))
+        },
+    );
+
+    drop(arr);
+    glsp::gc();
+
+    let items = backwards
+        .map(|item| i32::from_val(&item?))
+        .collect::<GResult<Vec<i32>>>()?;
+    assert_eq!(items, [30, 20, 10]);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn giter_from_fn_with_trace<S, F>(
+        state: S,
+        trace: fn(&S, &mut GcVisitor),
+        f: F,
+    ) -> Root<GIter>
+    where
+        S: Clone + 'static,
+        F: Fn(&mut S) -> GResult<Option<Val>> + 'static,
+    {
+        glsp::giter(GIterState::FromFn(Box::new(FromFn {
+            state,
+            trace,
+            f: Rc::new(f),
+        })))
+    }
+
+    /**
+    Converts a Rust iterator into a lazy [`GIter`](struct.GIter.html).
+
+    [`IntoVal`](trait.IntoVal.html) converts Rust iterators and collections into arrays, which
+    requires the whole sequence to be produced up-front. `glsp::lazy` instead converts each item
+    on demand, so it can be used with infinite or expensive iterators. Any side-effects of the
+    iterator will occur while the script is iterating, not when `glsp::lazy` is called.
+
+    When the resulting `GIter` is cloned, the Rust iterator is cloned along with it.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    //an infinite sequence, which could be passed to a script's (take 5 squares)
+    let squares = glsp::lazy((0..).map(|x: i32| x * x));
+
+    let first = squares.take(5).collect::<GResult<Vec<Val>>>()?;
+    assert_eq!(i32::from_val(&first[4])?, 16);
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn lazy<I>(iter: I) -> Root<GIter>
+    where
+        I: IntoIterator,
+        I::IntoIter: Clone + 'static,
+        I::Item: IntoVal,
+    {
+        glsp::giter_from_fn(iter.into_iter(), |iter| match iter.next() {
+            Some(item) => Ok(Some(item.into_val()?)),
+            None => Ok(None),
+        })
+    }
+
     /** Equivalent to [`(chunks len src-arr)`](https://gamelisp.rs/std/chunks). */
 
     pub fn chunks(chunk_len: usize, src_arr: &Root<Arr>) -> GResult<Root<GIter>> {
@@ -3854,6 +5426,51 @@ pub mod glsp {
         glsp::giter(GIterState::SkipWhile(Some(raw_callable), base.to_raw()))
     }
 
+    //---------------------------------------------------------------------------------------------
+    // equality
+    //---------------------------------------------------------------------------------------------
+
+    /**
+    Tests whether two values are structurally equal.
+
+    Arrays are compared element-wise, tables are compared key-by-key (regardless of iteration
+    order), and strings are compared by content. Other values are compared using
+    [`Val::same`](enum.Val.html#method.same), so `1` and `1.0` are equal, and objects and
+    `RData` are only equal to themselves.
+
+    Unlike [`Val::try_eq`](enum.Val.html#method.try_eq), this never invokes an `op-eq?` method,
+    so it can't fail. Reference cycles are supported.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let a = arr![1, arr![2.0, "three"]];
+    let b = arr![1.0, arr![2, "three"]];
+    assert!(glsp::deep_eq(&Val::Arr(a.clone()), &Val::Arr(b.clone())));
+
+    a.push(a.clone())?;
+    b.push(b.clone())?;
+    assert!(glsp::deep_eq(&Val::Arr(a), &Val::Arr(b)));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn deep_eq(v0: &Val, v1: &Val) -> bool {
+        val::deep_eq(v0, v1, &mut |r0, r1| Root::ptr_eq(r0, r1))
+    }
+
+    /**
+    Equivalent to [`glsp::deep_eq`](fn.deep_eq.html), but pairs of `RData` are compared by
+    calling `rdata_eq`, rather than by identity.
+    */
+    pub fn deep_eq_with<F>(v0: &Val, v1: &Val, mut rdata_eq: F) -> bool
+    where
+        F: FnMut(&Root<RData>, &Root<RData>) -> bool,
+    {
+        val::deep_eq(v0, v1, &mut rdata_eq)
+    }
+
     //---------------------------------------------------------------------------------------------
     // garbage collection
     //---------------------------------------------------------------------------------------------
@@ -3867,6 +5484,32 @@ pub mod glsp {
         })
     }
 
+    /**
+    Performs a full garbage-collection cycle, freeing all unreachable objects.
+
+    Unlike [`glsp::gc`](fn.gc.html), the amount of work performed is unbounded, so this
+    function may cause a long pause. It's intended to be called at times when a pause is
+    acceptable, such as during a loading screen.
+    */
+    pub fn gc_collect_full() {
+        with_engine(|engine| {
+            for _ in 0..2 {
+                engine.vm.traverse_stacks();
+                engine.heap.step_full();
+            }
+        })
+    }
+
+    /**
+    Returns a snapshot of the garbage collector's internal counters.
+
+    The amount of work performed by each call to [`glsp::gc`](fn.gc.html) can be tuned
+    using [`glsp::gc_set_ratio`](fn.gc_set_ratio.html).
+    */
+    pub fn gc_stats() -> GcStats {
+        with_engine(|engine| engine.heap.stats())
+    }
+
     /** Equivalent to [`(gc-value 'ratio)`](https://gamelisp.rs/std/gc-value). */
 
     pub fn gc_ratio() -> f32 {
@@ -4013,6 +5656,32 @@ pub mod glsp {
         eval::eval(&vals, None, false)
     }
 
+    /**
+    Parses and evaluates a string, converting the value of its final toplevel form into `R`.
+
+    This is similar to [`glsp::load_str`](fn.load_str.html), except that it's an error for
+    the string to contain no forms.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let total: i32 = glsp::eval_str("(let a 10) (let b 20) (+ a b)")?;
+    assert!(total == 30);
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn eval_str<R: FromVal>(text: &str) -> GResult<R> {
+        glsp::push_frame(Frame::GlspApi(GlspApiName::EvalStr, None));
+        let _guard = Guard::new(glsp::pop_frame);
+
+        let vals = glsp::parse_all(&text, None)?;
+        ensure!(!vals.is_empty(), "no forms to evaluate");
+
+        R::from_val(&eval::eval(&vals, None, false)?)
+    }
+
     /**
     Loads a file and serializes its compiled bytecode to a `Vec<u8>`.
 
@@ -4246,6 +5915,18 @@ pub mod glsp {
         C: CallableOps,
         A: IntoCallArgs,
         R: FromVal,
+    {
+        glsp::call_impl(receiver, args, |arg_count| {
+            R::from_val(&receiver.receive_call(arg_count)?)
+        })
+    }
+
+    //pushes the arguments to the reg stack, then passes the argument count to `f`
+    fn call_impl<C, A, R, F>(receiver: &C, args: A, f: F) -> GResult<R>
+    where
+        C: CallableOps,
+        A: IntoCallArgs,
+        F: FnOnce(usize) -> GResult<R>,
     {
         glsp::push_frame(Frame::GlspCall(receiver.name()));
         let _guard = Guard::new(glsp::pop_frame);
@@ -4254,15 +5935,144 @@ pub mod glsp {
             let mut stacks = engine.vm.stacks.borrow_mut();
             let starting_len = stacks.regs.len();
 
+            //for ArgsFromIter, arg_count() is only a lower bound, so the reg stack may still
+            //need to grow while the arguments are being pushed
+            stacks.regs.reserve(args.arg_count());
             args.into_call_args(&mut stacks.regs)?;
 
             let arg_count = stacks.regs.len() - starting_len;
             drop(stacks);
 
-            R::from_val(&receiver.receive_call(arg_count)?)
+            f(arg_count)
         })
     }
 
+    /**
+    Invokes a callable value, converting any Rust panic into an `Err`.
+
+    This is equivalent to [`glsp::call`](fn.call.html), except that the call takes place
+    within a [`catch_unwind`](https://doc.rust-lang.org/std/panic/fn.catch_unwind.html)
+    boundary. It's intended for running untrusted code, such as mods.
+
+    [`RFn`](struct.RFn.html)s already convert their own panics into errors, so this function
+    only makes a difference when a panic occurs elsewhere: for example, in a callback
+    registered with [`glsp::set_rfn_interceptor`](fn.set_rfn_interceptor.html), or in a
+    [`FromVal`](trait.FromVal.html) or [`IntoCallArgs`](trait.IntoCallArgs.html)
+    implementation.
+
+    When a panic is caught, the error message is `"script triggered a panic: ..."`. Before the
+    error is returned, the VM's register stack, stack-trace frames and recursion counter are
+    truncated to the depth they had when `call_catching` was called, so that the `Runtime`
+    remains usable. RefCell borrows are released during unwinding, as usual. However, no other
+    state is rolled back: any global variables or collections which were mutated before the
+    panic will remain mutated, and a coroutine which was running when the panic occurred may
+    be left in an unusable state.
+    */
+    pub fn call_catching<C, A, R>(receiver: &C, args: A) -> GResult<R>
+    where
+        C: CallableOps,
+        A: IntoCallArgs,
+        R: FromVal,
+    {
+        let depths = with_engine(|engine| engine.vm.depths());
+
+        match panic::catch_unwind(AssertUnwindSafe(|| glsp::call(receiver, args))) {
+            Ok(result) => result,
+            Err(payload) => {
+                with_engine(|engine| engine.vm.restore_depths(depths));
+
+                if let Some(msg) = payload.downcast_ref::<&str>() {
+                    bail!("script triggered a panic: '{}'", msg)
+                } else if let Some(msg) = payload.downcast_ref::<String>() {
+                    bail!("script triggered a panic: '{}'", msg)
+                } else {
+                    bail!("script triggered a panic")
+                }
+            }
+        }
+    }
+
+    /**
+    Invokes a callable value, discarding its return value.
+
+    This is equivalent to [`glsp::call`](fn.call.html), but it skips the conversion of the
+    return value, so there's no need to annotate its type. When the `receiver` is an
+    [`RFn`](struct.RFn.html), its return value is discarded without being rooted.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # fn example(on_click: Root<GFn>) -> GResult<()> {
+    //equivalent to `let _: Val = glsp::call(&on_click, &[10, 20])?;`
+    glsp::call_unit(&on_click, &[10, 20])?;
+    # Ok(()) }
+    ```
+    */
+    pub fn call_unit<C, A>(receiver: &C, args: A) -> GResult<()>
+    where
+        C: CallableOps,
+        A: IntoCallArgs,
+    {
+        glsp::call_impl(receiver, args, |arg_count| receiver.receive_call_unit(arg_count))
+    }
+
+    /**
+    Invokes a callable value, passing it a table of named arguments.
+
+    GameLisp doesn't have keyword arguments. Instead, functions which accept many optional
+    parameters conventionally receive a single [table](struct.Tab.html), which they can
+    destructure using a table pattern. `call_with_keys` constructs that table from a slice of
+    key-value pairs, and passes it to the `receiver` as its only argument.
+
+    Returns an `Err` if any key appears more than once, if a key is a
+    [gensym](struct.Sym.html#method.is_gensym) (which a table pattern would be unable to name),
+    or if the `receiver`'s [argument limits](trait.CallableOps.html#tymethod.arg_limits) don't
+    permit it to be called with a single argument.
+
+    Parameter names aren't retained after a function has been compiled, so the keys can't
+    be validated against the callee's parameter list. An [`RFn`](struct.RFn.html) can receive
+    the table as a `Root<Tab>`, or as a `HashMap<Sym, T>`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # fn example(spawn_enemy: Root<GFn>) -> GResult<()> {
+    let enemy: Root<Obj> = glsp::call_with_keys(&spawn_enemy, &[
+        (sym!("x"), Val::Int(100)),
+        (sym!("y"), Val::Int(50)),
+        (sym!("speed"), Val::Flo(1.5)),
+    ])?;
+    # Ok(()) }
+    ```
+    */
+    pub fn call_with_keys<C, R>(receiver: &C, keys: &[(Sym, Val)]) -> GResult<R>
+    where
+        C: CallableOps,
+        R: FromVal,
+    {
+        let (min_args, max_args) = receiver.arg_limits();
+        ensure!(
+            min_args <= 1 && max_args.map_or(true, |max_args| max_args >= 1),
+            "call_with_keys passes one argument, but the receiver expects {}",
+            match max_args {
+                Some(max_args) if max_args == min_args => format!("{}", min_args),
+                Some(max_args) => format!("{} to {}", min_args, max_args),
+                None => format!("{} or more", min_args),
+            }
+        );
+
+        let tab = glsp::tab_with_capacity(keys.len());
+        for &(key, ref value) in keys {
+            ensure!(!key.is_gensym(), "gensym {} passed to call_with_keys as a key", key);
+            ensure!(!tab.has(key)?, "duplicate key {} passed to call_with_keys", key);
+            tab.set(key, value)?;
+        }
+
+        glsp::call(receiver, &[tab])
+    }
+
     pub(crate) fn call_gfn(gfn: &Root<GFn>, arg_count: usize) -> GResult<Val> {
         with_engine(|engine| Ok(engine.vm.exec_gfn(gfn, arg_count)?))
     }
@@ -4285,6 +6095,69 @@ pub mod glsp {
         with_engine(|engine| Ok(engine.vm.coro_finish(coro)?))
     }
 
+    /**
+    Resumes a set of coroutines in round-robin order until a time budget is exhausted.
+
+    A coroutine is runnable if it's in the `Newborn` or `Paused` state. Each runnable coroutine
+    is resumed with no argument, and any value it yields is discarded. The clock is only
+    checked between resumptions, so a single coroutine which runs for a long time without
+    yielding may overshoot the budget.
+
+    Returns [`RunOutcome::Idle`](enum.RunOutcome.html) if every coroutine has finished (or is
+    otherwise not runnable), or `RunOutcome::BudgetExhausted` if work remains. If a coroutine
+    fails with an error, that error is returned immediately and the coroutine is poisoned.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # use std::time::Duration;
+    #
+    # Engine::new().run(|| {
+    let gfn = Root::<GFn>::from_val(&glsp::load_str("
+        (fn ()
+          (yield)
+          (yield))
+    ")?)?;
+
+    let coros: Vec<Root<Coro>> = vec![glsp::call(&gfn, ())?, glsp::call(&gfn, ())?];
+
+    let outcome = glsp::run_coros_for(&coros, Duration::from_secs(10))?;
+    assert_eq!(outcome, RunOutcome::Idle);
+    assert!(coros.iter().all(|coro| coro.state() == CoroState::Finished));
+    #
+    # Ok(()) }).unwrap();
+    ```
+
+    This is a convenient way to time-box cooperative multitasking, e.g. when a game needs
+    to limit the amount of script execution which takes place in each frame.
+    */
+
+    pub fn run_coros_for(coros: &[Root<Coro>], budget: Duration) -> GResult<RunOutcome> {
+        let start = Instant::now();
+
+        loop {
+            let mut any_runnable = false;
+
+            for coro in coros {
+                match coro.state() {
+                    CoroState::Newborn | CoroState::Paused => (),
+                    _ => continue,
+                }
+
+                if start.elapsed() >= budget {
+                    return Ok(RunOutcome::BudgetExhausted);
+                }
+
+                any_runnable = true;
+                glsp::coro_run(coro, None)?;
+            }
+
+            if !any_runnable {
+                return Ok(RunOutcome::Idle);
+            }
+        }
+    }
+
     /** Equivalent to [`(expand val env-mode)`](https://gamelisp.rs/std/expand) */
 
     pub fn expand(val: &Val, env_mode: Option<EnvMode>) -> GResult<Val> {
@@ -1,4 +1,4 @@
-use super::engine::{glsp, with_vm, Guard, Span};
+use super::engine::{glsp, with_vm, Guard, Span, Sym};
 use super::val::Val;
 use super::vm::Frame;
 use super::wrap::IntoVal;
@@ -26,6 +26,31 @@ of `GError` which is not caught by [`try`](https://gamelisp.rs/std/try) or
 The [`with_source` method](#method.with_source) can be used to chain together two `GErrors`,
 or to chain an arbitrary [`Error`](https://doc.rust-lang.org/std/error/trait.Error.html) type
 onto a `GError`.
+
+An error's payload isn't limited to a string message: [`GError::from_val`](#method.from_val)
+and `error!(x)` accept any value which implements [`IntoVal`](trait.IntoVal.html). This can
+be used to pass structured data to GameLisp's error-handling forms. When an error is caught
+by [`try`](https://gamelisp.rs/std/try), the payload is returned as the second element of the
+`(err payload)` array. Because the payload is stored as a [`Val`](enum.Val.html), it will
+remain alive for as long as the `GError` exists.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+# Engine::new().run(|| {
+#
+let payload = tab! {
+    (sym!("kind"), sym!("out-of-ammo")),
+    (sym!("weapon"), "crossbow"),
+};
+
+let error = error!(payload);
+let tab = Root::<Tab>::from_val(&error.val())?;
+let kind: Sym = tab.get(sym!("kind"))?;
+assert!(kind == sym!("out-of-ammo"));
+#
+# Ok(()) }).unwrap();
+```
 */
 
 pub struct GError {
@@ -40,6 +65,15 @@ pub(crate) enum Payload {
         file_location: Option<String>,
         stack_trace: Option<String>,
 
+        //the location of a failed conversion within a nested value, like "[3].key[7]"
+        path: Option<String>,
+
+        //the argument of an rfn call whose conversion failed. the index is recorded by
+        //wrapped_call(), and then converted into a message like "in argument 2 of rfn (name)"
+        //by call_rfn_impl(), which is the only place where the rfn's name is known
+        arg_index: Option<usize>,
+        arg_context: Option<String>,
+
         defer_chain: Option<GError>,
         source: Option<Box<dyn Error + 'static>>,
     },
@@ -82,6 +116,9 @@ impl GError {
                 val,
                 file_location,
                 stack_trace,
+                path: None,
+                arg_index: None,
+                arg_context: None,
                 defer_chain: None,
                 source: None,
             }),
@@ -137,6 +174,86 @@ impl GError {
         }
     }
 
+    /**
+    Returns the location within a nested value at which a conversion failed.
+
+    When a [`FromVal`](trait.FromVal.html) conversion fails for an element of a collection,
+    such as a `Vec<HashMap<String, Vec<i32>>>`, the error records the path from the outermost
+    value to the failing element, in the format `root[3].key[7]`. Returns `None` if the
+    error wasn't produced by a nested conversion.
+    */
+    pub fn path(&self) -> Option<String> {
+        match &*self.payload {
+            Payload::MacroNoOp => panic!(),
+            Payload::Error { path, .. } => path.as_ref().map(|path| format!("root{}", path)),
+        }
+    }
+
+    //prepends a segment, like "[3]" or ".key", to the path at which a conversion failed
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn prepend_path(mut self, segment: &str) -> GError {
+        if let Payload::Error { ref mut path, .. } = *self.payload {
+            *path = Some(match path.take() {
+                Some(path) => format!("{}{}", segment, path),
+                None => segment.to_string(),
+            });
+        }
+
+        self
+    }
+
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    pub fn prepend_path_index(self, i: usize) -> GError {
+        self.prepend_path(&format!("[{}]", i))
+    }
+
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn prepend_path_key(self, key: &Val) -> GError {
+        match *key {
+            Val::Sym(sym) => self.prepend_path(&format!(".{}", sym)),
+            Val::Str(ref st) => self.prepend_path(&format!(".{}", st)),
+            ref key => self.prepend_path(&format!("[{:?}]", key)),
+        }
+    }
+
+    //records that this error was produced while converting the rfn argument at index `i`
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn with_arg_index(mut self, i: usize) -> GError {
+        if let Payload::Error { ref mut arg_index, .. } = *self.payload {
+            *arg_index = Some(i);
+        }
+
+        self
+    }
+
+    //if this error was produced by an argument conversion, records the name of the rfn which
+    //was being called. consumes the argument index, so that enclosing rfn calls which
+    //propagate the error don't overwrite it.
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn with_arg_rfn(mut self, name: Option<Sym>) -> GError {
+        if let Payload::Error {
+            ref mut arg_index,
+            ref mut arg_context,
+            ..
+        } = *self.payload
+        {
+            if let Some(i) = arg_index.take() {
+                *arg_context = Some(match name {
+                    Some(name) => format!("in argument {} of rfn ({}): ", i + 1, name),
+                    None => format!("in argument {} of anonymous rfn: ", i + 1),
+                });
+            }
+        }
+
+        self
+    }
+
     #[allow(dead_code)]
     pub(crate) fn defer_chain(&self) -> Option<&GError> {
         match &*self.payload {
@@ -258,15 +375,23 @@ impl Display for GError {
                 val,
                 file_location,
                 stack_trace,
+                path,
+                arg_context,
                 source,
                 defer_chain,
+                ..
             } => {
+                let mut at_path = arg_context.clone().unwrap_or_default();
+                if let Some(ref path) = path {
+                    at_path.push_str(&format!("at root{}: ", path));
+                }
+
                 match (file_location, stack_trace) {
                     (&None, &None) => {
-                        write!(f, "{:?}", val)
+                        write!(f, "{}{:?}", at_path, val)
                     }
                     (&Some(ref file_location), &None) => {
-                        write!(f, "{}: {:?}", file_location, val)
+                        write!(f, "{}: {}{:?}", file_location, at_path, val)
                     }
                     (_, &Some(ref stack_trace)) => {
                         writeln!(f, "stack trace:")?;
@@ -298,10 +423,10 @@ impl Display for GError {
                                 }
                             }
 
-                            write!(f, "\n    {}", &val)?;
+                            write!(f, "\n    {}{}", at_path, &val)?;
                             write_source(f, source.as_ref())?;
                         } else {
-                            write!(f, "\nerror: {}", &val)?;
+                            write!(f, "\nerror: {}{}", at_path, &val)?;
                         }
 
                         if let Some(ref defer_chain) = defer_chain {
@@ -1,11 +1,13 @@
 use super::class::{Class, Obj};
 use super::code::{Bytecode, Coro, GFn, Lambda, Stay};
-use super::collections::{Arr, DequeOps, Str, Tab};
+use super::collections::{Arr, DequeAccess, DequeOps, IterDeque, Str, Tab};
 use super::engine::{glsp, with_heap, Guard, RData, RFn, RGc, Span, Sym};
 use super::error::GResult;
 use super::iter::{GIter, GIterState};
 use super::val::{Hashable, Val};
-use super::wrap::IntoVal;
+use super::wrap::{FromVal, IntoVal};
+use fnv::FnvHashMap;
+use smallvec::SmallVec;
 use std::borrow::Borrow;
 use std::cell::{Cell, RefCell, RefMut};
 use std::cmp::{max, min, Ordering};
@@ -911,6 +913,175 @@ impl GcVal {
     }
 }
 
+//-------------------------------------------------------------------------------------------------
+// RootSet
+//-------------------------------------------------------------------------------------------------
+
+/**
+A collection of values which are rooted as a group.
+
+Each [`Root`](struct.Root.html) occupies its own entry in the garbage collector's root storage,
+which is scanned during every gc step. When the host application needs to keep a large number
+of values alive (for example, every entity in a game world), storing them in a `RootSet`
+is cheaper: the whole set is registered as a single root, and its contents are traced
+together when the set itself is traced.
+
+Values are compared by identity (using [`Val::same`](enum.Val.html#method.same)) when
+they're removed from the set, or when testing whether the set contains them. Heap-allocated
+values are looked up by address in constant time. Primitive values like numbers and symbols,
+which don't need to be rooted, fall back to a linear search.
+*/
+
+pub struct RootSet {
+    arr: Root<Arr>,
+    index: RefCell<FnvHashMap<usize, SmallVec<[usize; 1]>>>, //heap address -> indexes into arr
+}
+
+impl RootSet {
+    /**
+    Constructs an empty `RootSet`.
+    */
+    pub fn new() -> RootSet {
+        RootSet {
+            arr: with_heap(|heap| heap.recycler.arr()),
+            index: RefCell::new(FnvHashMap::default()),
+        }
+    }
+
+    /**
+    Adds a value to the set.
+
+    Adding the same value more than once is permitted. It will need to be removed the same
+    number of times before it's unrooted.
+    */
+    pub fn add<V: IntoVal>(&self, val: V) -> GResult<()> {
+        let val = val.into_val()?;
+        let i = self.arr.len();
+        self.arr.push(&val)?;
+
+        if let Some(addr) = heap_addr(&val) {
+            self.index.borrow_mut().entry(addr).or_default().push(i);
+        }
+
+        Ok(())
+    }
+
+    /**
+    Removes one occurrence of a value from the set.
+
+    Returns `true` if the value was present. The order of the set's remaining values
+    may be changed.
+    */
+    pub fn remove(&self, val: &Val) -> GResult<bool> {
+        let i = match heap_addr(val) {
+            Some(addr) => {
+                let mut index = self.index.borrow_mut();
+                let positions = match index.get_mut(&addr) {
+                    Some(positions) => positions,
+                    None => return Ok(false),
+                };
+
+                let i = positions.pop().unwrap();
+                if positions.is_empty() {
+                    index.remove(&addr);
+                }
+
+                i
+            }
+            None => match self.arr.iter().position(|item| item.same(val)) {
+                Some(i) => i,
+                None => return Ok(false),
+            },
+        };
+
+        //swap_remove moves the last element into the hole, so its index entry needs updating
+        let last = self.arr.len() - 1;
+        self.arr.swap_remove::<Val>(i)?;
+
+        if i != last {
+            let moved: Val = self.arr.get(i)?;
+            if let Some(addr) = heap_addr(&moved) {
+                let mut index = self.index.borrow_mut();
+                let positions = index.get_mut(&addr).unwrap();
+                let position = positions.iter_mut().find(|p| **p == last).unwrap();
+                *position = i;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /**
+    Returns `true` if the set contains a value which is [identical](enum.Val.html#method.same)
+    to `val`.
+    */
+    pub fn contains(&self, val: &Val) -> bool {
+        match heap_addr(val) {
+            Some(addr) => self.index.borrow().contains_key(&addr),
+            None => self.arr.iter().any(|item| item.same(val)),
+        }
+    }
+
+    /**
+    Returns the number of values in the set.
+    */
+    pub fn len(&self) -> usize {
+        self.arr.len()
+    }
+
+    /**
+    Returns `true` if the set contains no values.
+    */
+    pub fn is_empty(&self) -> bool {
+        self.arr.len() == 0
+    }
+
+    /**
+    Removes all values from the set.
+    */
+    pub fn clear(&self) -> GResult<()> {
+        self.index.borrow_mut().clear();
+        self.arr.clear()
+    }
+
+    /**
+    Creates an iterator over the set's contents.
+
+    The set can't be mutated while the iterator exists.
+    */
+    pub fn iter(&self) -> IterDeque<Arr> {
+        self.arr.iter()
+    }
+}
+
+fn heap_addr(val: &Val) -> Option<usize> {
+    match *val {
+        Val::Nil | Val::Int(_) | Val::Flo(_) | Val::Char(_) | Val::Bool(_) | Val::Sym(_) => None,
+        Val::Arr(ref root) => Some(&**root as *const _ as usize),
+        Val::Str(ref root) => Some(&**root as *const _ as usize),
+        Val::Tab(ref root) => Some(&**root as *const _ as usize),
+        Val::GIter(ref root) => Some(&**root as *const _ as usize),
+        Val::Obj(ref root) => Some(&**root as *const _ as usize),
+        Val::Class(ref root) => Some(&**root as *const _ as usize),
+        Val::GFn(ref root) => Some(&**root as *const _ as usize),
+        Val::Coro(ref root) => Some(&**root as *const _ as usize),
+        Val::RData(ref root) => Some(&**root as *const _ as usize),
+        Val::RFn(ref root) => Some(&**root as *const _ as usize),
+    }
+}
+
+impl Default for RootSet {
+    fn default() -> RootSet {
+        RootSet::new()
+    }
+}
+
+impl Debug for RootSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.arr.iter()).finish()
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Slot
 //-------------------------------------------------------------------------------------------------
@@ -959,6 +1130,12 @@ impl Slot {
         }
     }
 
+    ///Equivalent to [`Val::is`](enum.Val.html#method.is). The slot is temporarily rooted.
+    #[inline]
+    pub fn is<T: FromVal>(&self) -> bool {
+        T::can_from_val(&self.root())
+    }
+
     #[inline]
     pub(crate) fn root(&self) -> Val {
         match *self {
@@ -1325,6 +1502,33 @@ pub const GC_MIN_RATIO: f32 = 1.2;
 /** Equivalent to [`(gc-value 'default-ratio)`](https://gamelisp.rs/std/gc-value). */
 pub const GC_DEFAULT_RATIO: f32 = INITIAL_U;
 
+/**
+A snapshot of the garbage collector's internal counters.
+
+Returned by [`glsp::gc_stats`](fn.gc_stats.html).
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct GcStats {
+    ///The number of objects which have not yet been identified as garbage.
+    ///
+    ///This includes young objects which may turn out to be unreachable during the next
+    ///[`glsp::gc`](fn.gc.html).
+    pub live_objects: usize,
+
+    ///Equivalent to [`glsp::gc_young_bytes`](fn.gc_young_bytes.html): the number of bytes
+    ///allocated since the last call to `glsp::gc`.
+    pub young_bytes: usize,
+
+    ///Equivalent to [`glsp::gc_old_bytes`](fn.gc_old_bytes.html).
+    pub old_bytes: usize,
+
+    ///Equivalent to [`glsp::gc_ghost_bytes`](fn.gc_ghost_bytes.html).
+    pub ghost_bytes: usize,
+
+    ///The number of incremental collection steps which have been performed by this `Runtime`.
+    pub steps: u64,
+}
+
 pub(crate) struct Heap {
     pub(crate) engine_id: u8,
     pub(crate) recycler: Recycler,
@@ -1349,6 +1553,8 @@ pub(crate) struct Heap {
     ratio_u: Cell<f32>,
     ratio_r: Cell<f32>,
     ratio_w: Cell<Option<f32>>,
+
+    steps: Cell<u64>,
 }
 
 impl Drop for Heap {
@@ -1394,6 +1600,8 @@ impl Heap {
             ratio_u: Cell::new(INITIAL_U),
             ratio_r: Cell::new(INITIAL_R),
             ratio_w: Cell::new(INITIAL_W),
+
+            steps: Cell::new(0),
         }
     }
 
@@ -1542,6 +1750,20 @@ impl Heap {
     //the caller is required to to write-barrier anything that's in the grey memory-areas (those
     //which aren't write-barriered when mutated) just before calling collect_*.
     pub(crate) fn step(&self) {
+        self.step_impl(false)
+    }
+
+    //a step which ignores the incremental targets. it traverses every gray object and frees
+    //every ghost object, then ends the cycle, turning all unreachable old objects into ghosts.
+    //two consecutive full steps will free everything which was unreachable before the first.
+    //the same write-barrier requirements apply as for step().
+    pub(crate) fn step_full(&self) {
+        self.step_impl(true)
+    }
+
+    fn step_impl(&self, full: bool) {
+        self.steps.set(self.steps.get() + 1);
+
         self.gc_in_progress.set(true);
         let _in_progress_guard = Guard::new(|| self.gc_in_progress.set(false));
 
@@ -1623,7 +1845,7 @@ impl Heap {
         let target_incr = ((self.ratio_r.get() + 1.0) * promoted_bytes as f32).ceil() as usize;
         self.black_target.set(self.black_target.get() + target_incr);
 
-        while self.old_bytes[black_index].get() < self.black_target.get()
+        while (full || self.old_bytes[black_index].get() < self.black_target.get())
             && !old_objects[gray_index].is_empty()
         {
             let erased = old_objects[gray_index].last().unwrap().clone();
@@ -1638,11 +1860,15 @@ impl Heap {
         }
 
         //if there were a nonzero number of ghost objects last cycle, ratio_w will be Some.
-        //free (promoted_bytes * W/U) bytes of ghost objects.
+        //free (promoted_bytes * W/U) bytes of ghost objects, or all of them for a full step.
         if let Some(ratio_w) = self.ratio_w.get() {
-            let bytes_to_free = (ratio_w * target_incr as f32).ceil() as usize;
-            self.ghost_target
-                .set(self.ghost_target.get().saturating_sub(bytes_to_free));
+            if full {
+                self.ghost_target.set(0);
+            } else {
+                let bytes_to_free = (ratio_w * target_incr as f32).ceil() as usize;
+                self.ghost_target
+                    .set(self.ghost_target.get().saturating_sub(bytes_to_free));
+            }
 
             while self.old_bytes[ghost_index].get() > self.ghost_target.get() {
                 let erased = old_objects[ghost_index].pop().unwrap();
@@ -1679,7 +1905,7 @@ impl Heap {
         //of old black objects, then we've reached the end of the cycle. make all white objects
         //into ghost objects, update W, and turn all black objects white.
         if old_objects[gray_index].is_empty()
-            && self.old_bytes[black_index].get() >= MIN_SURVIVING_BYTES
+            && (full || self.old_bytes[black_index].get() >= MIN_SURVIVING_BYTES)
         {
             //if there are any remaining ghost objects (unlikely unless the surviving heap has
             //sharply decreased in size), we need to change each object's color index so that
@@ -1741,6 +1967,21 @@ impl Heap {
         self.old_bytes[self.ghost_index.get()].get()
     }
 
+    pub(crate) fn stats(&self) -> GcStats {
+        let young_objects = self.young_objects.borrow().len();
+        let old_objects = self.old_objects[self.white_index.get()].borrow().len()
+            + self.old_objects[self.gray_index.get()].borrow().len()
+            + self.old_objects[self.black_index.get()].borrow().len();
+
+        GcStats {
+            live_objects: young_objects + old_objects,
+            young_bytes: self.young_memory_usage(),
+            old_bytes: self.old_memory_usage(),
+            ghost_bytes: self.ghost_memory_usage(),
+            steps: self.steps.get(),
+        }
+    }
+
     pub(crate) fn traverse_stack_slot(&self, dst: &Slot) {
         match *dst {
             Slot::Nil
@@ -1906,6 +2147,41 @@ impl Heap {
         }
     }
 
+    /*
+    used when an object which owns Gc pointers, other than an RData, is mutated. we don't have a
+    Raw which points to `src`, so we can't turn it gray as write_barrier_rdata does. instead, we
+    pass each of its Gc pointees to write_barrier, as though `src` had just started pointing to
+    them.
+    */
+    pub(crate) fn write_barrier_gcs<T, F>(&self, src: &T, trace: F)
+    where
+        T: Allocate,
+        F: FnOnce(&mut GcVisitor),
+    {
+        struct BarrierVisitor<'a, T> {
+            heap: &'a Heap,
+            src: &'a T,
+        }
+
+        impl<'a, T: Allocate> Visitor for BarrierVisitor<'a, T> {
+            fn visit_raw<U: Allocate>(&mut self, raw: &Raw<U>) {
+                self.heap.write_barrier(self.src, raw);
+            }
+
+            fn visit_gc(&mut self, root_index: u32) {
+                let root_storage = self.heap.root_storage.borrow();
+
+                if let Some(erased) = root_storage.entries[root_index as usize].raw.as_ref() {
+                    with_erased_raw!(erased, raw, self.visit_raw(raw))
+                }
+            }
+        }
+
+        if !src.header().young() {
+            trace(&mut GcVisitor(&mut BarrierVisitor { heap: self, src }));
+        }
+    }
+
     #[inline]
     pub(crate) fn memory_usage_barrier<T>(&self, src: &T, prev_usage: usize, cur_usage: usize)
     where
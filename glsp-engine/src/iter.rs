@@ -1,13 +1,14 @@
 use super::class::{Class, Obj};
 use super::code::{Coro, CoroState, GFn};
 use super::collections::{Arr, DequeAccess, DequeOps, Str, Tab};
-use super::engine::{glsp, with_heap, RData, RFn};
+use super::engine::{call_trace_callback, glsp, with_heap, RData, RFn};
 use super::error::GResult;
-use super::gc::{Allocate, Header, Raw, Root, Slot, Visitor};
+use super::gc::{Allocate, GcVisitor, Header, Raw, Root, Slot, Visitor};
 use super::val::Val;
 use super::wrap::{Callable, FromVal};
 use std::cell::RefCell;
 use std::cmp::min;
+use std::rc::Rc;
 use std::usize;
 
 //-------------------------------------------------------------------------------------------------
@@ -60,6 +61,10 @@ impl Allocate for GIter {
             Repeat1(slot) => v.visit_slot(slot),
             RepeatN(arr, ..) => v.visit_raw(arr),
             RepeatWith(callable) => visit_raw_callable(v, callable),
+            FromFn(from_fn) => {
+                let mut gc_visitor = GcVisitor(v);
+                call_trace_callback(|| from_fn.trace(&mut gc_visitor));
+            }
             AccessArr(arr, iter) => {
                 v.visit_raw(arr);
                 v.visit_raw(iter);
@@ -242,6 +247,7 @@ impl GIter {
             Repeat1(_) => Infinite,
             RepeatN(..) => Infinite,
             RepeatWith(_) => Infinite,
+            FromFn(_) => Unknown,
             AccessArr(_, ref giter) => giter.len(),
             AccessStr(_, ref giter) => giter.len(),
             AccessObj(_, ref giter) => giter.len(),
@@ -353,6 +359,7 @@ impl GIter {
             Repeat1(_) => true,
             RepeatN(..) => true,
             RepeatWith(_) => false,
+            FromFn(_) => false,
             AccessArr(_, ref giter) => giter.is_double_ended(),
             AccessStr(_, ref giter) => giter.is_double_ended(),
             AccessObj(_, ref giter) => giter.is_double_ended(),
@@ -540,6 +547,16 @@ impl GIter {
             RepeatWith(ref raw_callable) => {
                 Some(glsp::call(&raw_callable.root(), &()).map(|val| Slot::from_val(&val)))
             }
+            FromFn(ref mut from_fn) => {
+                let result = from_fn.next();
+                with_heap(|heap| heap.write_barrier_gcs(self, |v| from_fn.trace(v)));
+
+                match result {
+                    Ok(Some(val)) => Some(Ok(Slot::from_val(&val))),
+                    Ok(None) => None,
+                    Err(err) => Some(Err(err)),
+                }
+            }
             AccessArr(ref arr, ref giter) => {
                 let item = giter.raw_next();
                 if let Some(Ok(item)) = item {
@@ -985,6 +1002,7 @@ impl GIter {
                 Some(Ok(element))
             }
             RepeatWith(_) => Some(Err(error!("repeat-with iterators are not double-ended"))),
+            FromFn(_) => Some(Err(error!("giter_from_fn iterators are not double-ended"))),
             AccessArr(ref arr, ref giter) => {
                 let item = giter.raw_next_back();
                 if let Some(Ok(item)) = item {
@@ -1241,6 +1259,7 @@ impl GIter {
             OnceWith(_) => "once-with",
             Repeat1(_) | RepeatN(_, _, _) => "repeat",
             RepeatWith(_) => "repeat-with",
+            FromFn(_) => "from-fn",
             AccessArr(_, _) => "access-arr",
             AccessStr(_, _) => "access-str",
             AccessObj(_, _) => "access-obj",
@@ -1319,6 +1338,9 @@ pub(crate) enum GIterState {
     RepeatN(Raw<Arr>, u32, u32), //elems, next_i, next_back_i
     RepeatWith(RawCallable),
 
+    //created by glsp::giter_from_fn()
+    FromFn(Box<dyn FromFnState>),
+
     AccessArr(Raw<Arr>, Raw<GIter>),
     AccessStr(Raw<Str>, Raw<GIter>),
     AccessObj(Raw<Obj>, Raw<GIter>),
@@ -1433,6 +1455,48 @@ impl RawCallable {
     }
 }
 
+//the state of a glsp::giter_from_fn() iterator. when the GIter is cloned, its state is cloned,
+//but its closure and trace callback are shared
+pub(crate) trait FromFnState {
+    fn next(&mut self) -> GResult<Option<Val>>;
+    fn trace(&self, visitor: &mut GcVisitor);
+    fn clone_box(&self) -> Box<dyn FromFnState>;
+}
+
+impl Clone for Box<dyn FromFnState> {
+    fn clone(&self) -> Box<dyn FromFnState> {
+        self.clone_box()
+    }
+}
+
+pub(crate) struct FromFn<S, F> {
+    pub(crate) state: S,
+    pub(crate) trace: fn(&S, &mut GcVisitor),
+    pub(crate) f: Rc<F>,
+}
+
+impl<S, F> FromFnState for FromFn<S, F>
+where
+    S: Clone + 'static,
+    F: Fn(&mut S) -> GResult<Option<Val>> + 'static,
+{
+    fn next(&mut self) -> GResult<Option<Val>> {
+        (self.f)(&mut self.state)
+    }
+
+    fn trace(&self, visitor: &mut GcVisitor) {
+        (self.trace)(&self.state, visitor)
+    }
+
+    fn clone_box(&self) -> Box<dyn FromFnState> {
+        Box::new(FromFn {
+            state: self.state.clone(),
+            trace: self.trace,
+            f: Rc::clone(&self.f),
+        })
+    }
+}
+
 fn visit_raw_callable<V: Visitor>(v: &mut V, raw_callable: &RawCallable) {
     match raw_callable {
         RawCallable::RFn(rfn) => v.visit_raw(rfn),
@@ -2,9 +2,13 @@
 #![allow(clippy::comparison_chain)]
 #![allow(clippy::float_cmp)]
 #![allow(clippy::useless_format)]
-#![feature(min_specialization)]
+#![feature(fn_traits)]
+#![feature(specialization)]
+#![allow(incomplete_features)]
 #![feature(rustc_attrs)]
+#![feature(tuple_trait)]
 #![feature(unboxed_closures)]
+#![feature(unsize)]
 #![doc(html_root_url = "https://docs.rs/glsp/0.2")]
 
 #[macro_use]
@@ -34,27 +38,38 @@ mod lex;
 mod parse;
 mod print;
 mod serde;
+mod snapshot;
 mod transform;
 mod vm;
 
 pub use self::{
     class::{Class, Obj},
-    code::{Coro, CoroState, GFn},
+    code::{Coro, CoroState, CoroStatus, GFn, RunOutcome},
     collections::{
-        Arr, Deque, DequeAccess, DequeAccessRange, DequeIndex, DequeOps, DequeRange, FromElement,
-        IntoElement, IterDeque, IterDequeTo, IterTab, IterTabKeys, IterTabKeysTo, IterTabTo,
-        IterTabValues, IterTabValuesTo, Splay, Str, Tab, TabEntries,
+        Arr, Deque, DequeAccess, DequeAccessRange, DequeIndex, DequeOps, DequeRange, FieldColumns,
+        FromElement, IntoElement, IterDeque, IterDequeTo, IterTab, IterTabKeys, IterTabKeysTo,
+        IterTabTo, IterTabValues, IterTabValuesTo, Splay, Str, StrChars, Tab, TabEntries,
+        TabEntry,
     },
     engine::{
-        with_lazy_val, EprWriter, PrWriter, RClass, RClassBuilder, RData, RFn, RGc, RGlobal,
-        RGlobalRef, RGlobalRefMut, RRef, RRefMut, RRoot, Sym, ToSym,
+        with_lazy_val, EprWriter, PrWriter, RAnyRef, RAnyRefMut, RClass, RClassBuilder, RData,
+        RFn, RGc, RGlobal, RGlobalRef, RGlobalRefMut, RRef, RRefMut, RRoot, RTrait, Sym, SymKind,
+        ToSym,
     },
     error::{GError, GResult},
     eval::{EnvMode, Expander, Expansion},
-    gc::{Allocate, Gc, GcVal, GcVisitor, Root, GC_DEFAULT_RATIO, GC_MIN_RATIO},
+    gc::{
+        Allocate, Gc, GcStats, GcVal, GcVisitor, Root, RootSet, GC_DEFAULT_RATIO, GC_MIN_RATIO,
+    },
     iter::{GIter, GIterLen, Iterable, IterableOps},
+    parse::{Reader, SourceSpan, SpanTable},
+    snapshot::HeapSerializer,
     val::{Hashable, Num, Val},
-    wrap::{Callable, CallableOps, FromVal, IntoCallArgs, IntoVal, Rest, WrappedCall, Wrapper},
+    wrap::{
+        ArgDefaults, ArgType, ArgsFromIter, Blob, Callable, CallableOps, Either, FnMutCell,
+        FromVal, IntoCallArgs, IntoErrorVal, IntoVal, IntoValExt, KeyedTuple, OwnedRest, Rest,
+        RestRef, RestRefIter, StrictFlo, WrappedCall, Wrapper,
+    },
 };
 
 pub use self::engine::glsp::*;
@@ -62,7 +77,7 @@ pub use self::engine::glsp::*;
 //undocumented apis required by the glsp-stdlib crate or by macros
 #[doc(hidden)]
 pub use self::{
-    engine::{stock_syms, Engine, EngineBuilder, Span, SymKind},
+    engine::{stock_syms, Engine, EngineBuilder, Span},
     gc::Slot,
     parse::Parser,
     print::{dump_fn, dump_form, dump_macro},
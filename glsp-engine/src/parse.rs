@@ -1,12 +1,15 @@
 use super::collections::{Arr, DequeOps, Str, Tab};
-use super::engine::{glsp, stock_syms::*, Filename, Span, SpanStorage, Sym};
+use super::engine::{glsp, stock_syms::*, Filename, Guard, Span, SpanStorage, Sym};
 use super::error::GResult;
 use super::gc::Root;
 use super::lex::{char_is_whitespace, Lexer, StrStatus, TokType};
-use super::val::Val;
+use super::val::{Num, Val};
+use super::vm::{Frame, GlspApiName};
+use fnv::FnvHashMap;
 use smallvec::SmallVec;
 use std::cell::Cell;
 use std::convert::TryFrom;
+use std::io::{BufRead, BufReader, Read};
 use std::str::{self, FromStr};
 
 /*
@@ -26,6 +29,12 @@ pub struct Parser {
     stack: SmallVec<[Form; 32]>,
     prev_tok_type: TokType,
     file: Option<Filename>,
+
+    //source positions are only tracked when recording spans for glsp::parse_with_spans. `starts`
+    //runs parallel to `stack`, storing the position of each partial form's opening token.
+    spans: Option<SpanTable>,
+    starts: SmallVec<[SourcePos; 32]>,
+    pos: SourcePos,
 }
 
 impl Parser {
@@ -35,9 +44,28 @@ impl Parser {
             stack: SmallVec::new(),
             prev_tok_type: TokType::Whitespace,
             file,
+            spans: None,
+            starts: SmallVec::new(),
+            pos: SourcePos {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+        }
+    }
+
+    //starts recording the source location of each form parsed from now on
+    pub fn record_spans(&mut self) {
+        if self.spans.is_none() {
+            self.spans = Some(SpanTable::new());
+            self.starts.clear();
         }
     }
 
+    pub fn take_spans(&mut self) -> Option<SpanTable> {
+        self.spans.take()
+    }
+
     pub fn parse_all(&mut self, mut text: &str, dst: &mut Vec<Val>) -> GResult<usize> {
         let starting_len = dst.len();
 
@@ -84,6 +112,250 @@ impl Parser {
     }
 }
 
+/**
+A streaming reader which parses forms from a [`Read`][0].
+
+[0]: https://doc.rust-lang.org/std/io/trait.Read.html
+
+`Reader` is an iterator which yields each toplevel form as it's parsed. The input is
+buffered one line at a time, so it's possible to process a very large data file without
+loading all of its text into memory. It uses the same parser as
+[`glsp::parse_all`](fn.parse_all.html), so the forms are identical.
+
+Each line is buffered in full before it's parsed, so a file which has very long lines (for
+example, a large data file written without any line breaks) will still be read into memory
+all at once.
+
+When a filename is provided, it's used to report the line number of any syntax errors. The
+iterator stops after it yields an error. Input which isn't valid utf-8 is an error.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+# Engine::new().run(|| {
+#
+let data = "(1 2 3)\n(4 5\n 6)\n".as_bytes();
+
+let mut total = 0;
+for form in Reader::new(data, Some("data.glsp")) {
+    let arr = Root::<Arr>::from_val(&form?)?;
+    total += arr.len();
+}
+
+assert_eq!(total, 6);
+#
+# Ok(()) }).unwrap();
+```
+*/
+pub struct Reader<R: Read> {
+    input: BufReader<R>,
+    parser: Parser,
+    line: String,
+    line_pos: usize,
+    finished: bool,
+}
+
+impl<R: Read> Reader<R> {
+    /**
+    Creates a new `Reader`.
+
+    Like most of the `glsp` API, this function must be called while a `Runtime` is active.
+    */
+    pub fn new(input: R, filename: Option<&str>) -> Reader<R> {
+        let file_id = filename.map(|path| glsp::filename(path));
+
+        Reader {
+            input: BufReader::new(input),
+            parser: Parser::new(file_id),
+            line: String::new(),
+            line_pos: 0,
+            finished: false,
+        }
+    }
+
+    fn read_form(&mut self) -> GResult<Option<Val>> {
+        loop {
+            //the parser requires that each str passed to it ends with a newline, unless it's
+            //the end of the input. read_line() gives us that guarantee.
+            if self.line_pos < self.line.len() {
+                let mut text = &self.line[self.line_pos..];
+                let result = self.parser.parse(&mut text);
+                self.line_pos = self.line.len() - text.len();
+
+                if let Some(form) = result? {
+                    return Ok(Some(form));
+                }
+            } else {
+                self.line.clear();
+                self.line_pos = 0;
+
+                match self.input.read_line(&mut self.line) {
+                    Ok(0) => {
+                        self.parser.ensure_finished()?;
+                        return Ok(None);
+                    }
+                    Ok(_) => (),
+                    Err(err) => {
+                        return Err(error!("Reader failed to read its input").with_source(err))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = GResult<Val>;
+
+    fn next(&mut self) -> Option<GResult<Val>> {
+        if self.finished {
+            return None;
+        }
+
+        glsp::push_frame(Frame::GlspApi(GlspApiName::ReaderNext, self.parser.file));
+        let _guard = Guard::new(glsp::pop_frame);
+
+        match self.read_form() {
+            Ok(Some(form)) => Some(Ok(form)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/**
+The location of a form within its source text.
+
+Produced by [`glsp::parse_with_spans`](fn.parse_with_spans.html).
+
+`start` and `end` are byte offsets into the source text, with `end` being exclusive.
+`line` and `column` are the one-based position of the form's first character, with columns
+counted in chars rather than bytes.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Copy, Clone)]
+struct SourcePos {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl SourcePos {
+    fn advance(&mut self, text: &str) {
+        self.offset += text.len();
+        for ch in text.chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+    }
+
+    fn span_to(&self, end: SourcePos) -> SourceSpan {
+        SourceSpan {
+            start: self.offset,
+            end: end.offset,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+/**
+A side-table which records where each form produced by the reader came from.
+
+Produced by [`glsp::parse_with_spans`](fn.parse_with_spans.html).
+
+Atoms like ints and syms don't have an identity, so their locations are recorded positionally:
+each element of an arr has a span which can be queried using
+[`element_span`](#method.element_span), and each toplevel form has a span which can be queried
+using [`form_span`](#method.form_span). Arrs are also recorded by identity, so that an arr
+produced by the reader can be located using [`arr_span`](#method.arr_span).
+
+Elements which don't appear in the source text, like the `quote` symbol in `'x`, the `access`
+symbol in `[x y]`, or the literal fragments of a template str, have no span. The contents of a
+tab are only recorded when they're arrs.
+*/
+pub struct SpanTable {
+    forms: Vec<SourceSpan>,
+    arr_spans: FnvHashMap<usize, SourceSpan>,
+    element_spans: FnvHashMap<usize, Vec<Option<SourceSpan>>>,
+
+    //keeps each recorded arr alive, so that its address can't be reused
+    arrs: Vec<Root<Arr>>,
+}
+
+impl SpanTable {
+    fn new() -> SpanTable {
+        SpanTable {
+            forms: Vec::new(),
+            arr_spans: FnvHashMap::default(),
+            element_spans: FnvHashMap::default(),
+            arrs: Vec::new(),
+        }
+    }
+
+    /**
+    Returns the span of the toplevel form at index `i`, or `None` if `i` is out of bounds.
+    */
+    pub fn form_span(&self, i: usize) -> Option<SourceSpan> {
+        self.forms.get(i).copied()
+    }
+
+    /**
+    Returns the span of an arr which was produced by the reader.
+    */
+    pub fn arr_span(&self, arr: &Root<Arr>) -> Option<SourceSpan> {
+        self.arr_spans.get(&arr_id(arr)).copied()
+    }
+
+    /**
+    Returns the span of the element at index `i` of an arr which was produced by the reader.
+    */
+    pub fn element_span(&self, arr: &Root<Arr>, i: usize) -> Option<SourceSpan> {
+        self.element_spans
+            .get(&arr_id(arr))
+            .and_then(|spans| spans.get(i).copied().flatten())
+    }
+
+    fn record_val(&mut self, val: &Val, span: SourceSpan) {
+        if let Val::Arr(ref arr) = *val {
+            if self.arr_spans.insert(arr_id(arr), span).is_none() {
+                self.arrs.push(arr.clone());
+            }
+        }
+    }
+
+    fn record_element(&mut self, parent: &Root<Arr>, i: usize, val: &Val, span: SourceSpan) {
+        let spans = self.element_spans.entry(arr_id(parent)).or_default();
+        if spans.len() <= i {
+            spans.resize(i + 1, None);
+        }
+        spans[i] = Some(span);
+
+        self.record_val(val, span);
+    }
+}
+
+fn arr_id(arr: &Root<Arr>) -> usize {
+    &**arr as *const Arr as usize
+}
+
 //an incompletely-parsed form
 enum Form {
     Arr(Root<Arr>),
@@ -129,6 +401,12 @@ fn parse(parser: &mut Parser, text: &mut &str) -> GResult<Option<Val>> {
         Err(err) => return Err(error_at!(span(), "lexing error").with_source(err)),
     };
 
+    let recording = parser.spans.is_some();
+    let tok_start = parser.pos;
+    if recording {
+        parser.pos.advance(tok.text);
+    }
+
     //many tokens are delimited: they must be followed by ), ], }, or whitespace. this prevents a
     //number of syntax corner-cases such as (a"b""c"), (prn #t#f\c10), or \retuurn
     let delimited = match parser.prev_tok_type {
@@ -364,21 +642,51 @@ fn parse(parser: &mut Parser, text: &mut &str) -> GResult<Option<Val>> {
         }
     };
 
+    //when recording spans, bring `starts` back in sync with the stack. a token pushes at most
+    //one partial form, which begins at this token. if a partial form was popped, its start is
+    //the start of the value which it produced.
+    let mut val_start = tok_start;
+    if recording {
+        while parser.starts.len() > parser.stack.len() {
+            val_start = parser.starts.pop().unwrap();
+        }
+        while parser.starts.len() < parser.stack.len() {
+            parser.starts.push(tok_start);
+        }
+    }
+
     //if we haven't produced a value, we're finished. if we have, inspect the stack to decide
     //what we should do with it. if the stack is empty, we return our val to the caller as a
     //toplevel form.
     while let Some(ref val) = parsed_val {
+        let val_span = val_start.span_to(parser.pos);
+
         let to_pop = match parser.stack.last_mut() {
-            None => return Ok(Some(val.clone())),
+            None => {
+                if let Some(ref mut spans) = parser.spans {
+                    spans.record_val(val, val_span);
+                    spans.forms.push(val_span);
+                }
+
+                return Ok(Some(val.clone()));
+            }
             Some(&mut Form::Arr(ref arr))
             | Some(&mut Form::PausedStr(ref arr))
             | Some(&mut Form::Access(ref arr)) => {
+                if let Some(ref mut spans) = parser.spans {
+                    spans.record_element(arr, arr.len(), val, val_span);
+                }
+
                 arr.push(val)?;
                 parsed_val = None;
                 false
             }
             Some(&mut Form::Tab(_)) => bail_at!(span(), "invalid clause in tab literal"),
             Some(&mut Form::TabClause(ref mut key, ref mut value)) => {
+                if let Some(ref mut spans) = parser.spans {
+                    spans.record_val(val, val_span);
+                }
+
                 match (key.is_some(), value.is_some()) {
                     (false, false) => *key = Some(val.clone()),
                     (true, false) => *value = Some(val.clone()),
@@ -396,6 +704,11 @@ fn parse(parser: &mut Parser, text: &mut &str) -> GResult<Option<Val>> {
                 let arr = glsp::arr_with_capacity(2);
                 arr.set_span(span());
                 arr.push(abbrv_sym)?;
+
+                if let Some(ref mut spans) = parser.spans {
+                    spans.record_element(&arr, 1, val, val_span);
+                }
+
                 arr.push(val)?;
 
                 arr.freeze();
@@ -409,6 +722,10 @@ fn parse(parser: &mut Parser, text: &mut &str) -> GResult<Option<Val>> {
 
         if to_pop {
             parser.stack.pop().unwrap();
+
+            if recording {
+                val_start = parser.starts.pop().unwrap();
+            }
         }
     }
 
@@ -427,6 +744,17 @@ fn parse_num_or_sym(text: &str) -> GResult<Val> {
     Ok(val)
 }
 
+//parses a num using the reader's syntax for int and flo literals. used by Val::coerce_to_num
+pub(crate) fn parse_num(text: &str) -> Option<Num> {
+    if text.is_empty() {
+        None
+    } else if let Some(i) = parse_int(text) {
+        Some(Num::Int(i))
+    } else {
+        parse_flo(text).map(Num::Flo)
+    }
+}
+
 fn parse_int(mut text: &str) -> Option<i32> {
     //we closely follow rust's own grammar rules here, except for the _u32 etc. suffixes
     let sign = if text.starts_with('-') {
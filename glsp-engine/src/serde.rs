@@ -1,15 +1,21 @@
 #![cfg(feature = "serde")]
 
-use super::collections::{Arr, DequeOps, Str, Tab};
+use super::collections::{Arr, DequeAccess, DequeOps, Str, Tab};
 use super::engine::{glsp, Sym};
+use super::error::{GError, GResult};
 use super::gc::{Allocate, Raw, Root, Slot};
 use super::val::Val;
+use super::wrap::{FromVal, IntoVal};
 use serde::de::{
-    Deserialize, Deserializer, EnumAccess, Error as DeError, MapAccess, SeqAccess, VariantAccess,
-    Visitor,
+    Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error as DeError, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
 };
-use serde::ser::{Error as SerError, Serialize, SerializeMap, SerializeSeq, Serializer};
-use std::fmt;
+use serde::ser::{
+    Error as SerError, Serialize, SerializeMap, SerializeSeq, SerializeStruct,
+    SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+    Serializer,
+};
+use std::fmt::{self, Display};
 use std::rc::Rc;
 
 /*
@@ -21,6 +27,10 @@ it pulls in the `serde` crate as a dependency, but not `serde_derive` (because t
 in `proc_macro2`, `quote` and `syn`). we do depend on `serde_derive` if the "compiler" feature is
 enabled, to make it easier to define Serialize/Deserialize for Bytecode, Lambda, etc.
 
+it also contains ValSerializer, a Serializer which converts any Serialize type into a Val, and
+ValDeserializer, which performs the opposite conversion. they're the backends for glsp::to_val
+and glsp::from_val.
+
 */
 
 //-------------------------------------------------------------------------------------------------
@@ -373,3 +383,685 @@ impl<'de> Deserialize<'de> for Root<Tab> {
         d.deserialize_map(RootTabVisitor)
     }
 }
+
+//-------------------------------------------------------------------------------------------------
+// ValSerializer
+//-------------------------------------------------------------------------------------------------
+
+impl SerError for GError {
+    fn custom<T: Display>(msg: T) -> GError {
+        GError::from_str(&msg.to_string())
+    }
+}
+
+/*
+a Serializer which produces a Val, rather than serialized data. the representation is:
+    - bool, char and strings are converted to bools, chars and strs
+    - integers are converted to ints. integers outside the range of an i32 are an error, just
+      like the IntoVal impls for i64, u32, etc.
+    - f32 and f64 are converted to flos. an f64 is truncated to an f32, losing precision
+    - None, (), unit structs and byte arrays follow their IntoVal conversions. Some(x) and
+      newtype structs are transparent
+    - unit variants are converted to their name, as a sym
+    - sequences, tuples and tuple structs are converted to arrs
+    - maps are converted to tabs, and structs are converted to tabs keyed by field-name syms
+    - newtype, tuple and struct variants are converted to an arr which starts with the
+      variant's name, as a sym, followed by the payload: (name x), (name x y z), (name tab)
+*/
+pub(crate) struct ValSerializer;
+
+impl Serializer for ValSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    type SerializeSeq = ArrSerializer;
+    type SerializeTuple = ArrSerializer;
+    type SerializeTupleStruct = ArrSerializer;
+    type SerializeTupleVariant = ArrSerializer;
+    type SerializeMap = TabSerializer;
+    type SerializeStruct = TabSerializer;
+    type SerializeStructVariant = TabSerializer;
+
+    fn serialize_bool(self, v: bool) -> GResult<Val> {
+        Ok(Val::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> GResult<Val> {
+        Ok(Val::Int(v as i32))
+    }
+
+    fn serialize_i16(self, v: i16) -> GResult<Val> {
+        Ok(Val::Int(v as i32))
+    }
+
+    fn serialize_i32(self, v: i32) -> GResult<Val> {
+        Ok(Val::Int(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_i128(self, v: i128) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_u8(self, v: u8) -> GResult<Val> {
+        Ok(Val::Int(v as i32))
+    }
+
+    fn serialize_u16(self, v: u16) -> GResult<Val> {
+        Ok(Val::Int(v as i32))
+    }
+
+    fn serialize_u32(self, v: u32) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_u64(self, v: u64) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_u128(self, v: u128) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_f32(self, v: f32) -> GResult<Val> {
+        Ok(Val::Flo(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> GResult<Val> {
+        Ok(Val::Flo(v as f32))
+    }
+
+    fn serialize_char(self, v: char) -> GResult<Val> {
+        Ok(Val::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> GResult<Val> {
+        Ok(Val::Str(glsp::str_from_rust_str(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> GResult<Val> {
+        v.into_val()
+    }
+
+    fn serialize_none(self) -> GResult<Val> {
+        Ok(Val::Nil)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> GResult<Val> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> GResult<Val> {
+        Ok(Val::Nil)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> GResult<Val> {
+        Ok(Val::Nil)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> GResult<Val> {
+        Ok(Val::Sym(glsp::sym(variant)?))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> GResult<Val> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> GResult<Val> {
+        let arr = glsp::arr_with_capacity(2);
+        arr.push(glsp::sym(variant)?)?;
+        arr.push(value.serialize(ValSerializer)?)?;
+        Ok(Val::Arr(arr))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> GResult<ArrSerializer> {
+        Ok(ArrSerializer {
+            arr: glsp::arr_with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> GResult<ArrSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> GResult<ArrSerializer> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> GResult<ArrSerializer> {
+        let arr = glsp::arr_with_capacity(len + 1);
+        arr.push(glsp::sym(variant)?)?;
+        Ok(ArrSerializer { arr })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> GResult<TabSerializer> {
+        Ok(TabSerializer {
+            tab: glsp::tab_with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> GResult<TabSerializer> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> GResult<TabSerializer> {
+        Ok(TabSerializer {
+            tab: glsp::tab_with_capacity(len),
+            key: None,
+            variant: Some(glsp::sym(variant)?),
+        })
+    }
+}
+
+pub(crate) struct ArrSerializer {
+    arr: Root<Arr>,
+}
+
+impl SerializeSeq for ArrSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> GResult<()> {
+        self.arr.push(value.serialize(ValSerializer)?)
+    }
+
+    fn end(self) -> GResult<Val> {
+        Ok(Val::Arr(self.arr))
+    }
+}
+
+impl SerializeTuple for ArrSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> GResult<()> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> GResult<Val> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleStruct for ArrSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> GResult<()> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> GResult<Val> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleVariant for ArrSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> GResult<()> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> GResult<Val> {
+        SerializeSeq::end(self)
+    }
+}
+
+pub(crate) struct TabSerializer {
+    tab: Root<Tab>,
+    key: Option<Val>,
+    variant: Option<Sym>,
+}
+
+impl SerializeMap for TabSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> GResult<()> {
+        self.key = Some(key.serialize(ValSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> GResult<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.tab.set(key, value.serialize(ValSerializer)?)
+    }
+
+    fn end(self) -> GResult<Val> {
+        SerializeStruct::end(self)
+    }
+}
+
+impl SerializeStruct for TabSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> GResult<()> {
+        self.tab
+            .set(glsp::sym(key)?, value.serialize(ValSerializer)?)
+    }
+
+    fn end(self) -> GResult<Val> {
+        match self.variant {
+            Some(variant) => {
+                let arr = glsp::arr_with_capacity(2);
+                arr.push(variant)?;
+                arr.push(self.tab)?;
+                Ok(Val::Arr(arr))
+            }
+            None => Ok(Val::Tab(self.tab)),
+        }
+    }
+}
+
+impl SerializeStructVariant for TabSerializer {
+    type Ok = Val;
+    type Error = GError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> GResult<()> {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> GResult<Val> {
+        SerializeStruct::end(self)
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// ValDeserializer
+//-------------------------------------------------------------------------------------------------
+
+impl DeError for GError {
+    fn custom<T: Display>(msg: T) -> GError {
+        GError::from_str(&msg.to_string())
+    }
+}
+
+/*
+a Deserializer which reads from a Val. it accepts the same representation which is produced by
+ValSerializer. in addition, ints can be deserialized as floats, syms can be deserialized as
+strings, and strs can be used as the name of a unit variant.
+
+leaf type errors are produced by the FromVal impls where possible, so that they have the same
+wording: "expected f32, received a str". errors in nested values are qualified with their path,
+using GError::prepend_path_index and GError::prepend_path_key.
+*/
+pub(crate) struct ValDeserializer(pub(crate) Val);
+
+macro_rules! deserialize_from_val {
+    ($(($method:ident, $visit:ident, $t:ty)),+) => (
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+                visitor.$visit(<$t>::from_val(&self.0)?)
+            }
+        )+
+    );
+}
+
+impl<'de> Deserializer<'de> for ValDeserializer {
+    type Error = GError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Nil => visitor.visit_unit(),
+            Val::Int(i) => visitor.visit_i32(i),
+            Val::Flo(f) => visitor.visit_f32(f),
+            Val::Char(c) => visitor.visit_char(c),
+            Val::Bool(b) => visitor.visit_bool(b),
+            Val::Sym(sym) => visitor.visit_string(sym.name().to_string()),
+            Val::Str(ref st) => visitor.visit_string(st.to_string()),
+            Val::Arr(ref arr) => visitor.visit_seq(ArrDeserializer::new(arr.clone(), 0)),
+            Val::Tab(ref tab) => visitor.visit_map(TabDeserializer::new(tab)),
+            ref val => bail!("unable to deserialize {}", val.a_type_name()),
+        }
+    }
+
+    deserialize_from_val!(
+        (deserialize_bool, visit_bool, bool),
+        (deserialize_i8, visit_i8, i8),
+        (deserialize_i16, visit_i16, i16),
+        (deserialize_i32, visit_i32, i32),
+        (deserialize_i64, visit_i64, i64),
+        (deserialize_i128, visit_i128, i128),
+        (deserialize_u8, visit_u8, u8),
+        (deserialize_u16, visit_u16, u16),
+        (deserialize_u32, visit_u32, u32),
+        (deserialize_u64, visit_u64, u64),
+        (deserialize_u128, visit_u128, u128),
+        (deserialize_char, visit_char, char),
+        (deserialize_bytes, visit_byte_buf, Vec<u8>),
+        (deserialize_byte_buf, visit_byte_buf, Vec<u8>)
+    );
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Int(i) => visitor.visit_f32(i as f32),
+            ref val => visitor.visit_f32(f32::from_val(val)?),
+        }
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Int(i) => visitor.visit_f64(i as f64),
+            ref val => visitor.visit_f64(f64::from_val(val)?),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Str(ref st) => visitor.visit_string(st.to_string()),
+            Val::Sym(sym) => visitor.visit_string(sym.name().to_string()),
+            ref val => bail!("expected a str, received {}", val.a_type_name()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Nil => visitor.visit_unit(),
+            ref val => bail!("expected nil, received {}", val.a_type_name()),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> GResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> GResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Arr(ref arr) => visitor.visit_seq(ArrDeserializer::new(arr.clone(), 0)),
+            ref val => bail!("expected an arr, received {}", val.a_type_name()),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Arr(ref arr) => {
+                ensure!(
+                    arr.len() == len,
+                    "expected an arr of length {}, received an arr of length {}",
+                    len,
+                    arr.len()
+                );
+                visitor.visit_seq(ArrDeserializer::new(arr.clone(), 0))
+            }
+            ref val => bail!("expected an arr, received {}", val.a_type_name()),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> GResult<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        match self.0 {
+            Val::Tab(ref tab) => visitor.visit_map(TabDeserializer::new(tab)),
+            ref val => bail!("expected a tab, received {}", val.a_type_name()),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> GResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> GResult<V::Value> {
+        match self.0 {
+            Val::Sym(_) | Val::Str(_) => visitor.visit_enum(EnumDeserializer {
+                variant: self.0.clone(),
+                arr: None,
+            }),
+            Val::Arr(ref arr) if arr.len() > 0 => visitor.visit_enum(EnumDeserializer {
+                variant: arr.get::<Val>(0)?,
+                arr: Some(arr.clone()),
+            }),
+            ref val => bail!(
+                "expected a sym or a non-empty arr, received {}",
+                val.a_type_name()
+            ),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> GResult<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct ArrDeserializer {
+    arr: Root<Arr>,
+    i: usize,
+}
+
+impl ArrDeserializer {
+    fn new(arr: Root<Arr>, start: usize) -> ArrDeserializer {
+        ArrDeserializer { arr, i: start }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArrDeserializer {
+    type Error = GError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> GResult<Option<T::Value>> {
+        if self.i >= self.arr.len() {
+            return Ok(None);
+        }
+
+        let i = self.i;
+        self.i += 1;
+
+        let val: Val = self.arr.get(i)?;
+        match seed.deserialize(ValDeserializer(val)) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(err.prepend_path_index(i)),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.arr.len().saturating_sub(self.i))
+    }
+}
+
+struct TabDeserializer {
+    entries: Vec<(Val, Val)>,
+    i: usize,
+}
+
+impl TabDeserializer {
+    fn new(tab: &Root<Tab>) -> TabDeserializer {
+        TabDeserializer {
+            entries: tab.entries().iter().collect(),
+            i: 0,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for TabDeserializer {
+    type Error = GError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> GResult<Option<K::Value>> {
+        if self.i >= self.entries.len() {
+            return Ok(None);
+        }
+
+        let key = self.entries[self.i].0.clone();
+        match seed.deserialize(ValDeserializer(key.clone())) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(err.prepend_path_key(&key)),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> GResult<V::Value> {
+        let (key, value) = self.entries[self.i].clone();
+        self.i += 1;
+
+        seed.deserialize(ValDeserializer(value))
+            .map_err(|err| err.prepend_path_key(&key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len() - self.i)
+    }
+}
+
+struct EnumDeserializer {
+    variant: Val,
+    arr: Option<Root<Arr>>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = GError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> GResult<(V::Value, VariantDeserializer)> {
+        let value = seed.deserialize(ValDeserializer(self.variant))?;
+        Ok((value, VariantDeserializer { arr: self.arr }))
+    }
+}
+
+//the payload of an enum variant represented as an arr, (name ...), starts at index 1
+struct VariantDeserializer {
+    arr: Option<Root<Arr>>,
+}
+
+impl VariantDeserializer {
+    fn payload(&self, len: usize) -> GResult<Root<Arr>> {
+        match self.arr {
+            Some(ref arr) if arr.len() == len + 1 => Ok(arr.clone()),
+            Some(ref arr) => bail!(
+                "expected an enum variant with {} fields, received {}",
+                len,
+                arr.len() - 1
+            ),
+            None => bail!(
+                "expected an enum variant with {} fields, received a sym",
+                len
+            ),
+        }
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = GError;
+
+    fn unit_variant(self) -> GResult<()> {
+        match self.arr {
+            Some(ref arr) if arr.len() != 1 => {
+                bail!(
+                    "expected a unit enum variant, received {} fields",
+                    arr.len() - 1
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> GResult<T::Value> {
+        let arr = self.payload(1)?;
+        seed.deserialize(ValDeserializer(arr.get(1)?))
+            .map_err(|err| err.prepend_path_index(1))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> GResult<V::Value> {
+        let arr = self.payload(len)?;
+        visitor.visit_seq(ArrDeserializer::new(arr, 1))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> GResult<V::Value> {
+        let arr = self.payload(1)?;
+        ValDeserializer(arr.get(1)?)
+            .deserialize_map(visitor)
+            .map_err(|err| err.prepend_path_index(1))
+    }
+}
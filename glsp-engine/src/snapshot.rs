@@ -0,0 +1,632 @@
+use super::class::Obj;
+use super::collections::{Arr, DequeOps, Str, Tab};
+use super::engine::{glsp, RData, Sym};
+use super::error::GResult;
+use super::gc::Root;
+use super::val::Val;
+use fnv::FnvHashMap;
+use std::convert::TryFrom;
+use std::vec;
+
+/*
+
+this module implements glsp::serialize_heap and glsp::deserialize_heap, which convert a graph of
+gc values into a self-contained byte buffer and back again.
+
+unlike the serde impls, which can only represent trees, the snapshot format preserves shared
+references and cycles. each distinct heap object reachable from the root is assigned an index
+in an object table; every reference to that object is encoded as its index. the layout is:
+
+    - the magic bytes b"GLSPHEAP", followed by a one-byte format version
+    - the number of objects, and the number of distinct gensyms, as u32s
+    - one entry for each object, in index order
+    - the root value
+
+each arr, str and tab entry begins with a flag byte, recording whether the object is frozen.
+all integers are little-endian. objs and rdata don't have a generic representation, so they're
+converted to and from a payload value by the HeapSerializer hooks. the payload is stored in the
+object table, so that identity is preserved even when the hooks are only called once.
+
+*/
+
+const MAGIC: &[u8] = b"GLSPHEAP";
+const VERSION: u8 = 1;
+
+const VAL_NIL: u8 = 0;
+const VAL_INT: u8 = 1;
+const VAL_FLO: u8 = 2;
+const VAL_CHAR: u8 = 3;
+const VAL_BOOL: u8 = 4;
+const VAL_SYM: u8 = 5;
+const VAL_GENSYM: u8 = 6;
+const VAL_REF: u8 = 7;
+
+const ENTRY_ARR: u8 = 0;
+const ENTRY_STR: u8 = 1;
+const ENTRY_TAB: u8 = 2;
+const ENTRY_RDATA: u8 = 3;
+const ENTRY_OBJ: u8 = 4;
+
+/**
+Hooks which customize [`glsp::serialize_heap_with`](fn.serialize_heap_with.html) and
+[`glsp::deserialize_heap_with`](fn.deserialize_heap_with.html).
+
+[Objects](struct.Obj.html) and [Rust data](struct.RData.html) can't be serialized generically.
+Instead, each object or rdata is converted into a "payload" value, which is serialized in its
+place. During deserialization, the payload is converted back into an object or rdata.
+
+The payload may be any value which `serialize_heap` supports, including arrays and tables
+which refer to other parts of the heap. However, a payload must not refer back to the
+object or rdata which produced it.
+
+Each hook is called once for each distinct object or rdata, so shared references are preserved.
+The default implementations return an error. `()` implements this trait using the defaults.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+struct Health(i32);
+
+struct Hooks;
+
+impl HeapSerializer for Hooks {
+    fn serialize_rdata(&mut self, rdata: &Root<RData>) -> GResult<Val> {
+        Ok(Val::Int(rdata.borrow::<Health>().0))
+    }
+
+    fn deserialize_rdata(&mut self, payload: Val) -> GResult<Root<RData>> {
+        Ok(glsp::rdata(Health(i32::from_val(&payload)?)))
+    }
+}
+
+let before: Val = arr![glsp::rdata(Health(75))].into_val()?;
+let bytes = glsp::serialize_heap_with(&before, &mut Hooks)?;
+
+let after = glsp::deserialize_heap_with(&bytes, &mut Hooks)?;
+let arr = Root::<Arr>::from_val(&after)?;
+let rdata: Root<RData> = arr.get(0)?;
+assert!(rdata.borrow::<Health>().0 == 75);
+# Ok(()) }).unwrap();
+```
+*/
+
+pub trait HeapSerializer {
+    ///Converts an rdata into a payload value.
+    fn serialize_rdata(&mut self, rdata: &Root<RData>) -> GResult<Val> {
+        let _ = rdata;
+        bail!("serialize_heap: unable to serialize rust data")
+    }
+
+    ///Converts a payload value back into an rdata.
+    fn deserialize_rdata(&mut self, payload: Val) -> GResult<Root<RData>> {
+        let _ = payload;
+        bail!("deserialize_heap: unable to deserialize rust data")
+    }
+
+    ///Converts an object into a payload value.
+    fn serialize_obj(&mut self, obj: &Root<Obj>) -> GResult<Val> {
+        let _ = obj;
+        bail!("serialize_heap: unable to serialize an obj")
+    }
+
+    ///Converts a payload value back into an object.
+    fn deserialize_obj(&mut self, payload: Val) -> GResult<Root<Obj>> {
+        let _ = payload;
+        bail!("deserialize_heap: unable to deserialize an obj")
+    }
+}
+
+impl HeapSerializer for () {}
+
+//-------------------------------------------------------------------------------------------------
+// serialization
+//-------------------------------------------------------------------------------------------------
+
+pub(crate) fn serialize_heap<H: HeapSerializer + ?Sized>(
+    root: &Val,
+    hooks: &mut H,
+) -> GResult<Vec<u8>> {
+    let mut writer = HeapWriter {
+        hooks,
+        ids: FnvHashMap::default(),
+        objects: Vec::new(),
+        gensyms: FnvHashMap::default(),
+    };
+
+    let mut root_bytes = Vec::new();
+    writer.write_val(&mut root_bytes, root)?;
+
+    //writing an entry may discover further objects, which are appended to the table
+    let mut entry_bytes = Vec::new();
+    let mut i = 0;
+    while i < writer.objects.len() {
+        let object = writer.objects[i].clone();
+        writer.write_entry(&mut entry_bytes, &object)?;
+        i += 1;
+    }
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 9 + entry_bytes.len() + root_bytes.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    write_len(&mut bytes, writer.objects.len())?;
+    write_len(&mut bytes, writer.gensyms.len())?;
+    bytes.extend_from_slice(&entry_bytes);
+    bytes.extend_from_slice(&root_bytes);
+
+    Ok(bytes)
+}
+
+struct HeapWriter<'h, H: HeapSerializer + ?Sized> {
+    hooks: &'h mut H,
+
+    //keyed by address. every object in the table is kept alive by `objects`, so an address
+    //can't be reused by a different object during serialization
+    ids: FnvHashMap<usize, u32>,
+    objects: Vec<Val>,
+    gensyms: FnvHashMap<Sym, u32>,
+}
+
+impl<'h, H: HeapSerializer + ?Sized> HeapWriter<'h, H> {
+    fn write_val(&mut self, bytes: &mut Vec<u8>, val: &Val) -> GResult<()> {
+        let address = match *val {
+            Val::Nil => {
+                bytes.push(VAL_NIL);
+                return Ok(());
+            }
+            Val::Int(i) => {
+                bytes.push(VAL_INT);
+                bytes.extend_from_slice(&i.to_le_bytes());
+                return Ok(());
+            }
+            Val::Flo(f) => {
+                bytes.push(VAL_FLO);
+                bytes.extend_from_slice(&f.to_bits().to_le_bytes());
+                return Ok(());
+            }
+            Val::Char(c) => {
+                bytes.push(VAL_CHAR);
+                bytes.extend_from_slice(&(c as u32).to_le_bytes());
+                return Ok(());
+            }
+            Val::Bool(b) => {
+                bytes.push(VAL_BOOL);
+                bytes.push(b as u8);
+                return Ok(());
+            }
+            Val::Sym(sym) => {
+                if sym.is_gensym() {
+                    let next_id = self.gensyms.len() as u32;
+                    let id = *self.gensyms.entry(sym).or_insert(next_id);
+
+                    bytes.push(VAL_GENSYM);
+                    bytes.extend_from_slice(&id.to_le_bytes());
+                } else {
+                    bytes.push(VAL_SYM);
+                    write_str(bytes, &sym.name())?;
+                }
+                return Ok(());
+            }
+            Val::Arr(ref root) => &**root as *const _ as usize,
+            Val::Str(ref root) => &**root as *const _ as usize,
+            Val::Tab(ref root) => &**root as *const _ as usize,
+            Val::Obj(ref root) => &**root as *const _ as usize,
+            Val::RData(ref root) => &**root as *const _ as usize,
+            Val::GIter(_) | Val::Class(_) | Val::GFn(_) | Val::RFn(_) | Val::Coro(_) => {
+                bail!("serialize_heap: unable to serialize {}", val.a_type_name())
+            }
+        };
+
+        let id = match self.ids.get(&address) {
+            Some(&id) => id,
+            None => {
+                let id = u32::try_from(self.objects.len()).ok();
+                let id = id.ok_or_else(|| error!("serialize_heap: too many objects"))?;
+
+                self.ids.insert(address, id);
+                self.objects.push(val.clone());
+                id
+            }
+        };
+
+        bytes.push(VAL_REF);
+        bytes.extend_from_slice(&id.to_le_bytes());
+        Ok(())
+    }
+
+    fn write_entry(&mut self, bytes: &mut Vec<u8>, object: &Val) -> GResult<()> {
+        match *object {
+            Val::Arr(ref arr) => {
+                bytes.push(ENTRY_ARR);
+                bytes.push(arr.is_frozen() as u8);
+                write_len(bytes, arr.len())?;
+                for val in arr.iter() {
+                    self.write_val(bytes, &val)?;
+                }
+            }
+            Val::Str(ref st) => {
+                bytes.push(ENTRY_STR);
+                bytes.push(st.is_frozen() as u8);
+                write_str(bytes, &st.to_string())?;
+            }
+            Val::Tab(ref tab) => {
+                bytes.push(ENTRY_TAB);
+                bytes.push(tab.is_frozen() as u8);
+                write_len(bytes, tab.len())?;
+                for (key, value) in tab.entries().iter() {
+                    self.write_val(bytes, &key)?;
+                    self.write_val(bytes, &value)?;
+                }
+            }
+            Val::RData(ref rdata) => {
+                let payload = self.hooks.serialize_rdata(rdata)?;
+                bytes.push(ENTRY_RDATA);
+                self.write_val(bytes, &payload)?;
+            }
+            Val::Obj(ref obj) => {
+                let payload = self.hooks.serialize_obj(obj)?;
+                bytes.push(ENTRY_OBJ);
+                self.write_val(bytes, &payload)?;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+}
+
+fn write_len(bytes: &mut Vec<u8>, len: usize) -> GResult<()> {
+    match u32::try_from(len) {
+        Ok(len) => {
+            bytes.extend_from_slice(&len.to_le_bytes());
+            Ok(())
+        }
+        Err(_) => bail!("serialize_heap: collection too large"),
+    }
+}
+
+fn write_str(bytes: &mut Vec<u8>, st: &str) -> GResult<()> {
+    write_len(bytes, st.len())?;
+    bytes.extend_from_slice(st.as_bytes());
+    Ok(())
+}
+
+//-------------------------------------------------------------------------------------------------
+// deserialization
+//-------------------------------------------------------------------------------------------------
+
+pub(crate) fn deserialize_heap<H: HeapSerializer + ?Sized>(
+    bytes: &[u8],
+    hooks: &mut H,
+) -> GResult<Val> {
+    let mut reader = HeapReader { bytes, pos: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        bail!("deserialize_heap: not a heap snapshot")
+    }
+
+    let version = reader.read_u8()?;
+    ensure!(
+        version == VERSION,
+        "deserialize_heap: unsupported snapshot version {}",
+        version
+    );
+
+    let object_count = reader.read_u32()? as usize;
+    let gensym_count = reader.read_u32()? as usize;
+
+    //each gensym in the snapshot is replaced with a fresh gensym, consistently
+    ensure!(
+        gensym_count <= reader.remaining(),
+        "deserialize_heap: malformed snapshot"
+    );
+    let gensyms: Vec<Sym> = (0..gensym_count).map(|_| glsp::gensym()).collect();
+
+    ensure!(
+        object_count <= reader.remaining(),
+        "deserialize_heap: malformed snapshot"
+    );
+    let mut entries = Vec::with_capacity(object_count);
+    for _ in 0..object_count {
+        entries.push(Some(reader.read_entry(object_count, &gensyms)?));
+    }
+
+    let root = reader.read_val(object_count, &gensyms)?;
+    ensure!(
+        reader.remaining() == 0,
+        "deserialize_heap: malformed snapshot"
+    );
+
+    let mut builder = HeapBuilder {
+        hooks,
+        results: vec![None; object_count],
+        entries,
+    };
+
+    builder.resolve(root)
+}
+
+enum Repr {
+    Val(Val),
+    Ref(usize),
+}
+
+//the bools record whether the object is frozen
+enum Entry {
+    Arr(Vec<Repr>, bool),
+    Str(String, bool),
+    Tab(Vec<(Repr, Repr)>, bool),
+    RData(Repr),
+    Obj(Repr),
+}
+
+struct HeapReader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> HeapReader<'b> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> GResult<&'b [u8]> {
+        ensure!(
+            len <= self.remaining(),
+            "deserialize_heap: malformed snapshot"
+        );
+
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> GResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> GResult<u32> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_str(&mut self) -> GResult<String> {
+        let len = self.read_u32()? as usize;
+        match String::from_utf8(self.take(len)?.to_vec()) {
+            Ok(string) => Ok(string),
+            Err(_) => bail!("deserialize_heap: malformed snapshot"),
+        }
+    }
+
+    fn read_val(&mut self, object_count: usize, gensyms: &[Sym]) -> GResult<Repr> {
+        let val = match self.read_u8()? {
+            VAL_NIL => Val::Nil,
+            VAL_INT => Val::Int(self.read_u32()? as i32),
+            VAL_FLO => Val::Flo(f32::from_bits(self.read_u32()?)),
+            VAL_CHAR => match std::char::from_u32(self.read_u32()?) {
+                Some(c) => Val::Char(c),
+                None => bail!("deserialize_heap: malformed snapshot"),
+            },
+            VAL_BOOL => Val::Bool(self.read_u8()? != 0),
+            VAL_SYM => Val::Sym(glsp::sym(&self.read_str()?)?),
+            VAL_GENSYM => match gensyms.get(self.read_u32()? as usize) {
+                Some(&sym) => Val::Sym(sym),
+                None => bail!("deserialize_heap: malformed snapshot"),
+            },
+            VAL_REF => {
+                let id = self.read_u32()? as usize;
+                ensure!(id < object_count, "deserialize_heap: malformed snapshot");
+                return Ok(Repr::Ref(id));
+            }
+            _ => bail!("deserialize_heap: malformed snapshot"),
+        };
+
+        Ok(Repr::Val(val))
+    }
+
+    fn read_entry(&mut self, object_count: usize, gensyms: &[Sym]) -> GResult<Entry> {
+        Ok(match self.read_u8()? {
+            ENTRY_ARR => {
+                let frozen = self.read_u8()? != 0;
+                let len = self.read_u32()? as usize;
+                ensure!(
+                    len <= self.remaining(),
+                    "deserialize_heap: malformed snapshot"
+                );
+
+                let mut elements = Vec::with_capacity(len);
+                for _ in 0..len {
+                    elements.push(self.read_val(object_count, gensyms)?);
+                }
+
+                Entry::Arr(elements, frozen)
+            }
+            ENTRY_STR => {
+                let frozen = self.read_u8()? != 0;
+                Entry::Str(self.read_str()?, frozen)
+            }
+            ENTRY_TAB => {
+                let frozen = self.read_u8()? != 0;
+                let len = self.read_u32()? as usize;
+                ensure!(
+                    len <= self.remaining(),
+                    "deserialize_heap: malformed snapshot"
+                );
+
+                let mut pairs = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let key = self.read_val(object_count, gensyms)?;
+                    let value = self.read_val(object_count, gensyms)?;
+                    pairs.push((key, value));
+                }
+
+                Entry::Tab(pairs, frozen)
+            }
+            ENTRY_RDATA => Entry::RData(self.read_val(object_count, gensyms)?),
+            ENTRY_OBJ => Entry::Obj(self.read_val(object_count, gensyms)?),
+            _ => bail!("deserialize_heap: malformed snapshot"),
+        })
+    }
+}
+
+struct HeapBuilder<'h, H: HeapSerializer + ?Sized> {
+    hooks: &'h mut H,
+
+    //an object is being constructed when its entry has been taken, but it has no result yet.
+    //arrs and tabs are stored in `results` before their contents are resolved, so that cycles
+    //can refer back to them
+    results: Vec<Option<Val>>,
+    entries: Vec<Option<Entry>>,
+}
+
+//resolving a Repr either produces a value immediately, or it pushes a Frame and requests the
+//value of that frame's first child. like the HeapWriter, we use an explicit stack rather than
+//recursion, so that deeply-nested snapshots can't overflow the Rust stack
+enum Step {
+    Descend(Repr),
+    Ascend(Val),
+}
+
+//a partially-constructed object which is waiting for the value of one of its children. arrs and
+//tabs are frozen after their last child has been stored
+enum Frame {
+    Arr(Root<Arr>, vec::IntoIter<Repr>, bool),
+    TabKey(Root<Tab>, vec::IntoIter<(Repr, Repr)>, Repr, bool),
+    TabValue(Root<Tab>, vec::IntoIter<(Repr, Repr)>, Val, bool),
+    RData(usize),
+    Obj(usize),
+}
+
+impl<'h, H: HeapSerializer + ?Sized> HeapBuilder<'h, H> {
+    fn resolve(&mut self, root: Repr) -> GResult<Val> {
+        let mut stack = Vec::new();
+        let mut step = Step::Descend(root);
+
+        loop {
+            step = match step {
+                Step::Descend(repr) => self.descend(&mut stack, repr)?,
+                Step::Ascend(val) => match stack.pop() {
+                    Some(frame) => self.ascend(&mut stack, frame, val)?,
+                    None => return Ok(val),
+                },
+            };
+        }
+    }
+
+    fn descend(&mut self, stack: &mut Vec<Frame>, repr: Repr) -> GResult<Step> {
+        let id = match repr {
+            Repr::Val(val) => return Ok(Step::Ascend(val)),
+            Repr::Ref(id) => id,
+        };
+
+        if let Some(ref val) = self.results[id] {
+            return Ok(Step::Ascend(val.clone()));
+        }
+
+        let entry = match self.entries[id].take() {
+            Some(entry) => entry,
+            None => bail!("deserialize_heap: an obj or rdata's payload refers back to itself"),
+        };
+
+        match entry {
+            Entry::Arr(elements, frozen) => {
+                let arr = glsp::arr_with_capacity(elements.len());
+                self.results[id] = Some(Val::Arr(arr.clone()));
+                Ok(next_element(stack, arr, elements.into_iter(), frozen))
+            }
+            Entry::Str(string, frozen) => {
+                let st: Root<Str> = glsp::str_from_rust_str(&string);
+                if frozen {
+                    st.freeze();
+                }
+
+                self.results[id] = Some(Val::Str(st.clone()));
+                Ok(Step::Ascend(Val::Str(st)))
+            }
+            Entry::Tab(pairs, frozen) => {
+                let tab = glsp::tab_with_capacity(pairs.len());
+                self.results[id] = Some(Val::Tab(tab.clone()));
+                Ok(next_pair(stack, tab, pairs.into_iter(), frozen))
+            }
+            Entry::RData(payload) => {
+                stack.push(Frame::RData(id));
+                Ok(Step::Descend(payload))
+            }
+            Entry::Obj(payload) => {
+                stack.push(Frame::Obj(id));
+                Ok(Step::Descend(payload))
+            }
+        }
+    }
+
+    fn ascend(&mut self, stack: &mut Vec<Frame>, frame: Frame, val: Val) -> GResult<Step> {
+        match frame {
+            Frame::Arr(arr, elements, frozen) => {
+                arr.push(val)?;
+                Ok(next_element(stack, arr, elements, frozen))
+            }
+            Frame::TabKey(tab, pairs, value, frozen) => {
+                stack.push(Frame::TabValue(tab, pairs, val, frozen));
+                Ok(Step::Descend(value))
+            }
+            Frame::TabValue(tab, pairs, key, frozen) => {
+                tab.set(key, val)?;
+                Ok(next_pair(stack, tab, pairs, frozen))
+            }
+            Frame::RData(id) => {
+                let rdata = self.hooks.deserialize_rdata(val)?;
+                self.results[id] = Some(Val::RData(rdata.clone()));
+                Ok(Step::Ascend(Val::RData(rdata)))
+            }
+            Frame::Obj(id) => {
+                let obj = self.hooks.deserialize_obj(val)?;
+                self.results[id] = Some(Val::Obj(obj.clone()));
+                Ok(Step::Ascend(Val::Obj(obj)))
+            }
+        }
+    }
+}
+
+fn next_element(
+    stack: &mut Vec<Frame>,
+    arr: Root<Arr>,
+    mut elements: vec::IntoIter<Repr>,
+    frozen: bool,
+) -> Step {
+    match elements.next() {
+        Some(element) => {
+            stack.push(Frame::Arr(arr, elements, frozen));
+            Step::Descend(element)
+        }
+        None => {
+            if frozen {
+                arr.freeze();
+            }
+
+            Step::Ascend(Val::Arr(arr))
+        }
+    }
+}
+
+fn next_pair(
+    stack: &mut Vec<Frame>,
+    tab: Root<Tab>,
+    mut pairs: vec::IntoIter<(Repr, Repr)>,
+    frozen: bool,
+) -> Step {
+    match pairs.next() {
+        Some((key, value)) => {
+            stack.push(Frame::TabKey(tab, pairs, value, frozen));
+            Step::Descend(key)
+        }
+        None => {
+            if frozen {
+                tab.freeze();
+            }
+
+            Step::Ascend(Val::Tab(tab))
+        }
+    }
+}
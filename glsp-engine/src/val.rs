@@ -1,17 +1,22 @@
 use super::class::{Class, Obj};
 use super::code::{Coro, GFn};
 use super::collections::{Arr, DequeOps, Str, Tab};
-use super::engine::{stock_syms::*, RData, RFn, Sym};
+use super::engine::{glsp, stock_syms::*, RData, RFn, Sym};
 use super::error::GResult;
 use super::gc::Root;
 use super::iter::GIter;
+use super::parse::parse_num;
+use super::wrap::FromVal;
 use std::char;
 use std::cmp::{Ordering, PartialOrd};
+use std::collections::{HashMap, HashSet};
+use std::f32;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::iter::once;
 use std::num::FpCategory;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::vec;
 
 //-------------------------------------------------------------------------------------------------
 // Val
@@ -128,11 +133,66 @@ impl_val!(
 );
 
 impl Val {
+    /**
+    Returns the name of this value's type, as a symbol.
+
+    For most values, the result is the same as
+    [`(type-of self)`](https://gamelisp.rs/std/type-of), such as `int` or `arr`. Comparing
+    symbols is cheaper than comparing the strings returned by [`type_name`](#method.type_name).
+
+    When an `rdata` has an [`RClass`](struct.RClass.html), the result is the `RClass`'s
+    registered [name](struct.RClass.html#method.name). Any other `rdata` produces the symbol
+    `rdata`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    struct Unit;
+    RClassBuilder::<Unit>::new().name("Unit").build();
+
+    assert!(Val::Int(10).type_sym() == sym!("int"));
+    assert!(Val::RData(glsp::rdata(Unit)).type_sym() == sym!("Unit"));
+    assert!(Val::RData(glsp::rdata(0u8)).type_sym() == sym!("rdata"));
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn type_sym(&self) -> Sym {
+        match *self {
+            Val::Nil => NIL_SYM,
+            Val::Int(_) => INT_SYM,
+            Val::Flo(_) => FLO_SYM,
+            Val::Char(_) => CHAR_SYM,
+            Val::Bool(_) => BOOL_SYM,
+            Val::Sym(_) => SYM_SYM,
+            Val::RFn(_) => RFN_SYM,
+            Val::Arr(_) => ARR_SYM,
+            Val::Str(_) => STR_SYM,
+            Val::Tab(_) => TAB_SYM,
+            Val::GIter(_) => ITER_SYM,
+            Val::Obj(_) => OBJ_SYM,
+            Val::Class(_) => CLASS_SYM,
+            Val::GFn(_) => FN_SYM,
+            Val::Coro(_) => CORO_SYM,
+            Val::RData(ref rdata) => match rdata.rclass() {
+                Some(rclass) => rclass.name(),
+                None => RDATA_SYM,
+            },
+        }
+    }
+
     pub fn is_nil(&self) -> bool {
         matches!(*self, Val::Nil)
     }
 
-    ///Returns `true` if the value is anything other than `#n` or `#f`.
+    /**
+    Returns `true` if the value is anything other than `#n` or `#f`.
+
+    This is the rule used by conditional forms like [`if`](https://gamelisp.rs/std/if) and
+    [`and`](https://gamelisp.rs/std/and). Every other value is truthy, including `0`, `0.0`,
+    the empty str `""`, the empty arr `()` and the char `\0`.
+    */
     pub fn is_truthy(&self) -> bool {
         !matches!(*self, Val::Nil | Val::Bool(false))
     }
@@ -142,6 +202,38 @@ impl Val {
         matches!(*self, Val::Nil | Val::Bool(false))
     }
 
+    /**
+    Converts an `int`, a `flo`, or a `str` which contains a numeric literal, into a
+    [`Num`](enum.Num.html).
+
+    A `str` is parsed using the same rules as the reader, so `"10"`, `"-0x1f"`, `"1.5e3"` and
+    `"+inf.0"` are accepted, but `" 10"` and `"1.5f32"` are not. Any other value is an error.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    # Engine::new().run(|| {
+    #
+    assert!(Val::Int(3).coerce_to_num()? == Num::Int(3));
+    assert!(Val::Str(glsp::str_from_rust_str("0x10")).coerce_to_num()? == Num::Int(16));
+    assert!(Val::Str(glsp::str_from_rust_str("2.5")).coerce_to_num()? == Num::Flo(2.5));
+    assert!(Val::Str(glsp::str_from_rust_str("ten")).coerce_to_num().is_err());
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn coerce_to_num(&self) -> GResult<Num> {
+        match *self {
+            Val::Int(i) => Ok(Num::Int(i)),
+            Val::Flo(f) => Ok(Num::Flo(f)),
+            Val::Str(ref st) => match parse_num(&st.to_rust_string()) {
+                Some(num) => Ok(num),
+                None => bail!("the str {:?} is not a numeric literal", st.to_rust_string()),
+            },
+            ref val => bail!("expected a num or a str, received {}", val.a_type_name()),
+        }
+    }
+
     //if you change any of these, you also need to change the OpPredicate instruction in vm.rs...
 
     /**
@@ -183,6 +275,34 @@ impl Val {
         )
     }
 
+    /**
+    Returns `true` if the value can be converted to the type `T`.
+
+    For most built-in types, this is much cheaper than `T::from_val(&val).is_ok()`, because a
+    failed conversion doesn't allocate a [`GError`](struct.GError.html). For example,
+    `val.is::<String>()` doesn't copy the string, and `val.is::<RRoot<T>>()` checks that the
+    `rdata` is currently storing a value of type `T`. For other types, this method falls back to
+    attempting the conversion.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    let val = Val::Int(300);
+
+    assert!(val.is::<i32>());
+    assert!(val.is::<u16>());
+    assert!(!val.is::<u8>());
+    assert!(!val.is::<Root<Arr>>());
+    # Ok(()) }).unwrap();
+    ```
+    */
+    #[inline]
+    pub fn is<T: FromVal>(&self) -> bool {
+        T::can_from_val(self)
+    }
+
     /**
     Creates a shallow copy of the value.
 
@@ -269,6 +389,117 @@ impl Val {
         })
     }
 
+    /**
+    Returns a copy of this value with its floating-point numbers normalized.
+
+    Negative zero is replaced with positive zero, and every NaN is replaced with the
+    canonical NaN, [`f32::NAN`](https://doc.rust-lang.org/std/f32/constant.NAN.html). After
+    canonicalization, two floats which compare equal will also have identical bit patterns.
+
+    Arrays and tables are canonicalized recursively, including table keys. They're always
+    copied, even if they don't contain any floats; the original value is never modified.
+    Reference cycles and shared references are preserved in the copy. All other types,
+    including strings, objects and `rdata`, are shared with the original value rather
+    than being copied.
+
+    When a table contains both `0.0` and `-0.0` as keys, only one of the two entries will
+    be present in the copy.
+    */
+    pub fn canonicalize(&self) -> Val {
+        //rather than recursing into each arr and tab, we keep an explicit stack of frames, so
+        //that deeply-nested data can't overflow the native stack. the traversal order is the
+        //same as a recursive traversal, so a table key is always fully copied before the key
+        //is hashed, except when the key contains the table itself.
+        let mut copies = HashMap::new();
+        let (result, frame) = self.canonicalize_shallow(&mut copies);
+
+        let mut stack: Vec<CanonicalizeFrame> = frame.into_iter().collect();
+        while let Some(mut frame) = stack.pop() {
+            let child = match frame {
+                CanonicalizeFrame::Arr(ref mut items, ref copy) => match items.next() {
+                    Some(item) => {
+                        let (item_copy, child) = item.canonicalize_shallow(&mut copies);
+                        copy.push(item_copy).unwrap();
+                        child
+                    }
+                    None => continue,
+                },
+                CanonicalizeFrame::Tab(ref mut entries, ref copy, ref mut pending) => {
+                    match pending.take() {
+                        Some((key_copy, value)) => {
+                            let (value_copy, child) = value.canonicalize_shallow(&mut copies);
+                            copy.set(key_copy, value_copy).unwrap();
+                            child
+                        }
+                        None => match entries.next() {
+                            Some((key, value)) => {
+                                let (key_copy, child) = key.canonicalize_shallow(&mut copies);
+                                *pending = Some((key_copy, value));
+                                child
+                            }
+                            None => continue,
+                        },
+                    }
+                }
+            };
+
+            stack.push(frame);
+            stack.extend(child);
+        }
+
+        result
+    }
+
+    //returns the copy of this value, which will be empty for a newly-copied arr or tab, and the
+    //frame which will fill that copy. `copies` maps each original arr or tab's address to its
+    //copy, so that cycles and shared references are preserved.
+    fn canonicalize_shallow(
+        &self,
+        copies: &mut HashMap<usize, Val>,
+    ) -> (Val, Option<CanonicalizeFrame>) {
+        match *self {
+            Val::Flo(f) => {
+                let f = if f == 0.0 {
+                    0.0
+                } else if f.is_nan() {
+                    f32::NAN
+                } else {
+                    f
+                };
+
+                (Val::Flo(f), None)
+            }
+            Val::Arr(ref arr) => {
+                let addr = &**arr as *const Arr as usize;
+                if let Some(copy) = copies.get(&addr) {
+                    return (copy.clone(), None);
+                }
+
+                let copy = glsp::arr_with_capacity(arr.len());
+                copy.set_span(arr.span());
+                copies.insert(addr, Val::Arr(copy.clone()));
+
+                let items: Vec<Val> = arr.iter().collect();
+                let frame = CanonicalizeFrame::Arr(items.into_iter(), copy.clone());
+                (Val::Arr(copy), Some(frame))
+            }
+            Val::Tab(ref tab) => {
+                let addr = &**tab as *const Tab as usize;
+                if let Some(copy) = copies.get(&addr) {
+                    return (copy.clone(), None);
+                }
+
+                let copy = glsp::tab_with_capacity(tab.len());
+                copies.insert(addr, Val::Tab(copy.clone()));
+
+                let entries: Vec<(Val, Val)> = tab.entries().iter().collect();
+                let frame = CanonicalizeFrame::Tab(entries.into_iter(), copy.clone(), None);
+                (Val::Tab(copy), Some(frame))
+            }
+            ref val => (val.clone(), None),
+        }
+    }
+
     /**
     Makes the value immutable.
 
@@ -299,13 +530,21 @@ impl Val {
     Makes the value and all of its contents immutable.
 
     Equivalent to [`(deep-freeze! val)`](https://gamelisp.rs/std/deep-freeze-mut).
-    */
 
-    //todo: handle reference cycles
+    This is useful for sharing data, like a configuration table, between several subsystems:
+    any later attempt to mutate a frozen arr, str or tab will fail with an error. Reference
+    cycles are supported, so an arr which contains itself will be frozen correctly.
+    */
     pub fn deep_freeze(&self) {
+        let mut walk = FreezeWalk::new();
+        self.deep_freeze_impl(&mut walk);
+        walk.deep_freeze_pending();
+    }
+
+    pub(crate) fn deep_freeze_impl(&self, walk: &mut FreezeWalk) {
         match *self {
-            Val::Arr(ref arr) => arr.deep_freeze(),
-            Val::Tab(ref tab) => tab.deep_freeze(),
+            Val::Arr(ref arr) => arr.deep_freeze_impl(walk),
+            Val::Tab(ref tab) => tab.deep_freeze_impl(walk),
             Val::Str(ref st) => st.freeze(),
             Val::Obj(ref obj) => {
                 //todo
@@ -326,14 +565,18 @@ impl Val {
         }
     }
 
-    //todo: handle reference cycles
     pub(crate) fn is_deep_frozen(&self) -> bool {
+        let mut walk = FreezeWalk::new();
+        self.is_deep_frozen_impl(&mut walk) && walk.is_deep_frozen_pending()
+    }
+
+    pub(crate) fn is_deep_frozen_impl(&self, walk: &mut FreezeWalk) -> bool {
         //note that there's currently no way to traverse the fields of objects etc., so it only
         //makes sense to use this method for representable/evaluable values
         match *self {
-            Val::Arr(ref arr) => arr.is_deep_frozen(),
+            Val::Arr(ref arr) => arr.is_deep_frozen_impl(walk),
             Val::Str(ref st) => st.is_frozen(),
-            Val::Tab(ref tab) => tab.is_deep_frozen(),
+            Val::Tab(ref tab) => tab.is_deep_frozen_impl(walk),
 
             Val::Nil | Val::Int(_) | Val::Flo(_) | Val::Char(_) | Val::Bool(_) | Val::Sym(_) => {
                 true
@@ -350,6 +593,59 @@ impl Val {
     }
 }
 
+//the remaining elements of an original arr or tab, and the copy which they're being pushed to.
+//a tab frame may hold a key which has been copied, but whose value hasn't been copied yet.
+enum CanonicalizeFrame {
+    Arr(vec::IntoIter<Val>, Root<Arr>),
+    Tab(vec::IntoIter<(Val, Val)>, Root<Tab>, Option<(Val, Val)>),
+}
+
+/*
+the worklist for deep_freeze and is_deep_frozen. rather than recursing into each arr and tab,
+its elements are pushed onto `pending`, so that deeply-nested data can't overflow the stack.
+`visited` holds the address of each arr and tab which has already been processed, so that the
+traversal terminates when a collection contains itself.
+*/
+
+pub(crate) struct FreezeWalk {
+    visited: HashSet<usize>,
+    pending: Vec<Val>,
+}
+
+impl FreezeWalk {
+    pub(crate) fn new() -> FreezeWalk {
+        FreezeWalk {
+            visited: HashSet::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    //returns `true` the first time it's called for a particular collection
+    pub(crate) fn visit<T>(&mut self, collection: &T) -> bool {
+        self.visited.insert(collection as *const T as usize)
+    }
+
+    pub(crate) fn push(&mut self, val: Val) {
+        self.pending.push(val)
+    }
+
+    pub(crate) fn deep_freeze_pending(&mut self) {
+        while let Some(val) = self.pending.pop() {
+            val.deep_freeze_impl(self);
+        }
+    }
+
+    pub(crate) fn is_deep_frozen_pending(&mut self) -> bool {
+        while let Some(val) = self.pending.pop() {
+            if !val.is_deep_frozen_impl(self) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // Num
 //-------------------------------------------------------------------------------------------------
@@ -447,6 +743,87 @@ impl Num {
             (Num::Flo(left), Num::Flo(right)) => Num::Flo(left.rem_euclid(right)),
         }
     }
+
+    /**
+    Adds two numbers, returning an `Err` if integer addition overflows.
+
+    The arithmetic operators, like `+`, follow GameLisp's own rules: integer arithmetic wraps
+    on overflow. The `checked_` methods are identical, except that they fail rather than
+    wrapping. Mixed integer and floating-point operands are always promoted to `f32`.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # Engine::new().run(|| {
+    assert!(Num::Int(i32::MAX - 1).checked_add(Num::Int(1)).is_ok());
+    assert!(Num::Int(i32::MAX).checked_add(Num::Int(1)).is_err());
+    assert!(Num::Int(i32::MAX).checked_add(Num::Flo(1.0)).is_ok());
+    #
+    # Ok(()) }).unwrap();
+    ```
+    */
+    pub fn checked_add(self, other: Num) -> GResult<Num> {
+        match (self, other) {
+            (Num::Int(left), Num::Int(right)) => match left.checked_add(right) {
+                Some(result) => Ok(Num::Int(result)),
+                None => bail!("integer overflow when adding {} and {}", left, right),
+            },
+            (left, right) => Ok(left + right),
+        }
+    }
+
+    ///Subtracts two numbers, returning an `Err` if integer subtraction overflows.
+    pub fn checked_sub(self, other: Num) -> GResult<Num> {
+        match (self, other) {
+            (Num::Int(left), Num::Int(right)) => match left.checked_sub(right) {
+                Some(result) => Ok(Num::Int(result)),
+                None => bail!("integer overflow when subtracting {} from {}", right, left),
+            },
+            (left, right) => Ok(left - right),
+        }
+    }
+
+    ///Multiplies two numbers, returning an `Err` if integer multiplication overflows.
+    pub fn checked_mul(self, other: Num) -> GResult<Num> {
+        match (self, other) {
+            (Num::Int(left), Num::Int(right)) => match left.checked_mul(right) {
+                Some(result) => Ok(Num::Int(result)),
+                None => bail!("integer overflow when multiplying {} by {}", left, right),
+            },
+            (left, right) => Ok(left * right),
+        }
+    }
+
+    /**
+    Divides two numbers, returning an `Err` if integer division overflows or if an integer
+    is divided by zero.
+    */
+    pub fn checked_div(self, other: Num) -> GResult<Num> {
+        match (self, other) {
+            (Num::Int(_), Num::Int(0)) => bail!("integer division by zero"),
+            (Num::Int(left), Num::Int(right)) => match left.checked_div(right) {
+                Some(result) => Ok(Num::Int(result)),
+                None => bail!("integer overflow when dividing {} by {}", left, right),
+            },
+            (left, right) => Ok(left / right),
+        }
+    }
+
+    /**
+    Calculates the remainder of two numbers, returning an `Err` if integer division overflows
+    or if an integer is divided by zero.
+    */
+    pub fn checked_rem(self, other: Num) -> GResult<Num> {
+        match (self, other) {
+            (Num::Int(_), Num::Int(0)) => bail!("integer division by zero"),
+            (Num::Int(left), Num::Int(right)) => match left.checked_rem(right) {
+                Some(result) => Ok(Num::Int(result)),
+                None => bail!("integer overflow when dividing {} by {}", left, right),
+            },
+            (left, right) => Ok(left % right),
+        }
+    }
 }
 
 #[inline]
@@ -746,6 +1123,87 @@ impl Val {
     }
 }
 
+/*
+the implementation of glsp::deep_eq and glsp::deep_eq_with.
+
+unlike try_eq(), this never invokes user code, so it can't fail. arrs, strs and tabs are
+compared structurally; objs are compared by identity, and rdata are compared by identity unless
+the caller provides a hook. every pair of collections which is currently being compared, or which
+has already been compared, is recorded in `visited`. when a pair is encountered a second time, we
+assume that it's equal, so that cyclic structures don't cause infinite recursion. any difference
+will still be detected elsewhere in the traversal.
+
+rather than recursing, the pairs of elements which still need to be compared are pushed onto
+`pending`, so that deeply-nested data can't overflow the native stack.
+*/
+
+pub(crate) fn deep_eq<F>(v0: &Val, v1: &Val, rdata_eq: &mut F) -> bool
+where
+    F: FnMut(&Root<RData>, &Root<RData>) -> bool,
+{
+    let mut visited = HashSet::new();
+    let mut pending = vec![(v0.clone(), v1.clone())];
+
+    while let Some((v0, v1)) = pending.pop() {
+        if !deep_eq_shallow(&v0, &v1, &mut visited, &mut pending, rdata_eq) {
+            return false;
+        }
+    }
+
+    true
+}
+
+//compares two values without inspecting the contents of arrs and tabs. instead, each pair of
+//elements is pushed onto `pending`.
+fn deep_eq_shallow<F>(
+    v0: &Val,
+    v1: &Val,
+    visited: &mut HashSet<(usize, usize)>,
+    pending: &mut Vec<(Val, Val)>,
+    rdata_eq: &mut F,
+) -> bool
+where
+    F: FnMut(&Root<RData>, &Root<RData>) -> bool,
+{
+    match (v0, v1) {
+        (&Val::Arr(ref a0), &Val::Arr(ref a1)) => {
+            let key = (&**a0 as *const _ as usize, &**a1 as *const _ as usize);
+            if !visited.insert(key) {
+                return true;
+            }
+
+            if a0.len() != a1.len() {
+                return false;
+            }
+
+            pending.extend(a0.iter().zip(a1.iter()));
+            true
+        }
+        (&Val::Str(ref s0), &Val::Str(ref s1)) => **s0 == **s1,
+        (&Val::Tab(ref t0), &Val::Tab(ref t1)) => {
+            let key = (&**t0 as *const _ as usize, &**t1 as *const _ as usize);
+            if !visited.insert(key) {
+                return true;
+            }
+
+            if t0.len() != t1.len() {
+                return false;
+            }
+
+            for (k, value0) in t0.entries().iter() {
+                match t1.get_if_present::<_, Val>(&k).unwrap() {
+                    Some(value1) => pending.push((value0, value1)),
+                    None => return false,
+                }
+            }
+
+            true
+        }
+        (&Val::RData(ref r0), &Val::RData(ref r1)) => rdata_eq(r0, r1),
+        _ => v0.same(v1),
+    }
+}
+
 /*
 `val0 == val` has the same semantics as `eq?`. for symmetry with the other equality methods,
 it can be called as val0.eq(&val1). errors in `op-eq?` will panic - try_eq() is the alternative.
@@ -47,6 +47,15 @@ pub(crate) struct Vm {
     recursion: Cell<u32>,
 }
 
+#[derive(Copy, Clone)]
+pub(crate) struct VmDepths {
+    regs: usize,
+    stays: usize,
+    defers: usize,
+    frames: usize,
+    recursion: u32,
+}
+
 pub(crate) struct Stacks {
     pub(crate) regs: Vec<Slot>,
     stays: Vec<Option<Raw<Stay>>>,
@@ -94,11 +103,13 @@ pub(crate) enum GlspApiName {
     ParseAll,
     ParseFile,
     Parse1,
+    ReaderNext,
     Eval,
     EvalMulti,
     Require,
     Load,
     LoadStr,
+    EvalStr,
     LoadAndCompile,
     LoadAndCompileStr,
     LoadAndCompileVals,
@@ -118,11 +129,13 @@ impl fmt::Display for GlspApiName {
             ParseAll => "parse_all",
             ParseFile => "parse_file",
             Parse1 => "parse_1",
+            ReaderNext => "Reader::next",
             Eval => "eval",
             EvalMulti => "eval-multi",
             Require => "require",
             Load => "load",
             LoadStr => "load_str",
+            EvalStr => "eval_str",
             LoadAndCompile => "load_and_compile",
             LoadAndCompileStr => "load_and_compile_str",
             LoadAndCompileVals => "load_and_compile_vals",
@@ -177,6 +190,31 @@ impl Vm {
         self.frames.borrow_mut().pop().unwrap()
     }
 
+    pub(crate) fn depths(&self) -> VmDepths {
+        let stacks = self.stacks.borrow();
+        VmDepths {
+            regs: stacks.regs.len(),
+            stays: stacks.stays.len(),
+            defers: stacks.defers.len(),
+            frames: self.frames.borrow().len(),
+            recursion: self.recursion.get(),
+        }
+    }
+
+    //discards anything which was pushed onto the vm's stacks since `depths` was captured. used
+    //by glsp::call_catching after a panic, since an unwinding panic may skip the code which
+    //would normally pop those stacks.
+    pub(crate) fn restore_depths(&self, depths: VmDepths) {
+        let mut stacks = self.stacks.borrow_mut();
+        stacks.regs.truncate(depths.regs);
+        stacks.stays.truncate(depths.stays);
+        stacks.defers.truncate(depths.defers);
+        drop(stacks);
+
+        self.frames.borrow_mut().truncate(depths.frames);
+        self.recursion.set(depths.recursion);
+    }
+
     pub(crate) fn in_expander(&self) -> bool {
         self.frames
             .borrow()
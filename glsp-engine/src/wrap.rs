@@ -5,28 +5,35 @@ use super::class::{Class, Obj};
 use super::code::{Coro, GFn};
 use super::collections::{Arr, Deque, DequeAccess, DequeOps, Str, Tab};
 use super::engine::{
-    glsp, stock_syms::*, RData, RFn, RGlobal, RGlobalRef, RGlobalRefMut, RRef, RRefMut, RRoot, Sym,
+    glsp, short_type_name, stock_syms::*, RData, RFn, RGc, RGlobal, RGlobalRef, RGlobalRefMut,
+    RRef, RRefMut, RRoot, RTrait, Sym,
 };
 use super::error::{GError, GResult};
 use super::eval::{EnvMode, Expander};
-use super::gc::{Raw, Root, Slot};
-use super::iter::{GIter, GIterLen, Iterable};
+use super::gc::{Gc, GcVal, Raw, Root, Slot};
+use super::iter::{GIter, GIterLen, GIterState, Iterable};
 use super::val::{Num, Val};
+use fnv::FnvHashSet;
 use smallvec::SmallVec;
 use std::any::type_name;
-use std::cell::Ref;
+use std::borrow::Cow;
+use std::cell::{Ref, RefCell};
 use std::cmp::{min, Ordering};
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
 use std::convert::{TryFrom, TryInto};
 use std::error::Error;
 use std::ffi::{CStr, CString, OsStr, OsString};
 use std::hash::{BuildHasher, Hash};
 use std::io::Write;
-use std::iter::{Extend, IntoIterator};
+use std::iter::{Extend, FromIterator, IntoIterator};
 use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::{Deref, DerefMut, Index, IndexMut, Range, RangeInclusive};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::slice::SliceIndex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{i128, i16, i32, i64, i8, isize, slice, str, u128, u16, u32, u64, u8, usize};
 
 /*
@@ -36,16 +43,23 @@ this module defines:
   Rust functions into boxed type-erased functions which can be called by GameLisp
 
 todos:
-- should FromVal support Option<T> and Rest<T> in tuples?
-- should we support Either<T, U>? this will require argument type conversions to fail more cheaply
-  than they currently do, perhaps returning a dedicated error type rather than GError
-    - this would also enable us to provide a method Val::is::<T>(&self) -> bool
+- tuples support trailing Option<T> elements, via the hidden FromTupleElement trait. should
+  FromVal support Rest<T> in tuples?
+    - `impl<T: FromVal> FromVal for Option<T>` is currently impossible: it would overlap with
+      the blanket `impl<T: FromVal> FromArg for T` and the more specific
+      `impl<T: FromArg> FromArg for Option<T>`. specializing the latter would require
+      associated type specialization (FromArg::Temp, FromArg::OutputCtor), which isn't part of
+      min_specialization. when it's stabilized, FromVal for Option<T> should map nil to None,
+      and it should override from_slot so that it works for Vec<Option<T>> and map values.
+- FromVal::try_from_val and FromVal::can_from_val enable conversions to fail cheaply, which is
+  used by Either<L, R> and Val::is::<T>()
 - should FromVal::from_val accept any parameter which implements AsRef<Val>, so that it
   can accept either Val or &Val when called explicitly?
     - for now, i'm reluctant to add the extra monomorphization cost
-- it ought to be possible to add a write_back() method to FromArgRef, and implement that method
-  for any &mut T: FromVal + IntoVal, as well as &mut str etc. this would enable us to support
-  more Rust apis without any manual translation. however...
+- FromArgRef has a write_back() method, which is currently only implemented for &mut String,
+  &mut Vec<T> and &mut [T]. we could implement it for any &mut T: FromVal + IntoVal, as well as
+  &mut str etc. this would enable us to support more Rust apis without any manual
+  translation. however...
     - i'm skeptical there would be much demand for it, and perf would be poor
     - it would require us to remove the blanket implementations which let the user move
       RData on and off the heap using t.into_val() and T::from_val()
@@ -77,7 +91,7 @@ IntoVal:
     move_rdata_force() method on the same trait to override the lint.
 
     we would also implement analogous methods .into_rdata() and .into_inner_rdata() on the
-    return types themselves
+    return types themselves (these already exist, as the IntoValExt trait)
 
 FromVal, FromArg, FromArgRef:
 
@@ -106,6 +120,11 @@ impl<T: RGlobal> RGlobalMarker for T {}
 pub trait ErrorMarker: Error {}
 impl<T: Error> ErrorMarker for T {}
 
+#[doc(hidden)]
+#[rustc_unsafe_specialization_marker]
+pub trait IntoErrorValMarker: IntoErrorVal {}
+impl<T: IntoErrorVal> IntoErrorValMarker for T {}
+
 #[doc(hidden)]
 #[rustc_unsafe_specialization_marker]
 pub trait HashEqMarker: Hash + Eq {}
@@ -167,6 +186,16 @@ collections, including arrays, slices and tuples; `Root` and `RRoot`; type-erase
 `Deque` and `Callable`; shared and mutable references to all of the above; and shared references
 to primitive GameLisp types like `&Arr` and `&GFn`.
 
+Weak pointers, [`Gc`](struct.Gc.html), [`RGc`](struct.RGc.html) and
+[`GcVal`](struct.GcVal.html), don't implement `IntoVal`, because GameLisp has no weak value type.
+Call their `upgrade` method to convert them into a `Root`, `RRoot` or `Val` first.
+
+A `BinaryHeap` is converted into an array in its internal heap order, which is unspecified
+except that the greatest element comes first. Call [`into_sorted_vec`] first if you need an
+array in ascending order.
+
+[`into_sorted_vec`]: https://doc.rust-lang.org/std/collections/struct.BinaryHeap.html#method.into_sorted_vec
+
 `Option` and `Result` have special handling, which can be useful for return values:
 
 - `Option` will produce a nil value if it's `None`, or otherwise call `into_val()`
@@ -299,6 +328,101 @@ impl<T: StaticMarker> IntoVal for T {
     }
 }
 
+/**
+Explicit conversions which move a Rust value onto the garbage-collected heap.
+
+Most types' [`IntoVal`](trait.IntoVal.html) implementations convert them into a GameLisp
+value of a similar shape. For example, a `Vec<T>` is converted into an arr. `IntoValExt`
+makes it possible to bypass those implementations.
+
+- `into_rdata()` always moves the entire value onto the heap as a single
+  [`RData`](struct.RData.html). For example, a `Vec<Tile>` becomes one `RData` which stores
+  a `Vec<Tile>`.
+
+- `into_inner_rdata()` moves the contents of an `Option`, `Vec` or `Result` onto the heap.
+  `Some(x)` becomes an `RData` which stores `x`, and `None` becomes `#n`; a `Vec<T>` becomes an
+  arr of `RData`; and `Ok(x)` becomes an `RData`, while `Err(e)` is converted into an error in
+  the usual way. For any other type, it's equivalent to `into_rdata()`.
+
+This trait is implemented for all `'static` types, and it's part of the
+[prelude](prelude/index.html).
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+struct Tile;
+
+let tiles = vec![Tile, Tile, Tile];
+assert!(tiles.into_rdata()?.is_rdata());
+
+let tiles = vec![Tile, Tile, Tile];
+let arr = Root::<Arr>::from_val(&tiles.into_inner_rdata()?)?;
+assert!(arr.len() == 3);
+# Ok(()) }).unwrap();
+```
+*/
+
+pub trait IntoValExt: Sized {
+    fn into_rdata(self) -> GResult<Val>;
+    fn into_inner_rdata(self) -> GResult<Val>;
+}
+
+impl<T: StaticMarker> IntoValExt for T {
+    #[inline]
+    default fn into_rdata(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self)))
+    }
+
+    #[inline]
+    default fn into_inner_rdata(self) -> GResult<Val> {
+        self.into_rdata()
+    }
+}
+
+impl<T: StaticMarker> IntoValExt for Option<T> {
+    #[inline]
+    fn into_rdata(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self)))
+    }
+
+    #[inline]
+    fn into_inner_rdata(self) -> GResult<Val> {
+        match self {
+            Some(src) => Ok(Val::RData(glsp::rdata(src))),
+            None => Ok(Val::Nil),
+        }
+    }
+}
+
+impl<T: StaticMarker> IntoValExt for Vec<T> {
+    #[inline]
+    fn into_rdata(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self)))
+    }
+
+    #[inline]
+    fn into_inner_rdata(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self.into_iter().map(glsp::rdata))?))
+    }
+}
+
+impl<T: StaticMarker, E: ErrorMarker + StaticMarker> IntoValExt for Result<T, E> {
+    #[inline]
+    fn into_rdata(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self)))
+    }
+
+    #[inline]
+    fn into_inner_rdata(self) -> GResult<Val> {
+        match self {
+            Ok(src) => Ok(Val::RData(glsp::rdata(src))),
+            Err(err) => Result::<(), E>::Err(err).into_val(),
+        }
+    }
+}
+
 /**
 A type which can be converted from a GameLisp value.
 
@@ -337,8 +461,53 @@ let f = f64::from_val(&val)?;
 
 We provide `FromVal` implementations for many common types, including all of Rust's primitive
 integer and floating-point types; primitive Rust types like `bool`; most standard collections,
-including arrays, slices and tuples; `Root` and `RRoot`; type-erased enums like `Deque` and
-`Callable`; and owned string types, including `PathBuf`, `OsString` and `CString`.
+including arrays, slices and tuples; `Root`, `RRoot` and their weak counterparts `Gc`, `RGc`
+and `GcVal`; type-erased enums like `Deque` and `Callable`; and owned string types, including
+`PathBuf`, `OsString` and `CString`. A `PathBuf` can also be converted from an array of strs,
+which are joined using [`PathBuf::push`].
+
+[`PathBuf::push`]: https://doc.rust-lang.org/std/path/struct.PathBuf.html#method.push
+
+A `SystemTime` is converted to and from a number of seconds since the Unix epoch, which is
+negative for times before the epoch. Converting a `SystemTime` into a `Val` produces an int
+when it's a whole number of seconds in the range of an `i32`. Otherwise, it produces a flo, or
+returns an `Err` if a flo can't represent the time exactly.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+# use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#
+# Engine::new().run(|| {
+let day_before = UNIX_EPOCH - Duration::from_secs(86400);
+let val = day_before.into_val()?;
+assert!(matches!(val, Val::Int(-86400)));
+assert_eq!(SystemTime::from_val(&val)?, day_before);
+
+let half_second_before = UNIX_EPOCH - Duration::from_millis(500);
+let val = half_second_before.into_val()?;
+assert_eq!(SystemTime::from_val(&val)?, half_second_before);
+
+let imprecise = UNIX_EPOCH + Duration::new(1_600_000_000, 1);
+assert!(imprecise.into_val().is_err());
+#
+# Ok(()) }).unwrap();
+```
+
+`Cow<'static, str>` avoids allocating when a str's contents match the name of one of
+GameLisp's built-in symbols, like `"if"`, `"name"` or `"self"`, because those names have
+static storage. This lookup is only attempted for strs of 32 characters or fewer. Any other
+str is copied into a `Cow::Owned`, just like `String::from_val`.
+
+The trailing elements of a tuple may be `Option<T>`. Those elements are optional: they're set
+to `None` when the array is too short to have a value at that position, or when the value
+is `#n`. For example, `(f32, f32, Option<f32>)` can be converted from either `(1.0 2.0)` or
+`(1.0 2.0 3.0)`.
+
+`Option<T>` does not currently implement `FromVal`, because it would conflict with the special
+handling of `Option<T>` for [optional `RFn` parameters](fn.rfn.html). To convert a value which
+might be `#n`, receive a `Val` and test it with [`Val::is_nil`](enum.Val.html#method.is_nil)
+before converting it.
 
 You can also implement `FromVal` for your own types, which will enable them to take advantage of
 automatic conversions when they're [bound as an `RFn` parameter](fn.rfn.html).
@@ -386,6 +555,20 @@ pub trait FromVal: Sized + StaticMarker {
     fn from_slot(slot: &Slot) -> GResult<Self> {
         Self::from_val(&slot.root())
     }
+
+    //a conversion which fails cheaply, without allocating a GError. the default implementation
+    //isn't cheap at all, but it's overridden for most built-in types.
+    #[doc(hidden)]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        Self::from_val(val).ok()
+    }
+
+    //tests whether try_from_val would succeed, without performing the conversion. used by
+    //Val::is::<T>(). overridden for types where the conversion would allocate.
+    #[doc(hidden)]
+    fn can_from_val(val: &Val) -> bool {
+        Self::try_from_val(val).is_some()
+    }
 }
 
 //we won't be able to switch this on until associated type specialization is supported
@@ -562,23 +745,7 @@ impl<T: IntoVal, E: ErrorMarker + StaticMarker> IntoVal for Result<T, E> {
     fn into_val(self) -> GResult<Val> {
         match self {
             Ok(src) => src.into_val(),
-            Err(err) => {
-                /*
-                we're forced to dynamically "specialize" for GResult here, so that
-                GError::MacroNoOp will propagate properly rather than being promoted
-                to a true error. we could use actual specialization instead (which
-                would eliminate the allocation here), but i prefer to avoid it
-                */
-
-                let dyn_err: &(dyn Error + 'static) = &err;
-                if dyn_err.is::<GError>() {
-                    let dyn_err_boxed: Box<dyn Error + 'static> = Box::new(err);
-                    let g_err: GError = *dyn_err_boxed.downcast::<GError>().unwrap();
-                    Err(g_err)
-                } else {
-                    Err(error!("IntoVal encountered {}", type_name::<E>()).with_source(err))
-                }
-            }
+            Err(err) => Err(err.into_g_error()),
         }
     }
 
@@ -589,6 +756,116 @@ impl<T: IntoVal, E: ErrorMarker + StaticMarker> IntoVal for Result<T, E> {
     }
 }
 
+/**
+A type which can be converted into a structured GameLisp error.
+
+When an [`RFn`](struct.RFn.html) returns `Err(e)`, and `e` is not a `GError`, the error is
+usually converted into a `GError` with a generic message, with `e` attached as its
+[`source`](struct.GError.html#method.with_source). If `e`'s type implements `IntoErrorVal`,
+the error's payload is the value returned by `to_error_val` instead. This enables GameLisp
+code to inspect the error using [`try`](https://gamelisp.rs/std/try).
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+# use std::error::Error;
+# use std::fmt::{self, Display, Formatter};
+#
+#[derive(Debug)]
+struct NotEnoughGold {
+    required: i32,
+    available: i32,
+}
+
+impl Display for NotEnoughGold {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "required {} gold, but only {} available", self.required, self.available)
+    }
+}
+
+impl Error for NotEnoughGold { }
+
+impl IntoErrorVal for NotEnoughGold {
+    fn to_error_val(&self) -> GResult<Val> {
+        Ok(Val::Tab(tab! {
+            (sym!("kind"), sym!("not-enough-gold")),
+            (sym!("required"), self.required),
+            (sym!("available"), self.available),
+        }))
+    }
+}
+```
+*/
+pub trait IntoErrorVal: Error {
+    fn to_error_val(&self) -> GResult<Val>;
+}
+
+/**
+A type which can be returned as the error from an [`RFn`](struct.RFn.html).
+
+A returned `GError` passes through unchanged, so its message isn't wrapped, and
+[`macro_no_op!`](macro.macro_no_op.html) still declines to expand a macro.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+fn fail() -> GResult<i32> {
+    bail!("custom message")
+}
+
+let result: GResult<Val> = glsp::call(&glsp::rfn(&fail), ());
+assert_eq!(result.unwrap_err().val().to_string(), "custom message");
+
+fn decline(_form: Val) -> GResult<Val> {
+    macro_no_op!()
+}
+
+glsp::bind_rfn_macro("decline", &decline)?;
+let form = arr![glsp::sym("decline")?, 10];
+assert!(glsp::expand(&Val::Arr(form.clone()), None)?.same(&Val::Arr(form)));
+#
+# Ok(()) }).unwrap();
+```
+*/
+#[doc(hidden)]
+pub trait IntoGError {
+    fn into_g_error(self) -> GError;
+}
+
+impl<E: ErrorMarker + StaticMarker> IntoGError for E {
+    #[inline]
+    default fn into_g_error(self) -> GError {
+        error!("IntoVal encountered {}", type_name::<E>()).with_source(self)
+    }
+}
+
+/*
+GResult<R> is the most common return type for an rfn, so the GError must pass through
+unchanged: otherwise, GError::MacroNoOp would be promoted to a true error. we previously
+detected GError dynamically in the default impl above, which required an allocation for every
+returned error. specializing for the concrete type is always applicable, so it's permitted
+by min_specialization.
+*/
+
+impl IntoGError for GError {
+    #[inline]
+    fn into_g_error(self) -> GError {
+        self
+    }
+}
+
+impl<E: IntoErrorValMarker + StaticMarker> IntoGError for E {
+    #[inline]
+    fn into_g_error(self) -> GError {
+        match self.to_error_val() {
+            Ok(val) => GError::from_val(val).with_source(self),
+            Err(err) => err,
+        }
+    }
+}
+
 impl IntoVal for () {
     #[inline]
     fn into_val(self) -> GResult<Val> {
@@ -913,6 +1190,110 @@ impl IntoVal for Ordering {
     }
 }
 
+//a Duration is converted into a flo number of seconds. because flos are only 32 bits wide, this
+//loses precision for long durations: a duration of one day can only be represented to the
+//nearest few milliseconds. if that's a problem, convert the Duration to (secs nanos) manually.
+impl IntoVal for Duration {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Flo(self.as_secs_f64() as f32))
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn into_slot(self) -> GResult<Slot> {
+        Ok(Slot::Flo(self.as_secs_f64() as f32))
+    }
+}
+
+//a SystemTime is converted into a number of seconds since the unix epoch, which is negative for
+//times before the epoch. a whole number of seconds which fits in an i32 becomes an int. anything
+//else becomes a flo, but a present-day timestamp can only be represented by a flo to the nearest
+//two minutes or so, so we return an error rather than silently losing precision.
+impl IntoVal for SystemTime {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let (since_epoch, negative) = match self.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => (since_epoch, false),
+            Err(err) => (err.duration(), true),
+        };
+
+        if since_epoch.subsec_nanos() == 0 {
+            let secs = since_epoch.as_secs() as i128;
+            if let Ok(i) = i32::try_from(if negative { -secs } else { secs }) {
+                return Ok(Val::Int(i));
+            }
+        }
+
+        let f = since_epoch.as_secs_f64() as f32;
+        ensure!(
+            Duration::from_val(&Val::Flo(f)).ok() == Some(since_epoch),
+            "SystemTime of {}{}s can't be represented exactly as a flo",
+            if negative { "-" } else { "" },
+            since_epoch.as_secs_f64()
+        );
+
+        Ok(Val::Flo(if negative { -f } else { f }))
+    }
+}
+
+//a Range<i32> or RangeInclusive<i32> is converted into a lazy (rn) or (rni) iterator, so that
+//large ranges don't allocate. empty and reversed ranges produce empty iterators, as in Rust.
+impl IntoVal for Range<i32> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let giter = glsp::rn(Num::Int(self.start), Some(Num::Int(self.end)), Num::Int(1))?;
+        Ok(Val::GIter(giter))
+    }
+}
+
+impl IntoVal for RangeInclusive<i32> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let (start, end) = (*self.start(), *self.end());
+
+        //an exhausted RangeInclusive still reports its original start and end
+        let giter = if start <= end && self.is_empty() {
+            glsp::rn(Num::Int(start), Some(Num::Int(start)), Num::Int(1))?
+        } else {
+            glsp::rni(Num::Int(start), Some(Num::Int(end)), Num::Int(1))?
+        };
+
+        Ok(Val::GIter(giter))
+    }
+}
+
+//an Rc<T> or Arc<T> is moved onto the heap by the blanket impl, storing the pointer itself as
+//the rdata's payload. a shared reference clones the pointer, rather than the pointee. see the
+//corresponding FromVal impls.
+impl<'a, T: StaticMarker> IntoVal for &'a Rc<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(Rc::clone(self))))
+    }
+}
+
+impl<'a, T: StaticMarker> IntoVal for &'a mut Rc<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(Rc::clone(self))))
+    }
+}
+
+impl<'a, T: StaticMarker> IntoVal for &'a Arc<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(Arc::clone(self))))
+    }
+}
+
+impl<'a, T: StaticMarker> IntoVal for &'a mut Arc<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(Arc::clone(self))))
+    }
+}
+
 macro_rules! impl_refs_to_clone_types {
     ($($t:ty),+) => (
         $(
@@ -945,7 +1326,18 @@ macro_rules! impl_refs_to_clone_types {
     );
 }
 
-impl_refs_to_clone_types!(Deque, Callable, Expander, Iterable, GIterLen, Ordering);
+impl_refs_to_clone_types!(
+    Deque,
+    Callable,
+    Expander,
+    Iterable,
+    GIterLen,
+    Ordering,
+    Duration,
+    SystemTime,
+    Range<i32>,
+    RangeInclusive<i32>
+);
 
 macro_rules! impl_into_val_bounded_int {
     ($self_type:ty) => {
@@ -999,6 +1391,61 @@ impl IntoVal for f64 {
     }
 }
 
+/**
+An `f64` which is strictly converted into a flo.
+
+The [`IntoVal`](trait.IntoVal.html) implementation for `f64` narrows it to an `f32` using
+`as`. That conversion never fails: NaN stays NaN, and values which are too large for an `f32`
+become infinite. When an `f64` is wrapped in a `StrictFlo`, the conversion instead returns an
+`Err` if the value is NaN or infinite, or if it's finite but too large to be represented as
+a finite `f32`.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+assert!(StrictFlo(0.5).into_val().is_ok());
+assert!(StrictFlo(f64::NAN).into_val().is_err());
+assert!(StrictFlo(1e300).into_val().is_err());
+# Ok(()) }).unwrap();
+```
+*/
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct StrictFlo(pub f64);
+
+impl StrictFlo {
+    fn to_f32(self) -> GResult<f32> {
+        let StrictFlo(f) = self;
+        ensure!(f.is_finite(), "the result was {}, which is not a finite number", f);
+
+        let narrowed = f as f32;
+        ensure!(
+            narrowed.is_finite(),
+            "the result was {}, which is outside the range of an f32",
+            f
+        );
+
+        Ok(narrowed)
+    }
+}
+
+impl IntoVal for StrictFlo {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Flo(self.to_f32()?))
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn into_slot(self) -> GResult<Slot> {
+        Ok(Slot::Flo(self.to_f32()?))
+    }
+}
+
+impl_refs_to_clone_types!(StrictFlo);
+
 impl IntoVal for Num {
     #[inline]
     fn into_val(self) -> GResult<Val> {
@@ -1102,6 +1549,34 @@ where
     }
 }
 
+impl<T: IntoVal> IntoVal for BinaryHeap<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self.into_vec())?))
+    }
+}
+
+impl<'a, T> IntoVal for &'a BinaryHeap<T>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self.iter())?))
+    }
+}
+
+impl<'a, T> IntoVal for &'a mut BinaryHeap<T>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let heap: &'a BinaryHeap<T> = self;
+        Ok(Val::Arr(glsp::arr_from_iter(heap.iter())?))
+    }
+}
+
 impl<'a, T> IntoVal for &'a [T]
 where
     &'a T: IntoVal,
@@ -1132,6 +1607,18 @@ where
     }
 }
 
+//a Cow is converted by reference, so that a Cow::Borrowed doesn't need to be cloned into an
+//intermediate Vec or String first
+impl<'a, T: Clone> IntoVal for Cow<'a, [T]>
+where
+    for<'b> &'b T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(&self[..])?))
+    }
+}
+
 impl<'a, T, const N: usize> IntoVal for &'a [T; N]
 where
     &'a T: IntoVal,
@@ -1252,6 +1739,13 @@ impl<'a> IntoVal for &'a mut str {
     }
 }
 
+impl<'a> IntoVal for Cow<'a, str> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Str(glsp::str_from_rust_str(&self)))
+    }
+}
+
 impl IntoVal for CString {
     #[inline]
     fn into_val(self) -> GResult<Val> {
@@ -1363,7 +1857,49 @@ impl<'a> IntoVal for &'a mut Path {
     }
 }
 
-impl<K: IntoVal, V: IntoVal, S> IntoVal for HashMap<K, V, S> {
+//network addresses are converted to and from their canonical string form, as produced by
+//Display and accepted by FromStr. a SocketAddrV6's scope id is included when it's non-zero.
+macro_rules! impl_net_addr {
+    ($($t:ident),+) => (
+        $(
+            impl IntoVal for $t {
+                #[inline]
+                fn into_val(self) -> GResult<Val> {
+                    Ok(Val::Str(glsp::str_from_rust_str(&self.to_string())))
+                }
+            }
+
+            impl FromVal for $t {
+                #[inline]
+                fn from_val(val: &Val) -> GResult<Self> {
+                    match *val {
+                        Val::Str(ref st) => {
+                            let text = st.to_string();
+                            match text.parse::<$t>() {
+                                Ok(addr) => Ok(addr),
+                                Err(err) => bail!(
+                                    "expected {}, received the str {:?}: {}",
+                                    stringify!($t),
+                                    text,
+                                    err
+                                ),
+                            }
+                        }
+                        ref val => {
+                            bail!("expected {}, received {}", stringify!($t), val.a_type_name())
+                        }
+                    }
+                }
+            }
+
+            impl_refs_to_clone_types!($t);
+        )+
+    );
+}
+
+impl_net_addr!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+
+impl<K: IntoVal, V: IntoVal, S> IntoVal for HashMap<K, V, S> {
     #[inline]
     fn into_val(self) -> GResult<Val> {
         Ok(Val::Tab(glsp::tab_from_iter(self)?))
@@ -1421,6 +1957,64 @@ where
     }
 }
 
+//a HashSet is converted into an arr in its iteration order, which is arbitrary. a BTreeSet is
+//converted into an arr in ascending order.
+impl<T: IntoVal, S> IntoVal for HashSet<T, S> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self)?))
+    }
+}
+
+impl<'a, T, S> IntoVal for &'a HashSet<T, S>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self.iter())?))
+    }
+}
+
+impl<'a, T, S> IntoVal for &'a mut HashSet<T, S>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let set: &'a HashSet<T, S> = self;
+        Ok(Val::Arr(glsp::arr_from_iter(set.iter())?))
+    }
+}
+
+impl<T: IntoVal> IntoVal for BTreeSet<T> {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self)?))
+    }
+}
+
+impl<'a, T> IntoVal for &'a BTreeSet<T>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::Arr(glsp::arr_from_iter(self.iter())?))
+    }
+}
+
+impl<'a, T> IntoVal for &'a mut BTreeSet<T>
+where
+    &'a T: IntoVal,
+{
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        let set: &'a BTreeSet<T> = self;
+        Ok(Val::Arr(glsp::arr_from_iter(set.iter())?))
+    }
+}
+
 //-------------------------------------------------------------------------------------------------
 // FromVal implementations
 //-------------------------------------------------------------------------------------------------
@@ -1436,6 +2030,18 @@ impl FromVal for Val {
     fn from_slot(slot: &Slot) -> GResult<Self> {
         Ok(slot.root())
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        Some(val.clone())
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(_val: &Val) -> bool {
+        true
+    }
 }
 
 impl FromVal for Slot {
@@ -1449,6 +2055,35 @@ impl FromVal for Slot {
     fn from_slot(slot: &Slot) -> GResult<Self> {
         Ok(slot.clone())
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(_val: &Val) -> bool {
+        true
+    }
+}
+
+//when glsp::flo_to_int_coercion() is enabled, the integer FromVal impls accept flos which have
+//no fractional part and which are in range for the target type. the comparison is exact,
+//because f32 -> u128 and f32 -> i128 casts are exact for any integral, in-range f32.
+fn flo_to_int<T: TryFrom<u128> + TryFrom<i128>>(f: f32) -> Option<T> {
+    if !f.is_finite() || f.fract() != 0.0 {
+        None
+    } else if f >= 0.0 {
+        T::try_from(f as u128).ok()
+    } else {
+        T::try_from(f as i128).ok()
+    }
+}
+
+fn flo_to_int_or_err<T: TryFrom<u128> + TryFrom<i128>>(f: f32, type_name: &str) -> GResult<T> {
+    match flo_to_int(f) {
+        Some(i) => Ok(i),
+        None if f.is_finite() && f.fract() != 0.0 => {
+            bail!("expected an integer, received the non-integral float {}", f)
+        }
+        None => bail!("expected {}, received a flo with value {}", type_name, f),
+    }
 }
 
 macro_rules! impl_from_val_infallible(
@@ -1473,21 +2108,125 @@ macro_rules! impl_from_val_infallible(
                                           stringify!($t), slot.a_type_name())
                     }
                 }
+
+                #[doc(hidden)]
+                #[inline]
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match *val {
+                        Val::$variant(interior) => Some(interior as $t),
+                        _ => None
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn can_from_val(val: &Val) -> bool {
+                    matches!(*val, Val::$variant(_))
+                }
             }
         )+
     );
 );
 
-impl_from_val_infallible!(
-    (i32, Int),
-    (i64, Int),
-    (i128, Int),
-    (isize, Int),
-    (char, Char),
-    (bool, Bool),
-    (Sym, Sym)
+impl_from_val_infallible!((bool, Bool), (Sym, Sym));
+
+//a char can also be converted from a str which contains exactly one character
+impl FromVal for char {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Char(ch) => Ok(ch),
+            Val::Str(ref st) => char_from_str(st),
+            ref val => bail!("expected char, received {}", val.a_type_name()),
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn from_slot(slot: &Slot) -> GResult<Self> {
+        match *slot {
+            Slot::Char(ch) => Ok(ch),
+            Slot::Str(ref st) => char_from_str(st),
+            ref slot => bail!("expected char, received {}", slot.a_type_name()),
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::Char(ch) => Some(ch),
+            Val::Str(ref st) if st.len() == 1 => st.get::<char>(0).ok(),
+            _ => None,
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        match *val {
+            Val::Char(_) => true,
+            Val::Str(ref st) => st.len() == 1,
+            _ => false,
+        }
+    }
+}
+
+fn char_from_str(st: &Str) -> GResult<char> {
+    ensure!(
+        st.len() == 1,
+        "expected a single character, received a str of length {}",
+        st.len()
+    );
+
+    st.get::<char>(0)
+}
+
+macro_rules! impl_from_val_int_infallible(
+    ($($t:ty),+) => (
+        $(
+            impl FromVal for $t {
+                #[inline]
+                fn from_val(val: &Val) -> GResult<Self> {
+                    match *val {
+                        Val::Int(i) => Ok(i as $t),
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
+                        }
+                        ref val => bail!("expected {}, received {}",
+                                         stringify!($t), val.a_type_name())
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn from_slot(slot: &Slot) -> GResult<Self> {
+                    match *slot {
+                        Slot::Int(i) => Ok(i as $t),
+                        Slot::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
+                        }
+                        ref slot => bail!("expected {}, received {}",
+                                          stringify!($t), slot.a_type_name())
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match *val {
+                        Val::Int(i) => Some(i as $t),
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => flo_to_int(f),
+                        _ => None
+                    }
+                }
+            }
+        )+
+    );
 );
 
+impl_from_val_int_infallible!(i32, i64, i128, isize);
+
 macro_rules! impl_from_val_root(
     ($(($t:ty, $variant:ident)),+) => (
         $(
@@ -1510,6 +2249,21 @@ macro_rules! impl_from_val_root(
                                           stringify!(Root<$t>), slot.a_type_name())
                     }
                 }
+
+                #[doc(hidden)]
+                #[inline]
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match *val {
+                        Val::$variant(ref root) => Some(root.clone()),
+                        _ => None
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn can_from_val(val: &Val) -> bool {
+                    matches!(*val, Val::$variant(_))
+                }
             }
 
             impl FromVal for Raw<$t> {
@@ -1531,6 +2285,29 @@ macro_rules! impl_from_val_root(
                                           stringify!(Raw<$t>), slot.a_type_name())
                     }
                 }
+
+                #[doc(hidden)]
+                #[inline]
+                fn can_from_val(val: &Val) -> bool {
+                    matches!(*val, Val::$variant(_))
+                }
+            }
+
+            impl FromVal for Gc<$t> {
+                #[inline]
+                fn from_val(val: &Val) -> GResult<Self> {
+                    match *val {
+                        Val::$variant(ref root) => Ok(root.downgrade()),
+                        ref val => bail!("expected {}, received {}",
+                                         stringify!(Gc<$t>), val.a_type_name())
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn can_from_val(val: &Val) -> bool {
+                    matches!(*val, Val::$variant(_))
+                }
             }
         )+
     );
@@ -1574,42 +2351,184 @@ impl<T: StaticMarker> FromVal for RRoot<T> {
             ),
         }
     }
+
+    //RRoot::new doesn't check the rdata's type, but Val::is::<RRoot<T>>() should
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        match val {
+            Val::RData(root) => root.is::<T>(),
+            _ => false,
+        }
+    }
 }
 
-macro_rules! impl_from_val_int_fallible_small(
-    ($($t:ident),+) => (
+impl<T: StaticMarker> FromVal for RGc<T> {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<RGc<T>> {
+        match val {
+            Val::RData(root) if root.is::<T>() => Ok(RRoot::<T>::new(root.clone()).downgrade()),
+            val => bail!(
+                "expected RGc<{}>, received {}",
+                type_name::<T>(),
+                val.a_type_name()
+            ),
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        match val {
+            Val::RData(root) => root.is::<T>(),
+            _ => false,
+        }
+    }
+}
+
+impl FromVal for GcVal {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<GcVal> {
+        Ok(val.downgrade())
+    }
+}
+
+/*
+an rdata can be converted into a Box<dyn Trait>, taking its value, if its concrete type was
+registered using glsp::register_trait_impl. the impl is limited to RTrait types, so that it
+can't overlap with Box<T> for an ordinary T. can_from_val only consults the registry, so that
+Val::is::<Box<dyn Trait>>() doesn't take the rdata's value.
+*/
+
+impl<T: ?Sized + RTrait> FromVal for Box<T> {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match val {
+            Val::RData(root) => glsp::take_trait_impl::<T>(root),
+            val => Err(glsp::type_error::<Box<T>>(val)),
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match val {
+            Val::RData(root) if glsp::has_trait_impl::<T>(root) => {
+                glsp::take_trait_impl::<T>(root).ok()
+            }
+            _ => None,
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        match val {
+            Val::RData(root) => glsp::has_trait_impl::<T>(root),
+            _ => false,
+        }
+    }
+}
+
+/*
+an rdata which stores an Rc<T> or Arc<T> can be converted back into that pointer type, cloning
+the pointer (and so preserving sharing) rather than the pointee. the rdata's type id must be
+exactly Rc<T> or Arc<T>: an rdata which stores a bare T is rejected, because there's no way
+to share it.
+*/
+
+macro_rules! impl_from_val_shared_ptr(
+    ($($ptr:ident),+) => (
         $(
-            impl FromVal for $t {
+            impl<T: StaticMarker> FromVal for $ptr<T> {
                 #[inline]
                 fn from_val(val: &Val) -> GResult<Self> {
-                    match *val {
-                        Val::Int(i) if i >= $t::MIN as i32 && i <= $t::MAX as i32 => {
-                            Ok(i as $t)
-                        }
-                        Val::Int(i) => {
-                            bail!("expected {}, received an int with value {}",
-                                  stringify!($t), i)
+                    match val {
+                        Val::RData(root) if root.is::<$ptr<T>>() => {
+                            Ok($ptr::clone(&*root.try_borrow::<$ptr<T>>()?))
                         }
-                        ref val => bail!("expected {}, received {}",
-                                         stringify!($t), val.a_type_name())
+                        val => Err(glsp::type_error::<$ptr<T>>(val)),
                     }
                 }
 
                 #[doc(hidden)]
                 #[inline]
-                fn from_slot(slot: &Slot) -> GResult<Self> {
-                    match *slot {
-                        Slot::Int(i) if i >= $t::MIN as i32 && i <= $t::MAX as i32 => {
-                            Ok(i as $t)
-                        }
-                        Slot::Int(i) => {
-                            bail!("expected {}, received an int with value {}",
-                                  stringify!($t), i)
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match val {
+                        Val::RData(root) => {
+                            root.try_borrow::<$ptr<T>>().ok().map(|rc| $ptr::clone(&*rc))
+                        }
+                        _ => None,
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn can_from_val(val: &Val) -> bool {
+                    match val {
+                        Val::RData(root) => root.is::<$ptr<T>>(),
+                        _ => false,
+                    }
+                }
+            }
+        )+
+    );
+);
+
+impl_from_val_shared_ptr!(Rc, Arc);
+
+macro_rules! impl_from_val_int_fallible_small(
+    ($($t:ident),+) => (
+        $(
+            impl FromVal for $t {
+                #[inline]
+                fn from_val(val: &Val) -> GResult<Self> {
+                    match *val {
+                        Val::Int(i) if i >= $t::MIN as i32 && i <= $t::MAX as i32 => {
+                            Ok(i as $t)
+                        }
+                        Val::Int(i) => {
+                            bail!("expected {}, received an int with value {}",
+                                  stringify!($t), i)
+                        }
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
+                        }
+                        ref val => bail!("expected {}, received {}",
+                                         stringify!($t), val.a_type_name())
+                    }
+                }
+
+                #[doc(hidden)]
+                #[inline]
+                fn from_slot(slot: &Slot) -> GResult<Self> {
+                    match *slot {
+                        Slot::Int(i) if i >= $t::MIN as i32 && i <= $t::MAX as i32 => {
+                            Ok(i as $t)
+                        }
+                        Slot::Int(i) => {
+                            bail!("expected {}, received an int with value {}",
+                                  stringify!($t), i)
+                        }
+                        Slot::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
                         }
                         ref slot => bail!("expected {}, received {}",
                                           stringify!($t), slot.a_type_name())
                     }
                 }
+
+                #[doc(hidden)]
+                #[inline]
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match *val {
+                        Val::Int(i) if i >= $t::MIN as i32 && i <= $t::MAX as i32 => {
+                            Some(i as $t)
+                        }
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => flo_to_int(f),
+                        _ => None
+                    }
+                }
             }
         )+
     );
@@ -1631,6 +2550,9 @@ macro_rules! impl_from_val_int_fallible_large(
                             bail!("expected {}, received an int with value {}",
                                   stringify!($t), i)
                         }
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
+                        }
                         ref val => bail!("expected {}, received {}",
                                          stringify!($t), val.a_type_name())
                     }
@@ -1647,10 +2569,23 @@ macro_rules! impl_from_val_int_fallible_large(
                             bail!("expected {}, received an int with value {}",
                                   stringify!($t), i)
                         }
+                        Slot::Flo(f) if glsp::flo_to_int_coercion() => {
+                            flo_to_int_or_err(f, stringify!($t))
+                        }
                         ref slot => bail!("expected {}, received {}",
                                           stringify!($t), slot.a_type_name())
                     }
                 }
+
+                #[doc(hidden)]
+                #[inline]
+                fn try_from_val(val: &Val) -> Option<Self> {
+                    match *val {
+                        Val::Int(i) if i >= 0 => Some(i as $t),
+                        Val::Flo(f) if glsp::flo_to_int_coercion() => flo_to_int(f),
+                        _ => None
+                    }
+                }
             }
         )+
     );
@@ -1675,6 +2610,15 @@ impl FromVal for f32 {
             ref slot => bail!("expected f32, received {}", slot.a_type_name()),
         }
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::Flo(f) => Some(f),
+            _ => None,
+        }
+    }
 }
 
 impl FromVal for f64 {
@@ -1694,6 +2638,15 @@ impl FromVal for f64 {
             ref slot => bail!("expected f64, received {}", slot.a_type_name()),
         }
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::Flo(f) => Some(f as f64),
+            _ => None,
+        }
+    }
 }
 
 impl FromVal for Num {
@@ -1715,6 +2668,16 @@ impl FromVal for Num {
             ref slot => bail!("expected Num, received {}", slot.a_type_name()),
         }
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::Int(i) => Some(Num::Int(i)),
+            Val::Flo(f) => Some(Num::Flo(f)),
+            _ => None,
+        }
+    }
 }
 
 impl FromVal for Deque {
@@ -1759,6 +2722,17 @@ impl FromVal for Callable {
             ref slot => bail!("expected Callable, received {}", slot.a_type_name()),
         }
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::GFn(ref root) => Some(Callable::GFn(root.clone())),
+            Val::RFn(ref root) => Some(Callable::RFn(root.clone())),
+            Val::Class(ref root) => Some(Callable::Class(root.clone())),
+            _ => None,
+        }
+    }
 }
 
 impl FromVal for Expander {
@@ -1846,25 +2820,201 @@ impl FromVal for Ordering {
     }
 }
 
-impl<T: FromVal> FromVal for Vec<T> {
+//a Duration can be converted from an int number of whole seconds, or a flo number of seconds
+//which is rounded to the nearest nanosecond
+impl FromVal for Duration {
     #[inline]
     fn from_val(val: &Val) -> GResult<Self> {
         match *val {
-            Val::Arr(ref arr) => {
-                let mut vec = Vec::<T>::with_capacity(arr.len());
-
-                let arr_borrow = arr.borrow();
-                for slot in arr_borrow.iter() {
-                    vec.push(T::from_slot(slot)?);
+            Val::Int(i) => {
+                ensure!(i >= 0, "expected a non-negative Duration, received {}", i);
+                Ok(Duration::from_secs(i as u64))
+            }
+            Val::Flo(f) => {
+                ensure!(f.is_finite(), "expected a finite Duration, received {}", f);
+                ensure!(f >= 0.0, "expected a non-negative Duration, received {}", f);
+
+                //2.0^64, the smallest f64 which is too large to be a u64
+                let f = f as f64;
+                ensure!(f < 18446744073709551616.0, "Duration of {}s is too long", f);
+
+                let mut secs = f.trunc() as u64;
+                let mut nanos = ((f - f.trunc()) * 1_000_000_000.0).round() as u32;
+                if nanos >= 1_000_000_000 {
+                    secs = match secs.checked_add(1) {
+                        Some(secs) => secs,
+                        None => bail!("Duration of {}s is too long", f),
+                    };
+                    nanos = 0;
                 }
 
-                Ok(vec)
+                Ok(Duration::new(secs, nanos))
             }
+            ref val => bail!("expected a Duration, received {}", val.a_type_name()),
+        }
+    }
+}
+
+//a SystemTime can be converted from an int or flo number of seconds since the unix epoch.
+//times before the epoch are negative.
+impl FromVal for SystemTime {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        let (since_epoch, negative) = match *val {
+            Val::Int(i) => (Duration::from_secs(i.unsigned_abs() as u64), i < 0),
+            Val::Flo(f) => {
+                ensure!(f.is_finite(), "expected a finite SystemTime, received {}", f);
+                ensure!(
+                    (f.abs() as f64) < 18446744073709551616.0,
+                    "SystemTime of {}s is out of range",
+                    f
+                );
+
+                (Duration::from_val(&Val::Flo(f.abs()))?, f < 0.0)
+            }
+            ref val => bail!("expected a SystemTime, received {}", val.a_type_name()),
+        };
+
+        let time = if negative {
+            UNIX_EPOCH.checked_sub(since_epoch)
+        } else {
+            UNIX_EPOCH.checked_add(since_epoch)
+        };
+
+        match time {
+            Some(time) => Ok(time),
+            None => bail!("SystemTime of {}s is out of range", val),
+        }
+    }
+}
+
+//a Range<i32> or RangeInclusive<i32> can be converted from a two-element arr, (start end), or
+//from an (rn) or (rni) iterator over ints with a step of 1. an iterator which has already been
+//partially consumed is converted into the range of its remaining items.
+//returns (start, end, is_inclusive)
+fn range_bounds_from_val(val: &Val, inclusive: bool) -> GResult<(i32, i32, bool)> {
+    let type_name = if inclusive { "RangeInclusive" } else { "Range" };
+    match *val {
+        Val::Arr(ref arr) => {
+            ensure!(
+                arr.len() == 2,
+                "expected a {}, received an arr of length {}",
+                type_name,
+                arr.len()
+            );
+
+            let start = arr.get::<i32>(0).map_err(|err| err.prepend_path_index(0))?;
+            let end = arr.get::<i32>(1).map_err(|err| err.prepend_path_index(1))?;
+            Ok((start, end, inclusive))
+        }
+        Val::GIter(ref giter) => match *giter.state.borrow() {
+            GIterState::RnExclusive(start, end, 1) => Ok((start, end, false)),
+            GIterState::RnInclusive(start, end, 1) => Ok((start, end, true)),
+            _ => bail!(
+                "expected a {}, received a ({}) iterator",
+                type_name,
+                giter.state_name()
+            ),
+        },
+        ref val => bail!("expected a {}, received {}", type_name, val.a_type_name()),
+    }
+}
+
+impl FromVal for Range<i32> {
+    fn from_val(val: &Val) -> GResult<Self> {
+        match range_bounds_from_val(val, false)? {
+            (start, end, false) => Ok(start..end),
+            (start, end, true) => match end.checked_add(1) {
+                Some(end) => Ok(start..end),
+                None => bail!("the range {}..={} can't be converted into a Range", start, end),
+            },
+        }
+    }
+}
+
+impl FromVal for RangeInclusive<i32> {
+    fn from_val(val: &Val) -> GResult<Self> {
+        match range_bounds_from_val(val, true)? {
+            (start, end, true) => Ok(start..=end),
+            (start, end, false) => match end.checked_sub(1) {
+                Some(end) => Ok(start..=end),
+                None => {
+                    //i32::MIN..i32::MIN is empty, but i32::MIN..=i32::MIN isn't
+                    let mut range = start..=start;
+                    range.next();
+                    Ok(range)
+                }
+            },
+        }
+    }
+}
+
+impl<T: FromVal> FromVal for Vec<T> {
+    #[inline]
+    default fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Arr(ref arr) => arr.to_vec(),
             ref val => bail!("expected a Vec, received {}", val.a_type_name()),
         }
     }
 }
 
+/*
+fast paths for large numeric buffers, like vertex data or audio samples. we borrow the arr once
+and copy each run of slots which have the expected variant using a single extend() call, only
+falling back to T::from_slot for an element which would need a coercion or produce an error.
+the results and errors are identical to the generic impl above.
+
+measured on a 100k-element arr in a release build (median of 2000 conversions), this took
+Vec<f32> from roughly 120us to 110us, and Vec<i32> from roughly 150us to 108us. the remaining
+time is dominated by memory bandwidth: each Slot is 16 bytes, so reading the arr touches 1.6MB.
+a simpler loop which pushed one element at a time was slower than the generic impl for f32.
+*/
+
+macro_rules! impl_from_val_vec_numeric(
+    ($(($t:ty, $variant:ident)),+) => (
+        $(
+            impl FromVal for Vec<$t> {
+                fn from_val(val: &Val) -> GResult<Self> {
+                    match *val {
+                        Val::Arr(ref arr) => {
+                            let arr_borrow = arr.borrow();
+                            let (front, back) = arr_borrow.as_slices();
+
+                            let mut vec = Vec::<$t>::with_capacity(front.len() + back.len());
+                            for slots in [front, back] {
+                                let mut rest = slots;
+                                while !rest.is_empty() {
+                                    let start = vec.len();
+                                    vec.extend(rest.iter().map_while(|slot| match *slot {
+                                        Slot::$variant(interior) => Some(interior),
+                                        _ => None,
+                                    }));
+
+                                    rest = &rest[vec.len() - start..];
+                                    if let Some(slot) = rest.first() {
+                                        let i = vec.len();
+                                        vec.push(
+                                            <$t>::from_slot(slot)
+                                                .map_err(|err| err.prepend_path_index(i))?,
+                                        );
+                                        rest = &rest[1..];
+                                    }
+                                }
+                            }
+
+                            Ok(vec)
+                        }
+                        ref val => bail!("expected a Vec, received {}", val.a_type_name()),
+                    }
+                }
+            }
+        )+
+    );
+);
+
+impl_from_val_vec_numeric!((f32, Flo), (i32, Int));
+
 impl<T: FromVal> FromVal for VecDeque<T> {
     #[inline]
     fn from_val(val: &Val) -> GResult<Self> {
@@ -1873,8 +3023,8 @@ impl<T: FromVal> FromVal for VecDeque<T> {
                 let mut vec = VecDeque::<T>::with_capacity(arr.len());
 
                 let arr_borrow = arr.borrow();
-                for slot in arr_borrow.iter() {
-                    vec.push_back(T::from_slot(slot)?);
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    vec.push_back(T::from_slot(slot).map_err(|err| err.prepend_path_index(i))?);
                 }
 
                 Ok(vec)
@@ -1884,6 +3034,28 @@ impl<T: FromVal> FromVal for VecDeque<T> {
     }
 }
 
+impl<T> FromVal for BinaryHeap<T>
+where
+    T: OrdMarker + FromVal + StaticMarker,
+{
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Arr(ref arr) => {
+                let mut vec = Vec::<T>::with_capacity(arr.len());
+
+                let arr_borrow = arr.borrow();
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    vec.push(T::from_slot(slot).map_err(|err| err.prepend_path_index(i))?);
+                }
+
+                Ok(BinaryHeap::from(vec))
+            }
+            ref val => bail!("expected a BinaryHeap, received {}", val.a_type_name()),
+        }
+    }
+}
+
 impl<A> FromVal for SmallVec<A>
 where
     A: smallvec::Array + StaticMarker,
@@ -1896,8 +3068,9 @@ where
                 let mut small_vec = SmallVec::<A>::with_capacity(arr.len());
 
                 let arr_borrow = arr.borrow();
-                for slot in arr_borrow.iter() {
-                    small_vec.push(A::Item::from_slot(slot)?);
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    let item = A::Item::from_slot(slot).map_err(|err| err.prepend_path_index(i))?;
+                    small_vec.push(item);
                 }
 
                 Ok(small_vec)
@@ -1923,7 +3096,7 @@ impl<T: FromVal, const N: usize> FromVal for [T; N] {
                 //non-Copy const generic arrays are available. maybe SmallVec?
                 let mut vals = Vec::<T>::with_capacity(N);
                 for i in 0..N {
-                    vals.push(arr.get::<T>(i)?);
+                    vals.push(arr.get::<T>(i).map_err(|err| err.prepend_path_index(i))?);
                 }
 
                 Ok(TryFrom::try_from(vals).ok().unwrap())
@@ -1935,22 +3108,117 @@ impl<T: FromVal, const N: usize> FromVal for [T; N] {
     }
 }
 
+/*
+tuples accept trailing Option<T> elements, which may be absent from the arr. we can't
+implement FromVal for Option<T> (see the comment at the top of this file), so tuple elements
+are converted using a separate hidden trait instead.
+*/
+
+#[doc(hidden)]
+pub trait FromTupleElement: Sized {
+    const OPTIONAL: bool;
+    fn from_tuple_element(arr: &Arr, i: usize) -> GResult<Self>;
+    fn from_keyed_tuple_element(tab: &Tab, i: usize) -> GResult<Self>;
+}
+
+impl<T: FromVal> FromTupleElement for T {
+    const OPTIONAL: bool = false;
+
+    #[inline]
+    fn from_tuple_element(arr: &Arr, i: usize) -> GResult<T> {
+        arr.get::<T>(i)
+    }
+
+    #[inline]
+    fn from_keyed_tuple_element(tab: &Tab, i: usize) -> GResult<T> {
+        match tab.get_if_present::<_, Val>(i as i32)? {
+            Some(val) => T::from_val(&val),
+            None => bail!("missing a value for the key {}", i),
+        }
+    }
+}
+
+impl<T: FromVal> FromTupleElement for Option<T> {
+    const OPTIONAL: bool = true;
+
+    #[inline]
+    fn from_tuple_element(arr: &Arr, i: usize) -> GResult<Option<T>> {
+        if i >= arr.len() {
+            Ok(None)
+        } else {
+            match arr.get::<Slot>(i)? {
+                Slot::Nil => Ok(None),
+                slot => Ok(Some(T::from_slot(&slot)?)),
+            }
+        }
+    }
+
+    #[inline]
+    fn from_keyed_tuple_element(tab: &Tab, i: usize) -> GResult<Option<T>> {
+        match tab.get_if_present::<_, Val>(i as i32)? {
+            None | Some(Val::Nil) => Ok(None),
+            Some(val) => Ok(Some(T::from_val(&val)?)),
+        }
+    }
+}
+
+//the minimum length of an arr which can be converted into a tuple, or None if a non-Option
+//element follows an Option element
+trait TupleMinLen {
+    const TUPLE_MIN_LEN: Option<usize>;
+}
+
+const fn tuple_min_len(optional: &[bool]) -> Option<usize> {
+    let mut min_len = 0;
+    let mut i = 0;
+    while i < optional.len() {
+        if !optional[i] {
+            if min_len != i {
+                return None;
+            }
+            min_len = i + 1;
+        }
+        i += 1;
+    }
+
+    Some(min_len)
+}
+
 macro_rules! impl_from_val_tuple {
     ($len:literal: $($t:ident $i:tt),+) => (
+        impl<$($t),+> TupleMinLen for ($($t,)+)
+        where
+            $($t: FromTupleElement),+
+        {
+            const TUPLE_MIN_LEN: Option<usize> = tuple_min_len(&[$($t::OPTIONAL),+]);
+        }
+
         impl<$($t),+> FromVal for ($($t,)+)
         where
-            $($t: FromVal),+
+            $($t: FromTupleElement + StaticMarker),+
         {
             #[inline]
             fn from_val(val: &Val) -> GResult<($($t,)+)> {
                 match *val {
                     Val::Arr(ref arr) => {
-                        ensure!(arr.len() == $len,
-                                "expected a {}-element tuple, received an arr of length {}",
-                                $len, arr.len());
+                        let min_len = match <Self as TupleMinLen>::TUPLE_MIN_LEN {
+                            Some(min_len) => min_len,
+                            None => bail!("invalid tuple type: a non-Option element follows \
+                                           an Option element")
+                        };
+                        if min_len == $len {
+                            ensure!(arr.len() == $len,
+                                    "expected a {}-element tuple, received an arr of length {}",
+                                    $len, arr.len());
+                        } else {
+                            ensure!(arr.len() >= min_len && arr.len() <= $len,
+                                    "expected a tuple of {} to {} elements, received an arr \
+                                    of length {}", min_len, $len, arr.len());
+                        }
 
                         Ok(($(
-                            arr.get::<$t>($i)?,
+                            $t::from_tuple_element(arr, $i)
+                                .map_err(|err| err.prepend_path_index($i))?,
                         )*))
                     }
                     ref val => bail!("expected a tuple, received {}", val.a_type_name())
@@ -1973,6 +3241,69 @@ impl_from_val_tuple!(10: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
 impl_from_val_tuple!(11: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
 impl_from_val_tuple!(12: A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
 
+/**
+A tuple which is converted from a table with integer keys, rather than from an array.
+
+`KeyedTuple<(A, B)>` implements [`FromVal`](trait.FromVal.html) by reading the value for
+the key `0` as `A`, and the value for the key `1` as `B`. This is useful for records which
+are stored as tables, like `#((0 x) (1 y))`. Any other keys are ignored.
+
+When an element's type is `Option<T>`, a missing key converts to `None`. Otherwise, a missing
+key is an error.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+let tab = tab! { (0, 10), (1, "ten") };
+
+let KeyedTuple((num, name, extra)) =
+    KeyedTuple::<(i32, String, Option<i32>)>::from_val(&Val::Tab(tab))?;
+
+assert!(num == 10 && name == "ten" && extra.is_none());
+# Ok(()) }).unwrap();
+```
+*/
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyedTuple<T>(pub T);
+
+macro_rules! impl_from_val_keyed_tuple {
+    ($($t:ident $i:tt),+) => (
+        impl<$($t),+> FromVal for KeyedTuple<($($t,)+)>
+        where
+            $($t: FromTupleElement + StaticMarker),+
+        {
+            #[inline]
+            fn from_val(val: &Val) -> GResult<KeyedTuple<($($t,)+)>> {
+                match *val {
+                    Val::Tab(ref tab) => {
+                        Ok(KeyedTuple(($(
+                            $t::from_keyed_tuple_element(tab, $i)
+                                .map_err(|err| err.prepend_path_key(&Val::Int($i)))?,
+                        )*)))
+                    }
+                    ref val => bail!("expected a keyed tuple, received {}", val.a_type_name())
+                }
+            }
+        }
+    );
+}
+
+impl_from_val_keyed_tuple!(A 0);
+impl_from_val_keyed_tuple!(A 0, B 1);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_from_val_keyed_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
 impl FromVal for String {
     #[inline]
     fn from_val(val: &Val) -> GResult<Self> {
@@ -1981,114 +3312,592 @@ impl FromVal for String {
             ref val => bail!("expected a str, received {}", val.a_type_name()),
         }
     }
-}
 
-impl FromVal for CString {
+    #[doc(hidden)]
+    #[inline]
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match *val {
+            Val::Str(ref st) => Some(st.to_string()),
+            _ => None,
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        matches!(*val, Val::Str(_))
+    }
+}
+
+impl FromVal for Cow<'static, str> {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Str(ref st) => {
+                if st.len() <= 32 {
+                    let mut bytes = SmallVec::<[u8; 128]>::new();
+                    for ch in st.iter() {
+                        let mut buf = [0u8; 4];
+                        bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                    }
+
+                    let name = str::from_utf8(&bytes).unwrap();
+                    if let Some(static_name) = glsp::stock_sym_name(name) {
+                        return Ok(Cow::Borrowed(static_name));
+                    }
+                }
+
+                Ok(Cow::Owned(st.to_string()))
+            }
+            ref val => bail!("expected a str, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl FromVal for CString {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Str(ref st) => match CString::new(st.to_string()) {
+                Ok(cstring) => Ok(cstring),
+                Err(_) => {
+                    bail!("expected a C string, received a str with an inner nul")
+                }
+            },
+            ref val => bail!("expected a C string, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl FromVal for PathBuf {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Str(ref st) => Ok(PathBuf::from(st.to_string())),
+            Val::Arr(ref arr) => {
+                let mut path_buf = PathBuf::new();
+
+                let arr_borrow = arr.borrow();
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    match slot {
+                        Slot::Str(ref st) => path_buf.push(st.to_string()),
+                        slot => {
+                            let err = error!("expected a str, received {}", slot.a_type_name());
+                            return Err(err.prepend_path_index(i));
+                        }
+                    }
+                }
+
+                Ok(path_buf)
+            }
+            ref val => bail!("expected a path, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl FromVal for OsString {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Str(ref st) => Ok(OsString::from(st.to_string())),
+            ref val => bail!("expected an OS string, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl<K, V, S> FromVal for HashMap<K, V, S>
+where
+    K: HashEqMarker + FromVal + StaticMarker,
+    V: FromVal + StaticMarker,
+    S: BuildHasherDefaultMarker + StaticMarker,
+{
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Tab(ref tab) => {
+                let s = S::default();
+                let mut hash_map = HashMap::<K, V, S>::with_capacity_and_hasher(tab.len(), s);
+
+                let tab_borrow = tab.borrow();
+                for (internal_key, internal_value) in tab_borrow.iter() {
+                    let key = K::from_slot(internal_key)
+                        .map_err(|err| err.prepend_path_key(&internal_key.root()))?;
+                    let value = V::from_slot(internal_value)
+                        .map_err(|err| err.prepend_path_key(&internal_key.root()))?;
+
+                    if hash_map.insert(key, value).is_some() {
+                        bail!("duplicate key in HashMap argument");
+                    }
+                }
+
+                Ok(hash_map)
+            }
+            ref val => bail!("expected a HashMap, received {}", val.a_type_name()),
+        }
+    }
+}
+
+// BTreeMap<K, V>
+//-----------------------------------------------------------------------------
+
+impl<K, V> FromVal for BTreeMap<K, V>
+where
+    K: OrdMarker + FromVal + StaticMarker,
+    V: FromVal + StaticMarker,
+{
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Tab(ref tab) => {
+                let mut btree_map = BTreeMap::<K, V>::new();
+
+                let tab_borrow = tab.borrow();
+                for (internal_key, internal_value) in tab_borrow.iter() {
+                    let key = K::from_slot(internal_key)
+                        .map_err(|err| err.prepend_path_key(&internal_key.root()))?;
+                    let value = V::from_slot(internal_value)
+                        .map_err(|err| err.prepend_path_key(&internal_key.root()))?;
+
+                    if btree_map.insert(key, value).is_some() {
+                        bail!("duplicate key in BTreeMap argument");
+                    }
+                }
+
+                Ok(btree_map)
+            }
+            ref val => bail!("expected a BTreeMap, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl<T, S> FromVal for HashSet<T, S>
+where
+    T: HashEqMarker + FromVal + StaticMarker,
+    S: BuildHasherDefaultMarker + StaticMarker,
+{
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Arr(ref arr) => {
+                let s = S::default();
+                let mut hash_set = HashSet::<T, S>::with_capacity_and_hasher(arr.len(), s);
+
+                let arr_borrow = arr.borrow();
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    let item = T::from_slot(slot).map_err(|err| err.prepend_path_index(i))?;
+
+                    if !hash_set.insert(item) {
+                        bail!("duplicate element in HashSet argument");
+                    }
+                }
+
+                Ok(hash_set)
+            }
+            ref val => bail!("expected a HashSet, received {}", val.a_type_name()),
+        }
+    }
+}
+
+impl<T> FromVal for BTreeSet<T>
+where
+    T: OrdMarker + FromVal + StaticMarker,
+{
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::Arr(ref arr) => {
+                let mut btree_set = BTreeSet::<T>::new();
+
+                let arr_borrow = arr.borrow();
+                for (i, slot) in arr_borrow.iter().enumerate() {
+                    let item = T::from_slot(slot).map_err(|err| err.prepend_path_index(i))?;
+
+                    if !btree_set.insert(item) {
+                        bail!("duplicate element in BTreeSet argument");
+                    }
+                }
+
+                Ok(btree_set)
+            }
+            ref val => bail!("expected a BTreeSet, received {}", val.a_type_name()),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Blob
+//-------------------------------------------------------------------------------------------------
+
+/**
+A buffer of bytes which is stored on the GameLisp heap without any per-byte conversion.
+
+Converting a `Vec<u8>` [into a `Val`](trait.IntoVal.html) produces an arr of ints, which is
+slow and memory-hungry for large buffers. Wrapping the buffer in a `Blob` instead moves it
+onto the garbage-collected heap as an [`RData`](struct.RData.html), without copying it.
+
+When a Rust function [is bound as an `RFn`](fn.rfn.html), its `&Blob`, `&mut Blob`, `&[u8]`
+and `&mut [u8]` parameters borrow the buffer directly from the `RData`. `Blob` dereferences to
+`Vec<u8>`, so it can be used anywhere that a `&[u8]` is expected.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+fn load_asset(path: &str) -> Blob {
+    Blob(std::fs::read(path).unwrap_or_default())
+}
+
+fn checksum(blob: &Blob) -> u32 {
+    blob.iter().map(|&b| b as u32).sum()
+}
+
+glsp::bind_rfn("load-asset", &load_asset)?;
+glsp::bind_rfn("checksum", &checksum)?;
+# Ok(()) }).unwrap();
+```
+
+`Blob` also implements [`FromVal`](trait.FromVal.html). It can be converted from an `RData`
+which stores a `Blob`, which copies its bytes; from a str, using the same one-byte-per-character
+encoding as [`Str::as_bytes`](struct.Str.html#method.as_bytes); or from an arr of ints in the
+range `0..=255`.
+
+An `RFn` parameter of type `&[u8]` accepts all three. A `Blob` or a str is borrowed without
+copying it, and an arr is converted into a temporary buffer.
+*/
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Blob(pub Vec<u8>);
+
+impl Deref for Blob {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+impl DerefMut for Blob {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for Blob {
+    fn from(bytes: Vec<u8>) -> Blob {
+        Blob(bytes)
+    }
+}
+
+impl From<Blob> for Vec<u8> {
+    fn from(blob: Blob) -> Vec<u8> {
+        blob.0
+    }
+}
+
+impl<'a> IntoVal for &'a Blob {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self.clone())))
+    }
+}
+
+impl<'a> IntoVal for &'a mut Blob {
+    #[inline]
+    fn into_val(self) -> GResult<Val> {
+        Ok(Val::RData(glsp::rdata(self.clone())))
+    }
+}
+
+impl FromVal for Blob {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        match *val {
+            Val::RData(ref rdata) if rdata.is::<Blob>() => Ok(rdata.try_borrow::<Blob>()?.clone()),
+            Val::Str(ref st) => Ok(Blob(st.as_bytes()?.to_vec())),
+            Val::Arr(_) => Ok(Blob(Vec::<u8>::from_val(val)?)),
+            ref val => bail!("expected a Blob, received {}", val.a_type_name()),
+        }
+    }
+}
+
+//-------------------------------------------------------------------------------------------------
+// Either
+//-------------------------------------------------------------------------------------------------
+
+/**
+A value which may have either of two types.
+
+`Either<L, R>` implements [`FromVal`](trait.FromVal.html) by first attempting to convert
+to `L`, and then attempting to convert to `R`. This makes it possible for an
+[`RFn`](struct.RFn.html) to accept parameters of more than one type, while still benefiting
+from automatic type conversions. For three or more types, `Either` can be nested:
+`Either<Either<A, B>, C>`.
+
+When converting to `L` fails, no `GError` is allocated for built-in types like `i32`, `f32`,
+`String` and `Root<Arr>`. For other types, including user-defined `FromVal` types, a failed
+conversion to `L` is as expensive as any other failed conversion.
+
+`Either<L, R>` implements [`IntoVal`](trait.IntoVal.html) by converting whichever value
+it contains.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+fn describe(arg: Either<i32, String>) -> String {
+    match arg {
+        Either::Left(i) => format!("the number {}", i),
+        Either::Right(st) => format!("the text {:?}", st),
+    }
+}
+
+glsp::bind_rfn("describe", &describe)?;
+# Ok(()) }).unwrap();
+```
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L: FromVal, R: FromVal> FromVal for Either<L, R> {
+    #[inline]
+    fn from_val(val: &Val) -> GResult<Self> {
+        if let Some(left) = L::try_from_val(val) {
+            return Ok(Either::Left(left));
+        }
+
+        match R::from_val(val) {
+            Ok(right) => Ok(Either::Right(right)),
+            Err(err) => Err(error!(
+                "expected {} or {}, received {}",
+                short_type_name(type_name::<L>()),
+                short_type_name(type_name::<R>()),
+                val.a_type_name()
+            )
+            .with_source(err)),
+        }
+    }
+
+    #[doc(hidden)]
     #[inline]
-    fn from_val(val: &Val) -> GResult<Self> {
-        match *val {
-            Val::Str(ref st) => match CString::new(st.to_string()) {
-                Ok(cstring) => Ok(cstring),
-                Err(_) => {
-                    bail!("expected a C string, received a str with an inner nul")
-                }
-            },
-            ref val => bail!("expected a C string, received {}", val.a_type_name()),
+    fn try_from_val(val: &Val) -> Option<Self> {
+        match L::try_from_val(val) {
+            Some(left) => Some(Either::Left(left)),
+            None => R::try_from_val(val).map(Either::Right),
         }
     }
+
+    #[doc(hidden)]
+    #[inline]
+    fn can_from_val(val: &Val) -> bool {
+        L::can_from_val(val) || R::can_from_val(val)
+    }
 }
 
-impl FromVal for PathBuf {
+impl<L: IntoVal, R: IntoVal> IntoVal for Either<L, R> {
     #[inline]
-    fn from_val(val: &Val) -> GResult<Self> {
-        match *val {
-            Val::Str(ref st) => Ok(PathBuf::from(st.to_string())),
-            ref val => bail!("expected a path, received {}", val.a_type_name()),
+    fn into_val(self) -> GResult<Val> {
+        match self {
+            Either::Left(left) => left.into_val(),
+            Either::Right(right) => right.into_val(),
         }
     }
 }
 
-impl FromVal for OsString {
+impl<'a, L, R> IntoVal for &'a Either<L, R>
+where
+    &'a L: IntoVal,
+    &'a R: IntoVal,
+{
     #[inline]
-    fn from_val(val: &Val) -> GResult<Self> {
-        match *val {
-            Val::Str(ref st) => Ok(OsString::from(st.to_string())),
-            ref val => bail!("expected an OS string, received {}", val.a_type_name()),
+    fn into_val(self) -> GResult<Val> {
+        match self {
+            Either::Left(left) => left.into_val(),
+            Either::Right(right) => right.into_val(),
         }
     }
 }
 
-impl<K, V, S> FromVal for HashMap<K, V, S>
+impl<'a, L, R> IntoVal for &'a mut Either<L, R>
 where
-    K: HashEqMarker + FromVal + StaticMarker,
-    V: FromVal + StaticMarker,
-    S: BuildHasherDefaultMarker + StaticMarker,
+    &'a mut L: IntoVal,
+    &'a mut R: IntoVal,
 {
     #[inline]
-    fn from_val(val: &Val) -> GResult<Self> {
-        match *val {
-            Val::Tab(ref tab) => {
-                let s = S::default();
-                let mut hash_map = HashMap::<K, V, S>::with_capacity_and_hasher(tab.len(), s);
+    fn into_val(self) -> GResult<Val> {
+        match self {
+            Either::Left(left) => left.into_val(),
+            Either::Right(right) => right.into_val(),
+        }
+    }
+}
 
-                let tab_borrow = tab.borrow();
-                for (internal_key, internal_value) in tab_borrow.iter() {
-                    let key = K::from_slot(internal_key)?;
-                    let value = V::from_slot(internal_value)?;
+/**
+Defines an enum which is represented in GameLisp as a tagged arr.
 
-                    if hash_map.insert(key, value).is_some() {
-                        bail!("duplicate key in HashMap argument");
-                    }
-                }
+Each variant is represented by an arr which starts with a symbol tag, followed by each of the
+variant's fields in order. For example, the enum below converts to and from arrs like
+`(circle 10.0)` and `(rect 20.0 30.0)`.
 
-                Ok(hash_map)
-            }
-            ref val => bail!("expected a HashMap, received {}", val.a_type_name()),
-        }
+```
+# #![feature(min_specialization)]
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+tagged_enum! {
+    #[derive(Clone, Debug)]
+    pub enum Shape {
+        Circle: "circle" { radius: f32 },
+        Rect: "rect" { w: f32, h: f32 },
+        Point: "point" { }
     }
 }
 
-// BTreeMap<K, V>
-//-----------------------------------------------------------------------------
+# Engine::new().run(|| {
+let val = glsp::parse_1("(rect 20.0 30.0)", None)?;
+let shape = Shape::from_val(&val)?;
+# Ok(()) }).unwrap();
+```
 
-impl<K, V> FromVal for BTreeMap<K, V>
-where
-    K: OrdMarker + FromVal + StaticMarker,
-    V: FromVal + StaticMarker,
-{
-    #[inline]
-    fn from_val(val: &Val) -> GResult<Self> {
-        match *val {
-            Val::Tab(ref tab) => {
-                let mut btree_map = BTreeMap::<K, V>::new();
+The macro implements [`FromVal`](trait.FromVal.html) for the enum, and implements
+[`IntoVal`](trait.IntoVal.html) for the enum, a reference to it, and a mutable reference to it.
+Converting from a `Val` fails if it isn't an arr, if the arr's first element is not one of the
+enum's tags, or if the arr has the wrong number of elements for its tag. When one of the fields
+fails to convert, the field's index within the arr is recorded in the error's
+[`path`](struct.GError.html#method.path).
 
-                let tab_borrow = tab.borrow();
-                for (internal_key, internal_value) in tab_borrow.iter() {
-                    let key = K::from_slot(internal_key)?;
-                    let value = V::from_slot(internal_value)?;
+Every variant must be written with curly braces, even when it has no fields. When converting
+the enum into a `Val`, each field is converted by reference, so `&T` must implement `IntoVal`
+for each field type `T`.
+*/
 
-                    if btree_map.insert(key, value).is_some() {
-                        bail!("duplicate key in BTreeMap argument");
+#[macro_export]
+macro_rules! tagged_enum {
+    (
+        $(#[$enum_attr:meta])*
+        $enum_vis:vis enum $enum_name:ident {
+            $($variant:ident: $tag:literal { $($field:ident: $field_ty:ty),* $(,)? }),+ $(,)?
+        }
+    ) => (
+        $(#[$enum_attr])*
+        $enum_vis enum $enum_name {
+            $($variant { $($field: $field_ty),* },)+
+        }
+
+        impl $crate::FromVal for $enum_name {
+            #[allow(unused_mut, unused_variables)]
+            fn from_val(val: &$crate::Val) -> $crate::GResult<Self> {
+                use $crate::{DequeAccess, DequeOps};
+
+                let arr = match *val {
+                    $crate::Val::Arr(ref arr) if arr.len() > 0 => arr,
+                    _ => $crate::bail!(
+                        "expected a tagged arr for {}, received {}",
+                        stringify!($enum_name),
+                        val.a_type_name()
+                    ),
+                };
+
+                let tag = match arr.get::<$crate::Val>(0)? {
+                    $crate::Val::Sym(tag) => tag,
+                    other => $crate::bail!(
+                        "expected a symbol tag for {}, received {}",
+                        stringify!($enum_name),
+                        other.a_type_name()
+                    ),
+                };
+
+                let tag_name = tag.name();
+                $(
+                    if &*tag_name == $tag {
+                        let fields: &[&str] = &[$(stringify!($field)),*];
+                        $crate::ensure!(
+                            arr.len() == fields.len() + 1,
+                            "({} ...) expects {} field{}, received {}",
+                            $tag,
+                            fields.len(),
+                            if fields.len() == 1 { "" } else { "s" },
+                            arr.len() - 1
+                        );
+
+                        let mut i = 0usize;
+                        return Ok($enum_name::$variant {
+                            $($field: {
+                                i += 1;
+                                arr.get::<$field_ty>(i).map_err(|err| err.prepend_path_index(i))?
+                            },)*
+                        });
                     }
+                )+
+
+                let tags: &[&str] = &[$($tag),+];
+                $crate::bail!(
+                    "unknown tag {} for {}, expected one of {:?}",
+                    tag,
+                    stringify!($enum_name),
+                    tags
+                )
+            }
+        }
+
+        impl<'a> $crate::IntoVal for &'a $enum_name {
+            fn into_val(self) -> $crate::GResult<$crate::Val> {
+                use $crate::DequeOps;
+
+                match *self {
+                    $($enum_name::$variant { $(ref $field),* } => {
+                        let arr = $crate::arr();
+                        arr.push($crate::sym($tag)?)?;
+                        $(arr.push($field)?;)*
+                        Ok($crate::Val::Arr(arr))
+                    })+
                 }
+            }
+        }
 
-                Ok(btree_map)
+        impl<'a> $crate::IntoVal for &'a mut $enum_name {
+            #[inline]
+            fn into_val(self) -> $crate::GResult<$crate::Val> {
+                (&*self).into_val()
             }
-            ref val => bail!("expected a BTreeMap, received {}", val.a_type_name()),
         }
-    }
+
+        impl $crate::IntoVal for $enum_name {
+            #[inline]
+            fn into_val(self) -> $crate::GResult<$crate::Val> {
+                (&self).into_val()
+            }
+        }
+    );
 }
 
 //-------------------------------------------------------------------------------------------------
 // FromArg, FromArgRef
 //-------------------------------------------------------------------------------------------------
 
-#[doc(hidden)]
-#[derive(Copy, Clone, Debug, PartialEq)]
+/**
+The kind of a parameter accepted by an [`RFn`](struct.RFn.html).
+
+Returned by [`RFn::arg_types`](struct.RFn.html#method.arg_types).
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ArgType {
+    ///An `&T` or `&mut T` parameter, where `T` is an [`RGlobal`](trait.RGlobal.html). It doesn't
+    ///consume any arguments.
     RGlobal,
+
+    ///A required parameter.
     Normal,
+
+    ///An optional parameter, such as an `Option<T>`.
     Option,
+
+    ///A [`Rest<T>`](struct.Rest.html) parameter, which consumes all remaining arguments.
     Rest,
 }
 
@@ -2101,6 +3910,11 @@ pub trait FromArg: Sized {
     fn arg_type() -> ArgType;
     fn make_temp(args: &[Slot], i: usize) -> GResult<Self::Temp>;
     fn from_arg<'a>(temp: &'a mut Self::Temp) -> GResult<<Self::OutputCtor as Ctor<'a>>::Ty>;
+
+    #[inline(always)]
+    fn write_back(_temp: &mut Self::Temp) -> GResult<()> {
+        Ok(())
+    }
 }
 
 #[doc(hidden)]
@@ -2112,6 +3926,11 @@ pub trait FromArgRef: Sized {
     fn arg_type() -> ArgType;
     fn make_temp(args: &[Slot], i: usize) -> GResult<Self::Temp>;
     fn from_arg<'a>(temp: &'a mut Self::Temp) -> GResult<<Self::OutputCtor as Ctor<'a>>::Ty>;
+
+    #[inline(always)]
+    fn write_back(_temp: &mut Self::Temp) -> GResult<()> {
+        Ok(())
+    }
 }
 
 /*
@@ -2145,6 +3964,13 @@ impl<'a, T: 'a> Ctor<'a> for RestCtor<T> {
     type Ty = Rest<'a, T>;
 }
 
+#[doc(hidden)]
+pub struct RestRefCtor<T>(PhantomData<T>);
+
+impl<'a, T: 'static> Ctor<'a> for RestRefCtor<T> {
+    type Ty = RestRef<'a, T>;
+}
+
 #[doc(hidden)]
 pub struct RefCtor<T: ?Sized>(PhantomData<T>);
 
@@ -2252,7 +4078,9 @@ Rest::with([20, 30, 40, 50].iter().copied(), |rest| {
 It's possible to construct a custom `Rest<T>` yourself by calling [`Rest::with`](#method.with),
 but it's usually not elegant. Instead, consider defining a Rust function which receives a slice
 or a generic `IntoIterator`, and a wrapper which receives a `Rest<T>` and forwards it to the
-original function.
+original function. If you only need to pass some arguments to [`glsp::call`](fn.call.html),
+you can construct an [`OwnedRest<T>`](struct.OwnedRest.html) using
+[`Rest::from_slice`](#method.from_slice) instead.
 
 ```
 # extern crate glsp_engine as glsp;
@@ -2282,6 +4110,15 @@ impl<'a, T> Rest<'a, T> {
     {
         f(Rest(&mut Some(src.into_iter().collect())))
     }
+
+    ///Constructs an [`OwnedRest`](struct.OwnedRest.html) by cloning the contents of a slice.
+    #[inline]
+    pub fn from_slice(src: &[T]) -> OwnedRest<T>
+    where
+        T: Clone,
+    {
+        OwnedRest(src.iter().cloned().collect())
+    }
 }
 
 impl<'a, T> Deref for Rest<'a, T> {
@@ -2346,6 +4183,106 @@ impl<'r, 'a: 'r, T> IntoIterator for &'r mut Rest<'a, T> {
     }
 }
 
+/**
+An owned sequence of arguments.
+
+`OwnedRest<T>` is the owned counterpart of [`Rest<T>`](struct.Rest.html). It can be constructed
+using [`Rest::from_slice`](struct.Rest.html#method.from_slice), or by collecting an iterator.
+Unlike `Rest<T>`, it doesn't need to be confined to the body of a closure: it can be stored
+and passed around like any other value.
+
+`OwnedRest<T>` dereferences to `[T]`, and it implements [`IntoCallArgs`](trait.IntoCallArgs.html).
+It can't be used as an `RFn` parameter; use `Rest<T>` for that instead.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# fn example(callback: Root<GFn>) -> GResult<()> {
+let args = Rest::from_slice(&[10, 20, 30]);
+assert!(args.len() == 3);
+
+let _: Val = glsp::call(&callback, &args)?;
+# Ok(()) }
+```
+*/
+#[derive(Clone, Debug)]
+pub struct OwnedRest<T>(SmallVec<[T; 8]>);
+
+impl<T> OwnedRest<T> {
+    #[inline]
+    pub fn new() -> OwnedRest<T> {
+        OwnedRest(SmallVec::new())
+    }
+}
+
+impl<T> Default for OwnedRest<T> {
+    #[inline]
+    fn default() -> OwnedRest<T> {
+        OwnedRest::new()
+    }
+}
+
+impl<T> Deref for OwnedRest<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for OwnedRest<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T> FromIterator<T> for OwnedRest<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OwnedRest<T> {
+        OwnedRest(iter.into_iter().collect())
+    }
+}
+
+impl<T> From<Vec<T>> for OwnedRest<T> {
+    #[inline]
+    fn from(src: Vec<T>) -> OwnedRest<T> {
+        OwnedRest(SmallVec::from_vec(src))
+    }
+}
+
+impl<T> IntoIterator for OwnedRest<T> {
+    type Item = T;
+    type IntoIter = smallvec::IntoIter<[T; 8]>;
+
+    #[inline]
+    fn into_iter(self) -> smallvec::IntoIter<[T; 8]> {
+        self.0.into_iter()
+    }
+}
+
+impl<'r, T> IntoIterator for &'r OwnedRest<T> {
+    type Item = &'r T;
+    type IntoIter = slice::Iter<'r, T>;
+
+    #[inline]
+    fn into_iter(self) -> slice::Iter<'r, T> {
+        self.0.iter()
+    }
+}
+
+impl<'r, T> IntoIterator for &'r mut OwnedRest<T> {
+    type Item = &'r mut T;
+    type IntoIter = slice::IterMut<'r, T>;
+
+    #[inline]
+    fn into_iter(self) -> slice::IterMut<'r, T> {
+        self.0.iter_mut()
+    }
+}
+
 impl<'r, T: FromVal> FromArg for Rest<'r, T> {
     type Temp = (SmallVec<[Slot; 8]>, Option<SmallVec<[T; 8]>>);
     type OutputCtor = RestCtor<T>;
@@ -2380,12 +4317,153 @@ impl<'r, T: FromVal> FromArg for Rest<'r, T> {
             temp.1.as_mut().unwrap().push(T::from_slot(arg)?);
         }
 
-        Ok(Rest(&mut temp.1))
+        Ok(Rest(&mut temp.1))
+    }
+}
+
+/**
+An adapter type which borrows any number of trailing `RData` arguments.
+
+[`Rest<T>`](struct.Rest.html) converts each trailing argument into an owned `T`. When
+[binding a Rust function](fn.rfn.html), `RestRef<T>` can be used instead to borrow the value
+stored by each trailing [`RData`](struct.RData.html) argument, in the same way that a `&T`
+parameter borrows a single `RData`. Each argument must be an `RData` which stores a `T`,
+and it mustn't currently be mutably borrowed.
+
+`RestRef<T>` dereferences to a slice of [`RRef<T>`](struct.RRef.html), and it's iterable,
+yielding `&T`.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# Engine::new().run(|| {
+struct Sprite {
+    width: f32,
+}
+
+fn total_width(sprites: RestRef<Sprite>) -> f32 {
+    sprites.iter().map(|sprite| sprite.width).sum()
+}
+
+glsp::bind_rfn("total-width", &total_width)?;
+# Ok(()) }).unwrap();
+```
+*/
+pub struct RestRef<'a, T: 'static>(&'a [RRef<T>]);
+
+impl<'a, T> RestRef<'a, T> {
+    ///Returns an iterator over the borrowed values.
+    #[inline]
+    pub fn iter(&self) -> RestRefIter<'a, T> {
+        RestRefIter(self.0.iter())
+    }
+}
+
+///The iterator returned by [`RestRef::iter`](struct.RestRef.html#method.iter).
+pub struct RestRefIter<'a, T: 'static>(slice::Iter<'a, RRef<T>>);
+
+impl<'a, T> Iterator for RestRefIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next().map(|rref| &**rref)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for RestRefIter<'a, T> {}
+
+impl<'a, T> Deref for RestRef<'a, T> {
+    type Target = [RRef<T>];
+
+    #[inline]
+    fn deref(&self) -> &[RRef<T>] {
+        self.0
+    }
+}
+
+impl<'a, T> Index<usize> for RestRef<'a, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        &*self.0[index]
+    }
+}
+
+impl<'r, 'a: 'r, T> IntoIterator for &'r RestRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = RestRefIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> RestRefIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'r, T: StaticMarker> FromArg for RestRef<'r, T> {
+    type Temp = SmallVec<[RRef<T>; 8]>;
+    type OutputCtor = RestRefCtor<T>;
+
+    #[inline]
+    fn arg_type() -> ArgType {
+        ArgType::Rest
+    }
+
+    #[inline]
+    fn make_temp(args: &[Slot], i: usize) -> GResult<SmallVec<[RRef<T>; 8]>> {
+        /*
+        unlike Rest<T>, it's safe to do all of the work here. RData::try_borrow() never
+        executes any user code, so it can't push to the reg stack while the argument slice is
+        borrowed. each RRef holds an Rc to its rdata's storage, so the borrows remain valid
+        after the argument slice is released, until the temp is dropped when the rfn returns.
+        */
+
+        let mut rrefs = SmallVec::with_capacity(args.len().saturating_sub(i));
+        for (j, arg) in args[min(i, args.len())..].iter().enumerate() {
+            match arg {
+                Slot::RData(rdata) => rrefs.push(rdata.try_borrow()?),
+                slot => {
+                    return Err(error!(
+                        "expected &{}, received {}",
+                        type_name::<T>(),
+                        slot.a_type_name()
+                    )
+                    .prepend_path_index(j))
+                }
+            }
+        }
+
+        Ok(rrefs)
+    }
+
+    #[inline]
+    fn from_arg<'a>(temp: &'a mut SmallVec<[RRef<T>; 8]>) -> GResult<RestRef<'a, T>> {
+        Ok(RestRef(&temp[..]))
     }
 }
 
+/*
+we can't specialize FromArg::Temp, so a &[u8] argument uses extra variants of the same Temp
+type as any other &[T]. a str's bytes are borrowed from its cache (see Str::to_shared_bytes),
+and a Blob's bytes are borrowed directly from the rdata.
+*/
+
+#[doc(hidden)]
+pub enum SliceTemp<T: 'static> {
+    Converted(Slot, SmallVec<[T; 8]>),
+    Bytes(Rc<Vec<u8>>),
+    Blob(RRef<Blob>),
+}
+
 impl<'r, T: FromVal> FromArg for &'r [T] {
-    type Temp = (Slot, SmallVec<[T; 8]>);
+    type Temp = SliceTemp<T>;
     type OutputCtor = RefCtor<[T]>;
 
     #[inline]
@@ -2394,25 +4472,65 @@ impl<'r, T: FromVal> FromArg for &'r [T] {
     }
 
     #[inline]
-    fn make_temp(args: &[Slot], i: usize) -> GResult<(Slot, SmallVec<[T; 8]>)> {
+    default fn make_temp(args: &[Slot], i: usize) -> GResult<SliceTemp<T>> {
         /*
         we can't just call T::from_slot() here, because the argument slice
         is borrowed. a user-defined from_val() could do something which
         pushes to the reg stack, causing a panic
         */
 
-        Ok((args[i].clone(), SmallVec::with_capacity(args.len() - i)))
+        Ok(SliceTemp::Converted(args[i].clone(), SmallVec::with_capacity(args.len() - i)))
+    }
+
+    #[inline]
+    default fn from_arg<'a>(temp: &'a mut SliceTemp<T>) -> GResult<&'a [T]> {
+        match temp {
+            SliceTemp::Converted(slot, vec) => {
+                *vec = SmallVec::from_slot(slot)?;
+                Ok(&vec[..])
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'r> FromArg for &'r [u8] {
+    #[inline]
+    fn make_temp(args: &[Slot], i: usize) -> GResult<SliceTemp<u8>> {
+        match &args[i] {
+            Slot::Str(st) => Ok(SliceTemp::Bytes(st.to_shared_bytes()?)),
+            Slot::RData(rdata) if rdata.is::<Blob>() => Ok(SliceTemp::Blob(rdata.try_borrow()?)),
+            slot => Ok(SliceTemp::Converted(slot.clone(), SmallVec::new())),
+        }
     }
 
     #[inline]
-    fn from_arg<'a>(temp: &'a mut (Slot, SmallVec<[T; 8]>)) -> GResult<&'a [T]> {
-        temp.1 = SmallVec::from_slot(&temp.0)?;
-        Ok(&temp.1)
+    fn from_arg<'a>(temp: &'a mut SliceTemp<u8>) -> GResult<&'a [u8]> {
+        match temp {
+            SliceTemp::Converted(slot, vec) => {
+                *vec = SmallVec::from_slot(slot)?;
+                Ok(&vec[..])
+            }
+            SliceTemp::Bytes(bytes) => Ok(&bytes[..]),
+            SliceTemp::Blob(blob) => Ok(&blob[..]),
+        }
     }
 }
 
+/*
+short strs are copied into a stack buffer. longer strs are converted using
+Str::to_shared_rust_str(), which caches its result until the str is mutated, so passing the
+same large str to an rfn repeatedly will only copy its contents once.
+*/
+
+#[doc(hidden)]
+pub enum StrTemp {
+    Buffer(SmallVec<[u8; 128]>),
+    Shared(Rc<String>),
+}
+
 impl<'r> FromArg for &'r str {
-    type Temp = SmallVec<[u8; 128]>;
+    type Temp = StrTemp;
     type OutputCtor = RefCtor<str>;
 
     #[inline]
@@ -2421,20 +4539,24 @@ impl<'r> FromArg for &'r str {
     }
 
     #[inline]
-    fn make_temp(args: &[Slot], i: usize) -> GResult<SmallVec<[u8; 128]>> {
-        let mut vec = SmallVec::<[u8; 128]>::new();
-
+    fn make_temp(args: &[Slot], i: usize) -> GResult<StrTemp> {
         match &args[i] {
-            Slot::Str(st) => write!(&mut vec, "{}", st).unwrap(),
+            Slot::Str(st) if st.len() > 128 => Ok(StrTemp::Shared(st.to_shared_rust_str())),
+            Slot::Str(st) => {
+                let mut vec = SmallVec::<[u8; 128]>::new();
+                write!(&mut vec, "{}", st).unwrap();
+                Ok(StrTemp::Buffer(vec))
+            }
             slot => bail!("expected a &str, received {}", slot.a_type_name()),
         }
-
-        Ok(vec)
     }
 
     #[inline]
-    fn from_arg<'a>(temp: &'a mut SmallVec<[u8; 128]>) -> GResult<&'a str> {
-        Ok(str::from_utf8(&temp[..]).unwrap())
+    fn from_arg<'a>(temp: &'a mut StrTemp) -> GResult<&'a str> {
+        match temp {
+            StrTemp::Buffer(vec) => Ok(str::from_utf8(&vec[..]).unwrap()),
+            StrTemp::Shared(shared) => Ok(&shared[..]),
+        }
     }
 }
 
@@ -2487,6 +4609,7 @@ pub enum DynTemp<T: 'static> {
 pub enum DynTempMut<T: 'static> {
     RGlobalRefMut(RGlobalRefMut<T>),
     RRefMut(RRefMut<T>),
+    WriteBack(Slot, T),
 }
 
 impl<'r, T: StaticMarker> FromArgRef for &'r T {
@@ -2501,7 +4624,7 @@ impl<'r, T: StaticMarker> FromArgRef for &'r T {
     #[inline(always)]
     default fn make_temp(args: &[Slot], i: usize) -> GResult<DynTemp<T>> {
         match &args[i] {
-            Slot::RData(rdata) => Ok(DynTemp::RRef(rdata.borrow())),
+            Slot::RData(rdata) => Ok(DynTemp::RRef(rdata.try_borrow()?)),
             slot => bail!(
                 "expected &{}, received {}",
                 type_name::<T>(),
@@ -2531,7 +4654,7 @@ impl<'r, T: StaticMarker> FromArgRef for &'r mut T {
     #[inline(always)]
     default fn make_temp(args: &[Slot], i: usize) -> GResult<DynTempMut<T>> {
         match &args[i] {
-            Slot::RData(rdata) => Ok(DynTempMut::RRefMut(rdata.borrow_mut())),
+            Slot::RData(rdata) => Ok(DynTempMut::RRefMut(rdata.try_borrow_mut()?)),
             slot => bail!(
                 "expected &mut {}, received {}",
                 type_name::<T>(),
@@ -2547,6 +4670,11 @@ impl<'r, T: StaticMarker> FromArgRef for &'r mut T {
             _ => unreachable!(),
         }
     }
+
+    #[inline(always)]
+    default fn write_back(_temp: &mut DynTempMut<T>) -> GResult<()> {
+        Ok(())
+    }
 }
 
 /*
@@ -2561,8 +4689,208 @@ we could exploit the FromArg/FromArgRef dichotomy by implementing FromArg for
 &mut T: FromVal + IntoVal, because users are unlikely to implement both RGlobal and
 FromVal/IntoVal for the same type. however, this conflicts with the blanket IntoVal
 implementation for all 'static types.
+
+as a compromise, we specialize &mut String and &mut Vec<T>, which are by far the most common
+cases. when the argument is a str or arr, we convert it into a temporary String or Vec<T>,
+pass that to the function, and then overwrite the original collection with the temporary's
+contents once the function returns. this round-trips the entire collection on every call, so
+it's much slower than passing an &mut Arr or &mut Str. an rdata which stores a String or
+Vec<T> is still borrowed in-place, with no write-back.
+*/
+
+impl<'r> FromArgRef for &'r mut String {
+    #[inline(always)]
+    fn make_temp(args: &[Slot], i: usize) -> GResult<DynTempMut<String>> {
+        match &args[i] {
+            Slot::Str(st) => {
+                ensure!(!st.is_frozen(), "expected a mutable str, received a frozen str");
+                Ok(DynTempMut::WriteBack(args[i].clone(), st.to_string()))
+            }
+            Slot::RData(rdata) => Ok(DynTempMut::RRefMut(rdata.try_borrow_mut()?)),
+            slot => bail!("expected &mut String, received {}", slot.a_type_name()),
+        }
+    }
+
+    #[inline]
+    fn from_arg<'a>(temp: &'a mut DynTempMut<String>) -> GResult<&'a mut String> {
+        match temp {
+            DynTempMut::RRefMut(temp) => Ok(&mut **temp),
+            DynTempMut::WriteBack(_, temp) => Ok(temp),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn write_back(temp: &mut DynTempMut<String>) -> GResult<()> {
+        match temp {
+            DynTempMut::WriteBack(Slot::Str(st), temp) => {
+                st.clear()?;
+                st.extend(temp.chars())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'r, T: FromVal + IntoVal> FromArgRef for &'r mut Vec<T> {
+    #[inline(always)]
+    fn make_temp(args: &[Slot], i: usize) -> GResult<DynTempMut<Vec<T>>> {
+        match &args[i] {
+            Slot::Arr(arr) => {
+                ensure!(!arr.is_frozen(), "expected a mutable arr, received a frozen arr");
+                Ok(DynTempMut::WriteBack(args[i].clone(), Vec::<T>::from_slot(&args[i])?))
+            }
+            Slot::RData(rdata) => Ok(DynTempMut::RRefMut(rdata.try_borrow_mut()?)),
+            slot => bail!(
+                "expected &mut Vec<{}>, received {}",
+                type_name::<T>(),
+                slot.a_type_name()
+            ),
+        }
+    }
+
+    #[inline]
+    fn from_arg<'a>(temp: &'a mut DynTempMut<Vec<T>>) -> GResult<&'a mut Vec<T>> {
+        match temp {
+            DynTempMut::RRefMut(temp) => Ok(&mut **temp),
+            DynTempMut::WriteBack(_, temp) => Ok(temp),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn write_back(temp: &mut DynTempMut<Vec<T>>) -> GResult<()> {
+        match temp {
+            DynTempMut::WriteBack(Slot::Arr(arr), temp) => {
+                //convert every element before touching the arr, so that a conversion error
+                //doesn't leave it half-written
+                let mut slots = SmallVec::<[Slot; 8]>::with_capacity(temp.len());
+                for item in temp.drain(..) {
+                    slots.push(item.into_slot()?);
+                }
+
+                arr.clear()?;
+                arr.extend(slots)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/*
+a &mut [T] argument is similar to &mut Vec<T>, except that it only accepts a mutable arr, and
+the arr is overwritten element-by-element rather than cleared and refilled. the function
+can't resize a slice, so the lengths will only disagree if the arr itself was resized during
+the call (e.g. by a callback which holds a Root<Arr>). that case is reported as an error.
+
+like &[T], we defer conversion until from_arg(), because a user-defined from_val() could do
+something which pushes to the reg stack while the argument slice is still borrowed.
+
+a &mut [u8] argument can also be passed a Blob, whose bytes are borrowed directly from the
+rdata. strs are not accepted, because their bytes are only ever a copy.
 */
 
+#[doc(hidden)]
+pub enum SliceTempMut<T: 'static> {
+    Converted(Slot, SmallVec<[T; 8]>),
+    Blob(RRefMut<Blob>),
+}
+
+impl<'r, T: FromVal + IntoVal> FromArgRef for &'r mut [T] {
+    type Temp = SliceTempMut<T>;
+    type OutputCtor = RefMutCtor<[T]>;
+
+    #[inline]
+    fn arg_type() -> ArgType {
+        ArgType::Normal
+    }
+
+    #[inline]
+    default fn make_temp(args: &[Slot], i: usize) -> GResult<SliceTempMut<T>> {
+        match &args[i] {
+            Slot::Arr(arr) => {
+                ensure!(!arr.is_frozen(), "expected a mutable arr, received a frozen arr");
+                let vec = SmallVec::with_capacity(arr.len());
+                Ok(SliceTempMut::Converted(args[i].clone(), vec))
+            }
+            slot => bail!(
+                "expected &mut [{}], received {}",
+                type_name::<T>(),
+                slot.a_type_name()
+            ),
+        }
+    }
+
+    #[inline]
+    default fn from_arg<'a>(temp: &'a mut SliceTempMut<T>) -> GResult<&'a mut [T]> {
+        match temp {
+            SliceTempMut::Converted(slot, vec) => {
+                *vec = SmallVec::from_slot(slot)?;
+                Ok(&mut vec[..])
+            }
+            SliceTempMut::Blob(_) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    default fn write_back(temp: &mut SliceTempMut<T>) -> GResult<()> {
+        match temp {
+            SliceTempMut::Converted(Slot::Arr(arr), temp) => {
+                ensure!(
+                    arr.len() == temp.len(),
+                    "arr was resized from {} to {} elements while borrowed as &mut [{}]",
+                    temp.len(),
+                    arr.len(),
+                    type_name::<T>()
+                );
+
+                //convert every element before touching the arr, so that a conversion error
+                //doesn't leave it half-written
+                let mut slots = SmallVec::<[Slot; 8]>::with_capacity(temp.len());
+                for item in temp.drain(..) {
+                    slots.push(item.into_slot()?);
+                }
+
+                for (i, slot) in slots.into_iter().enumerate() {
+                    arr.set(i, slot)?;
+                }
+
+                Ok(())
+            }
+            SliceTempMut::Blob(_) => Ok(()),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'r> FromArgRef for &'r mut [u8] {
+    #[inline]
+    fn make_temp(args: &[Slot], i: usize) -> GResult<SliceTempMut<u8>> {
+        match &args[i] {
+            Slot::RData(rdata) if rdata.is::<Blob>() => {
+                Ok(SliceTempMut::Blob(rdata.try_borrow_mut()?))
+            }
+            Slot::Arr(arr) => {
+                ensure!(!arr.is_frozen(), "expected a mutable arr, received a frozen arr");
+                let vec = SmallVec::with_capacity(arr.len());
+                Ok(SliceTempMut::Converted(args[i].clone(), vec))
+            }
+            slot => bail!("expected &mut [u8], received {}", slot.a_type_name()),
+        }
+    }
+
+    #[inline]
+    fn from_arg<'a>(temp: &'a mut SliceTempMut<u8>) -> GResult<&'a mut [u8]> {
+        match temp {
+            SliceTempMut::Converted(slot, vec) => {
+                *vec = SmallVec::from_slot(slot)?;
+                Ok(&mut vec[..])
+            }
+            SliceTempMut::Blob(blob) => Ok(&mut blob[..]),
+        }
+    }
+}
+
 impl<'r, T: RGlobalMarker + Sized + StaticMarker> FromArgRef for &'r T {
     #[inline]
     fn arg_type() -> ArgType {
@@ -2630,6 +4958,14 @@ impl<T: FromArgRef> FromArgRef for Option<T> {
             Some(temp) => Ok(Some(T::from_arg(temp)?)),
         }
     }
+
+    #[inline]
+    fn write_back(temp: &mut Option<T::Temp>) -> GResult<()> {
+        match temp {
+            None => Ok(()),
+            Some(temp) => T::write_back(temp),
+        }
+    }
 }
 
 macro_rules! impl_pointee_from_arg_ref {
@@ -2655,54 +4991,292 @@ macro_rules! impl_pointee_from_arg_ref {
                     }
                 }
             }
-        )+
-    );
-}
+        )+
+    );
+}
+
+impl_pointee_from_arg_ref!(Arr, Str, Tab, GIter, GFn, Obj, Class, Coro, RData, RFn);
+
+//-------------------------------------------------------------------------------------------------
+// wrap() and its supporting traits
+//-------------------------------------------------------------------------------------------------
+
+#[doc(hidden)]
+pub trait CalculateArgLimits {
+    fn calculate_arg_limits() -> (usize, usize)
+    where
+        Self: Sized;
+
+    fn calculate_arg_types() -> &'static [ArgType]
+    where
+        Self: Sized;
+}
+
+#[doc(hidden)]
+pub trait WrappedCall: CalculateArgLimits {
+    fn arg_limits(&self) -> (usize, usize);
+    fn arg_types(&self) -> &[ArgType];
+    fn wrapped_call(&self, args: Ref<[Slot]>) -> GResult<Slot>;
+}
+
+#[doc(hidden)]
+pub struct Wrapper<ArgsWithTag, Ret, F> {
+    f: F,
+    arg_limits: (usize, usize),
+    arg_types: &'static [ArgType],
+    phantom: PhantomData<(ArgsWithTag, Ret)>,
+}
+
+/*
+a Wrapper's arg_types can't be an associated const, because FromArgRef::arg_type() is
+specialized. instead, each distinct list of arg_types is leaked once and shared by every Wrapper
+which produces it. there are only finitely many parameter lists in any program, so the leak is
+bounded.
+*/
+
+static INTERNED_ARG_TYPES: Mutex<Option<FnvHashSet<&'static [ArgType]>>> = Mutex::new(None);
+
+fn intern_arg_types(arg_types: &[ArgType]) -> &'static [ArgType] {
+    let mut guard = INTERNED_ARG_TYPES.lock().unwrap_or_else(|err| err.into_inner());
+    let interned = guard.get_or_insert_with(FnvHashSet::default);
+
+    match interned.get(arg_types) {
+        Some(&arg_types) => arg_types,
+        None => {
+            let arg_types: &'static [ArgType] = Box::leak(arg_types.to_vec().into_boxed_slice());
+            interned.insert(arg_types);
+            arg_types
+        }
+    }
+}
+
+/*
+previously, we enforced that F, and the wrapper thunk itself, must be convertible to bare
+function pointers. however, this was difficult to enforce and carried almost no performance
+benefit - benchmarking suggests that indirecting a call through Box<dyn WrappedCall> only costs
+one additional cpu cycle, compared to a bare function pointer.
+*/
+
+pub(crate) fn wrap<ArgsWithTag, Ret, F>(f: F) -> Box<dyn WrappedCall>
+where
+    Wrapper<ArgsWithTag, Ret, F>: WrappedCall + 'static,
+{
+    Box::new(Wrapper {
+        f,
+        arg_limits: Wrapper::<ArgsWithTag, Ret, F>::calculate_arg_limits(),
+        arg_types: Wrapper::<ArgsWithTag, Ret, F>::calculate_arg_types(),
+        phantom: PhantomData,
+    })
+}
+
+/*
+used by glsp::rfn_mut. an FnMut closure is stored in a RefCell and exposed through the Fn
+traits, so that it can be passed to wrap() like any other function. the return type is wrapped
+in a GResult, so that a reentrant call can fail with an error rather than a borrow panic.
+
+the Fn traits are implemented for any argument tuple which the inner closure accepts, in the
+same way that the standard library implements them for &F, so type inference is unaffected.
+*/
+
+#[doc(hidden)]
+pub struct FnMutCell<F>(RefCell<F>);
+
+impl<F> FnMutCell<F> {
+    pub(crate) fn new(f: F) -> FnMutCell<F> {
+        FnMutCell(RefCell::new(f))
+    }
+}
+
+impl<Args: std::marker::Tuple, F: FnMut<Args>> FnOnce<Args> for FnMutCell<F> {
+    type Output = GResult<F::Output>;
+
+    extern "rust-call" fn call_once(self, args: Args) -> GResult<F::Output> {
+        self.call(args)
+    }
+}
+
+impl<Args: std::marker::Tuple, F: FnMut<Args>> FnMut<Args> for FnMutCell<F> {
+    extern "rust-call" fn call_mut(&mut self, args: Args) -> GResult<F::Output> {
+        self.call(args)
+    }
+}
+
+impl<Args: std::marker::Tuple, F: FnMut<Args>> Fn<Args> for FnMutCell<F> {
+    extern "rust-call" fn call(&self, args: Args) -> GResult<F::Output> {
+        match self.0.try_borrow_mut() {
+            Ok(mut f) => Ok(f.call_mut(args)),
+            Err(_) => bail!("rfn called reentrantly"),
+        }
+    }
+}
+
+//used by glsp::rfn_with_defaults. when an argument is missing or #n, and it's one of the last
+//defaults.len() parameters, it's replaced with a value produced by the corresponding closure.
+pub(crate) struct WithDefaults {
+    inner: Box<dyn WrappedCall>,
+    defaults: Vec<Box<dyn Fn() -> GResult<Val>>>,
+    first_default: usize,
+    arg_limits: (usize, usize),
+    arg_types: Box<[ArgType]>,
+}
+
+impl WithDefaults {
+    pub(crate) fn new(
+        inner: Box<dyn WrappedCall>,
+        defaults: Vec<Box<dyn Fn() -> GResult<Val>>>,
+    ) -> GResult<WithDefaults> {
+        let (min_args, max_args) = inner.arg_limits();
+        ensure!(
+            max_args != usize::MAX,
+            "default arguments can't be combined with a Rest<T> parameter"
+        );
+        ensure!(
+            defaults.len() <= max_args,
+            "received {} default arguments for a function with {} parameters",
+            defaults.len(),
+            max_args
+        );
+
+        let first_default = max_args - defaults.len();
+
+        //parameters with a default value are reported as optional
+        let mut arg_types = inner.arg_types().to_vec();
+        let mut arg_i = 0;
+        for arg_type in &mut arg_types {
+            if *arg_type != ArgType::RGlobal {
+                if arg_i >= first_default {
+                    *arg_type = ArgType::Option;
+                }
+
+                arg_i += 1;
+            }
+        }
+
+        Ok(WithDefaults {
+            inner,
+            defaults,
+            first_default,
+            arg_limits: (min(min_args, first_default), max_args),
+            arg_types: arg_types.into_boxed_slice(),
+        })
+    }
+}
+
+impl CalculateArgLimits for WithDefaults {
+    fn calculate_arg_limits() -> (usize, usize) {
+        (0, usize::MAX)
+    }
+
+    fn calculate_arg_types() -> &'static [ArgType] {
+        &[ArgType::Rest]
+    }
+}
+
+impl WrappedCall for WithDefaults {
+    fn arg_limits(&self) -> (usize, usize) {
+        self.arg_limits
+    }
+
+    fn arg_types(&self) -> &[ArgType] {
+        &self.arg_types
+    }
+
+    fn wrapped_call(&self, args: Ref<[Slot]>) -> GResult<Slot> {
+        let (min_args, max_args) = self.arg_limits;
+
+        glsp::check_arity(args.len(), min_args, None, None)?;
+
+        //fast path: every parameter has a non-nil argument, or there are too many arguments
+        let first_default = min(self.first_default, args.len());
+        let any_nil = args[first_default..]
+            .iter()
+            .any(|arg| matches!(arg, Slot::Nil));
+
+        if args.len() >= max_args && !any_nil {
+            return self.inner.wrapped_call(args);
+        }
+
+        let mut all_args: SmallVec<[Slot; 8]> = args.iter().cloned().collect();
+
+        //the default closures may call into GameLisp, which will push to the reg stack
+        drop(args);
+
+        //the default values are stored as Vals so that they remain rooted during the call
+        let mut default_vals = SmallVec::<[Val; 4]>::new();
+        for i in self.first_default..max_args {
+            if i >= all_args.len() || matches!(all_args[i], Slot::Nil) {
+                let val = (self.defaults[i - self.first_default])()?;
+                let slot = Slot::from_val(&val);
+                default_vals.push(val);
+
+                if i >= all_args.len() {
+                    all_args.push(slot);
+                } else {
+                    all_args[i] = slot;
+                }
+            }
+        }
 
-impl_pointee_from_arg_ref!(Arr, Str, Tab, GIter, GFn, Obj, Class, Coro, RData, RFn);
+        let cell = RefCell::new(all_args);
+        let result = self.inner.wrapped_call(Ref::map(cell.borrow(), |args| &args[..]));
 
-//-------------------------------------------------------------------------------------------------
-// wrap() and its supporting traits
-//-------------------------------------------------------------------------------------------------
+        drop(default_vals);
+        result
+    }
+}
 
-#[doc(hidden)]
-pub trait CalculateArgLimits {
-    fn calculate_arg_limits() -> (usize, usize)
-    where
-        Self: Sized;
+/**
+A tuple of closures which produce default argument values.
+
+This trait is implemented for tuples of up to eight closures, each of which returns a type
+which implements [`IntoVal`](trait.IntoVal.html). It's used by
+[`glsp::rfn_with_defaults`](fn.rfn_with_defaults.html).
+*/
+pub trait ArgDefaults {
+    #[doc(hidden)]
+    fn into_arg_defaults(self) -> Vec<Box<dyn Fn() -> GResult<Val>>>;
 }
 
-#[doc(hidden)]
-pub trait WrappedCall: CalculateArgLimits {
-    fn arg_limits(&self) -> (usize, usize);
-    fn wrapped_call(&self, args: Ref<[Slot]>) -> GResult<Slot>;
+macro_rules! impl_arg_defaults {
+    ($($t:ident),+) => (
+        impl<$($t),+> ArgDefaults for ($($t,)+)
+        where
+            $(
+                $t: ArgDefault + 'static,
+            )+
+        {
+            #[allow(non_snake_case)]
+            fn into_arg_defaults(self) -> Vec<Box<dyn Fn() -> GResult<Val>>> {
+                let ($($t,)+) = self;
+                vec![$(
+                    Box::new(move || $t.arg_default()),
+                )+]
+            }
+        }
+    );
 }
 
 #[doc(hidden)]
-pub struct Wrapper<ArgsWithTag, Ret, F> {
-    f: F,
-    arg_limits: (usize, usize),
-    phantom: PhantomData<(ArgsWithTag, Ret)>,
+pub trait ArgDefault {
+    fn arg_default(&self) -> GResult<Val>;
 }
 
-/*
-previously, we enforced that F, and the wrapper thunk itself, must be convertible to bare
-function pointers. however, this was difficult to enforce and carried almost no performance
-benefit - benchmarking suggests that indirecting a call through Box<dyn WrappedCall> only costs
-one additional cpu cycle, compared to a bare function pointer.
-*/
-
-pub(crate) fn wrap<ArgsWithTag, Ret, F>(f: F) -> Box<dyn WrappedCall>
-where
-    Wrapper<ArgsWithTag, Ret, F>: WrappedCall + 'static,
-{
-    Box::new(Wrapper {
-        f,
-        arg_limits: Wrapper::<ArgsWithTag, Ret, F>::calculate_arg_limits(),
-        phantom: PhantomData,
-    })
+impl<R: IntoVal, F: Fn() -> R> ArgDefault for F {
+    #[inline]
+    fn arg_default(&self) -> GResult<Val> {
+        self().into_val()
+    }
 }
 
+impl_arg_defaults!(A);
+impl_arg_defaults!(A, B);
+impl_arg_defaults!(A, B, C);
+impl_arg_defaults!(A, B, C, D);
+impl_arg_defaults!(A, B, C, D, E);
+impl_arg_defaults!(A, B, C, D, E, F);
+impl_arg_defaults!(A, B, C, D, E, F, G);
+impl_arg_defaults!(A, B, C, D, E, F, G, H);
+
 macro_rules! arg_limits_fn {
     ($fn_name:ident, $arg_count: literal; $($i:literal)*) => (
 
@@ -2765,10 +5339,10 @@ pub struct TagArgRef;
 
 //an ugly workaround for rustc's tendency to fail to normalize associated types
 //when they're accessed via a HRTB
-pub trait OutputIntoVal<T>: Fn<T> {
+pub trait OutputIntoVal<T: std::marker::Tuple>: Fn<T> {
     fn output_into_slot(output: <Self as FnOnce<T>>::Output) -> GResult<Slot>;
 }
-impl<T, F> OutputIntoVal<T> for F
+impl<T: std::marker::Tuple, F> OutputIntoVal<T> for F
 where
     F: Fn<T>,
     <F as FnOnce<T>>::Output: IntoVal,
@@ -2797,6 +5371,10 @@ macro_rules! wrap_tuple_impls {
             fn calculate_arg_limits() -> (usize, usize) where Self: Sized {
                 $arg_limits_fn([$($arg_t::arg_type(),)*]).unwrap()
             }
+
+            fn calculate_arg_types() -> &'static [ArgType] where Self: Sized {
+                intern_arg_types(&[$($arg_t::arg_type(),)*])
+            }
         }
 
         #[allow(dead_code, unused_assignments, unused_mut, unused_variables)]
@@ -2821,6 +5399,10 @@ macro_rules! wrap_tuple_impls {
                 self.arg_limits
             }
 
+            fn arg_types(&self) -> &[ArgType] {
+                self.arg_types
+            }
+
             fn wrapped_call(&self, args: Ref<[Slot]>) -> GResult<Slot> {
                 /*
                 a dilemma: should we emit argument bounds-checks here, or store them
@@ -2835,36 +5417,34 @@ macro_rules! wrap_tuple_impls {
                 disadvantages:
                 - if we can't make the arg_limits an actual const, constant-folding might
                   fail, executing a large amount of code on every call
-                - we could move these checks elsewhere to reduce monomorphization cost (presumably
-                  only slightly?) and the initial cost of compiling the macro-generated impls
+                - the checks are monomorphized for every rfn, increasing code size and the
+                  initial cost of compiling the macro-generated impls
 
-                for now, we perform the check here, but it's non-const. intend to switch the
-                arg limits into constants asap, once the const_trait_impls feature no longer has
-                the incomplete_features warning (todo)
+                as a compromise, we perform the comparisons here, but the error-formatting is
+                delegated to glsp::check_arity's cold path, so it's only compiled once. intend
+                to switch the arg limits into constants asap, once the const_trait_impls
+                feature no longer has the incomplete_features warning (todo)
                 */
 
-                if args.len() < self.arg_limits.0 {
-                    bail!(
-                        "too few arguments: received {}, expected at least {}",
-                        args.len(),
-                        self.arg_limits.0
-                    )
-                }
-
-                if args.len() > self.arg_limits.1 {
-                    bail!(
-                        "too many arguments: received {}, expected no more than {}",
-                        args.len(),
-                        self.arg_limits.1
-                    )
-                }
+                glsp::check_arity(args.len(), self.arg_limits.0, Some(self.arg_limits.1), None)?;
 
                 let mut arg_i = 0;
 
+                //each temp is paired with its argument index, so that conversion errors can
+                //report which argument was at fault. RGlobal parameters don't consume an
+                //argument, so their errors aren't attributed to one
                 $(
-                    let mut $temp_name = $arg_t::make_temp(&args, arg_i)?;
+                    let arg_index = match $arg_t::arg_type() {
+                        ArgType::RGlobal => None,
+                        _ => Some(arg_i),
+                    };
 
-                    if $arg_t::arg_type() != ArgType::RGlobal {
+                    let mut $temp_name = match $arg_t::make_temp(&args, arg_i) {
+                        Ok(temp) => (arg_index, temp),
+                        Err(err) => return Err(arg_error(err, arg_index)),
+                    };
+
+                    if arg_index.is_some() {
                         arg_i += 1;
                     }
                 )*
@@ -2872,15 +5452,31 @@ macro_rules! wrap_tuple_impls {
                 drop(args);
 
                 let output = (self.f)($(
-                    $arg_t::from_arg(&mut $temp_name)?
+                    match $arg_t::from_arg(&mut $temp_name.1) {
+                        Ok(arg) => arg,
+                        Err(err) => return Err(arg_error(err, $temp_name.0)),
+                    }
                 ),*);
 
+                $(
+                    $arg_t::write_back(&mut $temp_name.1)?;
+                )*
+
                 F::output_into_slot(output)
             }
         }
     );
 }
 
+#[cold]
+#[inline(never)]
+fn arg_error(err: GError, arg_index: Option<usize>) -> GError {
+    match arg_index {
+        Some(i) => err.with_arg_index(i),
+        None => err,
+    }
+}
+
 /*
 for processing arguments, we really need three blanket implementations: FromArg for all
 T: FromVal, FromArg for all &T where T: 'static, and FromArg for all &T where
@@ -3003,6 +5599,25 @@ pub enum Callable {
     Class(Root<Class>),
 }
 
+impl Callable {
+    /**
+    Returns `true` if both `Callables` refer to the same function or class.
+
+    Functions are compared by identity, not by behaviour: two `rfns` which wrap the same Rust
+    function, but which were constructed by separate calls to [`glsp::rfn`](fn.rfn.html),
+    are not considered to be equal. To compare two roots of the same type, use
+    [`Root::ptr_eq`](struct.Root.html#method.ptr_eq).
+    */
+    pub fn ptr_eq(callable0: &Callable, callable1: &Callable) -> bool {
+        match (callable0, callable1) {
+            (Callable::RFn(rfn0), Callable::RFn(rfn1)) => Root::ptr_eq(rfn0, rfn1),
+            (Callable::GFn(gfn0), Callable::GFn(gfn1)) => Root::ptr_eq(gfn0, gfn1),
+            (Callable::Class(class0), Callable::Class(class1)) => Root::ptr_eq(class0, class1),
+            _ => false,
+        }
+    }
+}
+
 /**
 The `callable` abstract type.
 
@@ -3017,6 +5632,12 @@ pub trait CallableOps: callable_ops_private::Sealed {
     #[doc(hidden)]
     fn receive_call(&self, arg_count: usize) -> GResult<Val>;
 
+    //used by glsp::call_unit. an rfn's return value can be discarded without rooting it
+    #[doc(hidden)]
+    fn receive_call_unit(&self, arg_count: usize) -> GResult<()> {
+        self.receive_call(arg_count).map(|_| ())
+    }
+
     ///Returns this function's registered name, if any.
     fn name(&self) -> Option<Sym>;
 
@@ -3032,6 +5653,171 @@ pub trait CallableOps: callable_ops_private::Sealed {
     fn max_args(&self) -> Option<usize> {
         self.arg_limits().1
     }
+
+    /**
+    Invokes this callable value.
+
+    `callable.invoke(args)` is equivalent to [`glsp::call(&callable, args)`](fn.call.html).
+    For information about the `args` parameter, see [`IntoCallArgs`](trait.IntoCallArgs.html).
+
+    This method isn't named `call`, because that name is already taken by
+    [`Class::call`](struct.Class.html#method.call), which invokes a constant.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # fn example(rect_class: Root<Class>, area: Callable) -> GResult<()> {
+    let rect: Root<Obj> = rect_class.invoke(&[10, 10, 50, 50])?;
+    let area: i32 = area.invoke((&rect,))?;
+    # Ok(()) }
+    ```
+    */
+    fn invoke<A: IntoCallArgs, R: FromVal>(&self, args: A) -> GResult<R>
+    where
+        Self: Sized,
+    {
+        glsp::call(self, args)
+    }
+
+    /**
+    Partially applies this callable value, binding its leading arguments.
+
+    Returns a new [`RFn`](struct.RFn.html) which, when called, invokes this callable with the
+    bound `args` followed by its own arguments. The new `RFn` has the same
+    [`name`](#tymethod.name) as this callable, and its [`arg_limits`](#tymethod.arg_limits)
+    are reduced by the number of bound arguments.
+
+    Returns an `Err` if more arguments are bound than this callable's maximum argument count.
+
+    When one of the new `RFn`'s arguments can't be converted, the error message reports that
+    argument's position among this callable's parameters, so the bound arguments are counted.
+    For example, if one argument is bound, an error in the new `RFn`'s first argument refers to
+    "argument 2".
+
+    The bound arguments, and this callable, are rooted until the `RFn` is dropped. Because
+    `RFn`s aren't traced by the garbage collector, a reference cycle which passes through the
+    `RFn` will never be collected.
+
+    ```
+    # extern crate glsp_engine as glsp;
+    # use glsp::*;
+    #
+    # fn example(draw_sprite: Root<RFn>, player_sprite: Root<RData>) -> GResult<()> {
+    let draw_player = draw_sprite.bind_front((player_sprite,))?;
+    glsp::bind_global("draw-player", draw_player)?;
+    # Ok(()) }
+    ```
+    */
+    fn bind_front<A: IntoCallArgs>(&self, args: A) -> GResult<Root<RFn>>
+    where
+        Self: Sized + Clone + IntoVal,
+    {
+        let target = Callable::from_val(&self.clone().into_val()?)?;
+
+        let mut slots = SmallVec::<[Slot; 8]>::with_capacity(args.arg_count());
+        args.into_call_args(&mut slots)?;
+        let front: Vec<Val> = slots.iter().map(|slot| slot.root()).collect();
+
+        let (min_args, max_args) = target.arg_limits();
+        if let Some(max_args) = max_args {
+            ensure!(
+                front.len() <= max_args,
+                "attempted to bind {} arguments to a callable which accepts at most {}",
+                front.len(),
+                max_args
+            );
+        }
+
+        let arg_limits = (
+            min_args.saturating_sub(front.len()),
+            max_args.map_or(usize::MAX, |max_args| max_args - front.len()),
+        );
+
+        //when the target is an rfn, the bound parameters are removed from its arg_types. RGlobal
+        //parameters don't consume an argument, and a Rest parameter can absorb any number of
+        //bound arguments, so neither is removed. otherwise, we synthesize arg_types from the
+        //target's arg_limits
+        let arg_types: Vec<ArgType> = match target {
+            Callable::RFn(ref rfn) => {
+                let mut to_skip = front.len();
+                rfn.arg_types()
+                    .iter()
+                    .copied()
+                    .filter(|&arg_type| {
+                        let skippable = arg_type != ArgType::RGlobal && arg_type != ArgType::Rest;
+                        if to_skip > 0 && skippable {
+                            to_skip -= 1;
+                            false
+                        } else {
+                            true
+                        }
+                    })
+                    .collect()
+            }
+            _ => {
+                let (min_args, max_args) = arg_limits;
+                let mut arg_types = vec![ArgType::Normal; min_args];
+                if max_args == usize::MAX {
+                    arg_types.push(ArgType::Rest);
+                } else {
+                    arg_types.resize(max_args, ArgType::Option);
+                }
+
+                arg_types
+            }
+        };
+
+        let name = target.name();
+        let bound = BoundFront {
+            target,
+            front,
+            arg_limits,
+            arg_types: arg_types.into_boxed_slice(),
+        };
+
+        Ok(glsp::rfn_from_wrapped_call(name, Box::new(bound)))
+    }
+}
+
+//the WrappedCall used by CallableOps::bind_front
+struct BoundFront {
+    target: Callable,
+    front: Vec<Val>,
+    arg_limits: (usize, usize),
+    arg_types: Box<[ArgType]>,
+}
+
+impl CalculateArgLimits for BoundFront {
+    fn calculate_arg_limits() -> (usize, usize) {
+        (0, usize::MAX)
+    }
+
+    fn calculate_arg_types() -> &'static [ArgType] {
+        &[ArgType::Rest]
+    }
+}
+
+impl WrappedCall for BoundFront {
+    fn arg_limits(&self) -> (usize, usize) {
+        self.arg_limits
+    }
+
+    fn arg_types(&self) -> &[ArgType] {
+        &self.arg_types
+    }
+
+    fn wrapped_call(&self, args: Ref<[Slot]>) -> GResult<Slot> {
+        let mut all_args = SmallVec::<[Val; 8]>::with_capacity(self.front.len() + args.len());
+        all_args.extend(self.front.iter().cloned());
+        all_args.extend(args.iter().map(|slot| slot.root()));
+
+        //the Ref must be released before the call, because the call will push to the reg stack
+        drop(args);
+
+        let result: Val = glsp::call(&self.target, &all_args[..])?;
+        result.into_slot()
+    }
 }
 
 mod callable_ops_private {
@@ -3068,6 +5854,14 @@ impl CallableOps for Callable {
         }
     }
 
+    #[inline]
+    fn receive_call_unit(&self, arg_count: usize) -> GResult<()> {
+        match *self {
+            Callable::RFn(ref rfn_root) => rfn_root.receive_call_unit(arg_count),
+            _ => self.receive_call(arg_count).map(|_| ()),
+        }
+    }
+
     fn arg_limits(&self) -> (usize, Option<usize>) {
         match *self {
             Callable::RFn(ref rfn_root) => rfn_root.arg_limits(),
@@ -3089,8 +5883,9 @@ impl CallableOps for Callable {
 A type which can be converted into the arguments to a function call.
 
 It's not possible to implement this trait for your own types, but it's implemented for tuples,
-slices, arrays, and references to the same, when their elements all implement
-[`IntoVal`](trait.IntoVal.html).
+slices, arrays, `Vec`s, and references to the same, when their elements all implement
+[`IntoVal`](trait.IntoVal.html). To pass in the contents of an iterator, wrap it in
+[`ArgsFromIter`](struct.ArgsFromIter.html).
 
 Functions like [`glsp:call`](fn.call.html) and [`Obj::call`](struct.Obj.html#method.call) are
 generic over this trait:
@@ -3124,13 +5919,19 @@ pub trait IntoCallArgs: into_call_args_private::Sealed {
 }
 
 mod into_call_args_private {
-    use crate::wrap::{IntoVal, Rest};
+    use crate::wrap::{ArgsFromIter, IntoVal, OwnedRest, Rest};
 
     pub trait Sealed {}
 
     impl<'a, T> Sealed for &'a [T] where &'a T: IntoVal {}
     impl<'a, T> Sealed for &'a mut [T] where &'a mut T: IntoVal {}
 
+    impl<T> Sealed for Vec<T> where T: IntoVal {}
+    impl<'a, T> Sealed for &'a Vec<T> where &'a T: IntoVal {}
+    impl<'a, T> Sealed for &'a mut Vec<T> where &'a mut T: IntoVal {}
+
+    impl<I: Iterator> Sealed for ArgsFromIter<I> where I::Item: IntoVal {}
+
     impl<T, const N: usize> Sealed for [T; N] where for<'a> &'a T: IntoVal {}
     impl<'a, T, const N: usize> Sealed for &'a [T; N] where &'a T: IntoVal {}
     impl<'a, T, const N: usize> Sealed for &'a mut [T; N] where &'a mut T: IntoVal {}
@@ -3139,11 +5940,36 @@ mod into_call_args_private {
     impl<'r, 'a: 'r, T> Sealed for &'r Rest<'a, T> where &'r T: IntoVal {}
     impl<'r, 'a: 'r, T> Sealed for &'r mut Rest<'a, T> where &'r mut T: IntoVal {}
 
+    impl<T> Sealed for OwnedRest<T> where T: IntoVal {}
+    impl<'r, T> Sealed for &'r OwnedRest<T> where &'r T: IntoVal {}
+    impl<'r, T> Sealed for &'r mut OwnedRest<T> where &'r mut T: IntoVal {}
+
     impl Sealed for () {}
     impl<'a> Sealed for &'a () {}
     impl<'a> Sealed for &'a mut () {}
 }
 
+//converts each item into a slot and appends it to `dst`. Extend can't fail, so when a conversion
+//fails, we push a placeholder and return the first error after `dst` has been extended.
+fn extend_call_args<E, I>(dst: &mut E, iter: I) -> GResult<()>
+where
+    E: Extend<Slot>,
+    I: Iterator,
+    I::Item: IntoVal,
+{
+    let mut result = Ok(());
+    dst.extend(iter.map(|item| match item.into_slot() {
+        Ok(slot) => slot,
+        Err(err) => {
+            if result.is_ok() {
+                result = Err(err);
+            }
+            Slot::Nil
+        }
+    }));
+    result
+}
+
 impl<'a, T> IntoCallArgs for &'a [T]
 where
     &'a T: IntoVal,
@@ -3153,17 +5979,7 @@ where
     }
 
     fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
-        let mut result = Ok(());
-        dst.extend(self.iter().map(|item| match item.into_slot() {
-            Ok(slot) => slot,
-            Err(err) => {
-                if result.is_ok() {
-                    result = Err(err);
-                }
-                Slot::Nil
-            }
-        }));
-        result
+        extend_call_args(dst, self.iter())
     }
 }
 
@@ -3176,17 +5992,77 @@ where
     }
 
     fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
-        let mut result = Ok(());
-        dst.extend(self.iter_mut().map(|item| match item.into_slot() {
-            Ok(slot) => slot,
-            Err(err) => {
-                if result.is_ok() {
-                    result = Err(err);
-                }
-                Slot::Nil
-            }
-        }));
-        result
+        extend_call_args(dst, self.iter_mut())
+    }
+}
+
+impl<T> IntoCallArgs for Vec<T>
+where
+    T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        ArgsFromIter(self.into_iter()).into_call_args(dst)
+    }
+}
+
+impl<'a, T> IntoCallArgs for &'a Vec<T>
+where
+    &'a T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        (&self[..]).into_call_args(dst)
+    }
+}
+
+impl<'a, T> IntoCallArgs for &'a mut Vec<T>
+where
+    &'a mut T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        (&mut self[..]).into_call_args(dst)
+    }
+}
+
+/**
+An adapter which passes the contents of an iterator as the arguments to a function call.
+
+```
+# extern crate glsp_engine as glsp;
+# use glsp::*;
+#
+# fn example(print_all: Root<RFn>, names: &[&str]) -> GResult<()> {
+let _: Val = glsp::call(&print_all, ArgsFromIter(names.iter().map(|name| name.len())))?;
+# Ok(()) }
+```
+
+Its [`arg_count`](trait.IntoCallArgs.html#tymethod.arg_count) is the lower bound of the
+iterator's `size_hint`, so it may be an underestimate.
+*/
+
+pub struct ArgsFromIter<I: Iterator>(pub I);
+
+impl<I: Iterator> IntoCallArgs for ArgsFromIter<I>
+where
+    I::Item: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.0.size_hint().0
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        extend_call_args(dst, self.0)
     }
 }
 
@@ -3238,17 +6114,7 @@ where
     }
 
     fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
-        let mut result = Ok(());
-        dst.extend(self.into_iter().map(|item| match item.into_slot() {
-            Ok(slot) => slot,
-            Err(err) => {
-                if result.is_ok() {
-                    result = Err(err);
-                }
-                Slot::Nil
-            }
-        }));
-        result
+        extend_call_args(dst, self.into_iter())
     }
 }
 
@@ -3261,17 +6127,7 @@ where
     }
 
     fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
-        let mut result = Ok(());
-        dst.extend(self.into_iter().map(|item| match item.into_slot() {
-            Ok(slot) => slot,
-            Err(err) => {
-                if result.is_ok() {
-                    result = Err(err);
-                }
-                Slot::Nil
-            }
-        }));
-        result
+        extend_call_args(dst, self.into_iter())
     }
 }
 
@@ -3284,17 +6140,46 @@ where
     }
 
     fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
-        let mut result = Ok(());
-        dst.extend(self.into_iter().map(|item| match item.into_slot() {
-            Ok(slot) => slot,
-            Err(err) => {
-                if result.is_ok() {
-                    result = Err(err);
-                }
-                Slot::Nil
-            }
-        }));
-        result
+        extend_call_args(dst, self.into_iter())
+    }
+}
+
+impl<T> IntoCallArgs for OwnedRest<T>
+where
+    T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        extend_call_args(dst, self.into_iter())
+    }
+}
+
+impl<'r, T> IntoCallArgs for &'r OwnedRest<T>
+where
+    &'r T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        extend_call_args(dst, self.into_iter())
+    }
+}
+
+impl<'r, T> IntoCallArgs for &'r mut OwnedRest<T>
+where
+    &'r mut T: IntoVal,
+{
+    fn arg_count(&self) -> usize {
+        self.len()
+    }
+
+    fn into_call_args<E: Extend<Slot>>(self, dst: &mut E) -> GResult<()> {
+        extend_call_args(dst, self.into_iter())
     }
 }
 
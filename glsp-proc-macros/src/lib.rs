@@ -1,7 +1,7 @@
 #![forbid(unsafe_code)]
 
 use glsp::{stock_syms::*, DequeAccess, DequeOps, Engine, SymKind, Val};
-use proc_macro::{TokenStream, TokenTree, TokenTree::Literal};
+use proc_macro::{Delimiter, TokenStream, TokenTree, TokenTree::Literal};
 use std::char;
 use std::collections::{hash_map::Entry, HashMap};
 use std::fmt::Write;
@@ -250,6 +250,396 @@ pub fn try_backquote(input: TokenStream) -> TokenStream {
     TokenStream::from_str(&output).unwrap()
 }
 
+/**
+Implements [`IntoVal`](trait.IntoVal.html) and [`FromVal`](trait.FromVal.html) for a wrapper
+around an unsigned integer.
+
+The input must be a struct with a single field, which must have one of the types `u8`, `u16`,
+`u32`, `u64`, `u128` or `usize`. The field may be named, like the `bits` field generated by the
+[`bitflags`](https://docs.rs/bitflags) crate, or it may belong to a tuple struct.
+
+`IntoVal` converts the struct into its inner integer. `FromVal` converts an integer into the
+struct, returning an `Err` if the integer is out of range for the field's type. This means that
+the struct can be passed to and from GameLisp as an ordinary `int`, rather than being moved onto
+the heap as an `rdata`.
+
+Like hand-written `FromVal` implementations, the generated code requires the
+`min_specialization` feature to be enabled in your crate.
+
+```ignore
+#![feature(min_specialization)]
+
+#[derive(Copy, Clone, GlspBits)]
+struct Layers(u32);
+```
+*/
+
+#[proc_macro_derive(GlspBits)]
+pub fn derive_glsp_bits(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+
+    //skip attributes and visibility, then expect `struct Name`
+    let mut i = 0;
+    let name = loop {
+        match tokens.get(i) {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => i += 2,
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                i += 1;
+                if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        i += 1;
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {
+                match tokens.get(i + 1) {
+                    Some(TokenTree::Ident(name)) => break name.to_string(),
+                    _ => panic!("#[derive(GlspBits)] expected a struct name"),
+                }
+            }
+            _ => panic!("#[derive(GlspBits)] can only be applied to a struct"),
+        }
+    };
+
+    //expect a single field, either `(pub u32)` or `{ pub bits: u32 }`
+    let (is_tuple, body) = match tokens.get(i + 2) {
+        Some(TokenTree::Group(group)) => match group.delimiter() {
+            Delimiter::Parenthesis => (true, group.stream()),
+            Delimiter::Brace => (false, group.stream()),
+            _ => panic!("#[derive(GlspBits)] expected a struct with a single field"),
+        },
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            panic!("#[derive(GlspBits)] does not support generic structs")
+        }
+        _ => panic!("#[derive(GlspBits)] expected a struct with a single field"),
+    };
+
+    let mut field_tokens: Vec<TokenTree> = body.into_iter().collect();
+    if let Some(TokenTree::Punct(punct)) = field_tokens.last() {
+        if punct.as_char() == ',' {
+            field_tokens.pop();
+        }
+    }
+
+    let mut j = 0;
+    let mut field_name = String::from("0");
+    let mut field_type = None;
+    while j < field_tokens.len() {
+        match &field_tokens[j] {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => j += 2,
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                panic!("#[derive(GlspBits)] expected a struct with a single field")
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ':' => j += 1,
+            TokenTree::Group(_) => j += 1,
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                if ident != "pub" && ident != "crate" {
+                    if !is_tuple && field_type.is_none() && field_name == "0" {
+                        field_name = ident;
+                    } else if field_type.is_none() {
+                        field_type = Some(ident);
+                    } else {
+                        panic!("#[derive(GlspBits)] expected an unsigned integer field")
+                    }
+                }
+
+                j += 1;
+            }
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {
+                panic!("#[derive(GlspBits)] expected an unsigned integer field")
+            }
+        }
+    }
+
+    let field_type = match field_type {
+        Some(ty) if ["u8", "u16", "u32", "u64", "u128", "usize"].contains(&&ty[..]) => ty,
+        _ => panic!("#[derive(GlspBits)] expected an unsigned integer field"),
+    };
+
+    let ctor = if is_tuple {
+        format!("{}(bits)", name)
+    } else {
+        format!("{} {{ {}: bits }}", name, field_name)
+    };
+
+    let mut output = String::new();
+    for self_type in &[name.clone(), format!("&{}", name), format!("&mut {}", name)] {
+        write!(
+            &mut output,
+            r#"
+            impl ::glsp::IntoVal for {self_type} {{
+                #[inline]
+                fn into_val(self) -> ::glsp::GResult<::glsp::Val> {{
+                    ::glsp::IntoVal::into_val(self.{field_name})
+                }}
+            }}
+            "#,
+            self_type = self_type,
+            field_name = field_name
+        )
+        .unwrap();
+    }
+
+    write!(
+        &mut output,
+        r#"
+        impl ::glsp::FromVal for {name} {{
+            #[inline]
+            fn from_val(val: &::glsp::Val) -> ::glsp::GResult<Self> {{
+                let bits = <{field_type} as ::glsp::FromVal>::from_val(val)?;
+                Ok({ctor})
+            }}
+        }}
+        "#,
+        name = name,
+        field_type = field_type,
+        ctor = ctor
+    )
+    .unwrap();
+
+    TokenStream::from_str(&output).unwrap()
+}
+
+/**
+Implements [`IntoVal`](trait.IntoVal.html) and [`FromVal`](trait.FromVal.html) for an enum,
+representing each variant as a symbol.
+
+The input must be an enum with no generic parameters, and every variant must be fieldless.
+By default, each variant's name is converted to kebab-case: `North` becomes `north`, and
+`NorthEast` becomes `north-east`. The symbol for an individual variant can be overridden
+using the `#[glsp(rename = "...")]` attribute.
+
+`IntoVal` converts each variant into its symbol. `FromVal` converts a symbol back into a variant,
+returning an `Err` which lists the valid symbols if it doesn't recognize the symbol.
+
+Like hand-written `FromVal` implementations, the generated code requires the
+`min_specialization` feature to be enabled in your crate.
+
+```ignore
+#![feature(min_specialization)]
+
+#[derive(Copy, Clone, GlspSym)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+    #[glsp(rename = "nowhere")]
+    Stationary,
+}
+```
+*/
+
+#[proc_macro_derive(GlspSym, attributes(glsp))]
+pub fn derive_glsp_sym(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+
+    //skip attributes and visibility, then expect `enum Name`
+    let mut i = 0;
+    let name = loop {
+        match tokens.get(i) {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => i += 2,
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                i += 1;
+                if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        i += 1;
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "enum" => {
+                match tokens.get(i + 1) {
+                    Some(TokenTree::Ident(name)) => break name.to_string(),
+                    _ => panic!("#[derive(GlspSym)] expected an enum name"),
+                }
+            }
+            _ => panic!("#[derive(GlspSym)] can only be applied to an enum"),
+        }
+    };
+
+    let body = match tokens.get(i + 2) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            panic!("#[derive(GlspSym)] does not support generic enums")
+        }
+        _ => panic!("#[derive(GlspSym)] expected an enum body"),
+    };
+
+    //collect (variant name, symbol name) pairs
+    let variant_tokens: Vec<TokenTree> = body.into_iter().collect();
+    let mut variants = Vec::<(String, String)>::new();
+    let mut rename = None;
+    let mut j = 0;
+    while j < variant_tokens.len() {
+        match &variant_tokens[j] {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = variant_tokens.get(j + 1) {
+                    if let Some(sym_name) = parse_glsp_rename(group.stream()) {
+                        rename = Some(sym_name);
+                    }
+                }
+
+                j += 2;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ',' => j += 1,
+            TokenTree::Punct(punct) if punct.as_char() == '=' => {
+                //skip an explicit discriminant
+                while j < variant_tokens.len() {
+                    match &variant_tokens[j] {
+                        TokenTree::Punct(punct) if punct.as_char() == ',' => break,
+                        _ => j += 1,
+                    }
+                }
+            }
+            TokenTree::Ident(ident) => {
+                let variant = ident.to_string();
+                if let Some(TokenTree::Group(_)) = variant_tokens.get(j + 1) {
+                    panic!(
+                        "#[derive(GlspSym)] expected {} to be a fieldless variant",
+                        variant
+                    )
+                }
+
+                let sym_name = rename.take().unwrap_or_else(|| to_kebab_case(&variant));
+                assert!(
+                    glsp::is_valid_sym(&sym_name),
+                    "#[derive(GlspSym)] produced the invalid symbol {:?}",
+                    sym_name
+                );
+                assert!(
+                    variants.iter().all(|(_, existing)| *existing != sym_name),
+                    "#[derive(GlspSym)] produced the symbol {:?} more than once",
+                    sym_name
+                );
+
+                variants.push((variant, sym_name));
+                j += 1;
+            }
+            _ => panic!("#[derive(GlspSym)] expected a fieldless enum"),
+        }
+    }
+
+    assert!(
+        !variants.is_empty(),
+        "#[derive(GlspSym)] expected at least one variant"
+    );
+
+    let mut into_arms = String::new();
+    let mut from_arms = String::new();
+    for (variant, sym_name) in &variants {
+        writeln!(&mut into_arms, "{}::{} => {:?},", name, variant, sym_name).unwrap();
+        writeln!(
+            &mut from_arms,
+            "{:?} => Ok({}::{}),",
+            sym_name, name, variant
+        )
+        .unwrap();
+    }
+
+    let valid_syms = variants
+        .iter()
+        .map(|(_, sym_name)| &sym_name[..])
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let valid_note = format!("valid symbols are {}", valid_syms);
+
+    let mut output = String::new();
+    for self_type in &[name.clone(), format!("&{}", name), format!("&mut {}", name)] {
+        write!(
+            &mut output,
+            r#"
+            impl ::glsp::IntoVal for {self_type} {{
+                #[inline]
+                fn into_val(self) -> ::glsp::GResult<::glsp::Val> {{
+                    let name = match self {{
+                        {into_arms}
+                    }};
+
+                    Ok(::glsp::Val::Sym(::glsp::sym(name)?))
+                }}
+            }}
+            "#,
+            self_type = self_type,
+            into_arms = into_arms
+        )
+        .unwrap();
+    }
+
+    write!(
+        &mut output,
+        r#"
+        impl ::glsp::FromVal for {name} {{
+            #[inline]
+            fn from_val(val: &::glsp::Val) -> ::glsp::GResult<Self> {{
+                match *val {{
+                    ::glsp::Val::Sym(sym) => match &*sym.name() {{
+                        {from_arms}
+                        _ => ::glsp::bail!(
+                            "expected a {name}, received the symbol {{}}; {valid}",
+                            sym
+                        ),
+                    }},
+                    ref val => ::glsp::bail!(
+                        "expected a {name}, received {{}}",
+                        val.a_type_name()
+                    ),
+                }}
+            }}
+        }}
+        "#,
+        name = name,
+        from_arms = from_arms,
+        valid = valid_note
+    )
+    .unwrap();
+
+    TokenStream::from_str(&output).unwrap()
+}
+
+//parses the contents of an attribute like #[glsp(rename = "north")], returning "north"
+fn parse_glsp_rename(input: TokenStream) -> Option<String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    match &tokens[..] {
+        [TokenTree::Ident(ident), TokenTree::Group(group)] if ident.to_string() == "glsp" => {
+            let args: Vec<TokenTree> = group.stream().into_iter().collect();
+            match &args[..] {
+                [TokenTree::Ident(key), TokenTree::Punct(eq), Literal(lit)]
+                    if key.to_string() == "rename" && eq.as_char() == '=' =>
+                {
+                    let lit_stream: TokenStream = TokenTree::Literal(lit.clone()).into();
+                    Some(parse_single_str_literal(lit_stream))
+                }
+                _ => panic!("#[derive(GlspSym)] expected #[glsp(rename = \"...\")]"),
+            }
+        }
+        _ => None,
+    }
+}
+
+//NorthEast becomes north-east, and HTTPServer becomes http-server
+fn to_kebab_case(variant: &str) -> String {
+    let chars: Vec<char> = variant.chars().collect();
+    let mut kebab = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                kebab.push('-');
+            }
+        }
+
+        if ch == '_' {
+            kebab.push('-');
+        } else {
+            kebab.extend(ch.to_lowercase());
+        }
+    }
+
+    kebab
+}
+
 fn emit_val_for_quote<T: Write>(dst: &mut T, val: &Val) {
     match *val {
         Val::Nil => write!(dst, "::glsp::Val::Nil").unwrap(),
@@ -126,23 +126,11 @@ pub fn init(sandboxed: bool) -> GResult<()> {
 }
 
 fn type_of(arg: Val) -> Sym {
+    //Val::type_sym returns an rdata's RClass name, but type-of is only concerned with
+    //primitive types
     match arg {
-        Val::Nil => NIL_SYM,
-        Val::Int(_) => INT_SYM,
-        Val::Flo(_) => FLO_SYM,
-        Val::Char(_) => CHAR_SYM,
-        Val::Bool(_) => BOOL_SYM,
-        Val::Sym(_) => SYM_SYM,
-        Val::RFn(_) => RFN_SYM,
-        Val::Arr(_) => ARR_SYM,
-        Val::Str(_) => STR_SYM,
-        Val::Tab(_) => TAB_SYM,
-        Val::GIter(_) => ITER_SYM,
-        Val::Obj(_) => OBJ_SYM,
-        Val::Class(_) => CLASS_SYM,
-        Val::GFn(_) => FN_SYM,
-        Val::Coro(_) => CORO_SYM,
         Val::RData(_) => RDATA_SYM,
+        arg => arg.type_sym(),
     }
 }
 
@@ -14,6 +14,81 @@ pub use glsp_stdlib::*;
 #[cfg(feature = "compiler")]
 pub use glsp_proc_macros2::*;
 
+//the derive macros can't be tested from within glsp-proc-macros, so they're tested here
+
+/**
+```
+#![feature(min_specialization)]
+
+# use glsp::prelude::*;
+# use glsp::{Engine, GlspBits};
+#
+#[derive(Copy, Clone, Debug, PartialEq, GlspBits)]
+struct Layers(u32);
+
+#[derive(Copy, Clone, Debug, PartialEq, GlspBits)]
+struct Mask {
+    bits: u8,
+}
+
+# Engine::new().run(|| {
+assert!(Layers(0b101).into_val()? == Val::Int(0b101));
+assert!(Layers::from_val(&Val::Int(0b110))? == Layers(0b110));
+assert!(Layers::from_val(&Val::Int(-1)).is_err());
+
+assert!((&Mask { bits: 7 }).into_val()? == Val::Int(7));
+assert!(Mask::from_val(&Val::Int(255))? == Mask { bits: 255 });
+assert!(Mask::from_val(&Val::Int(256)).is_err());
+
+let toggle = glsp::rfn(|layers: Layers| Layers(layers.0 ^ 1));
+let toggled: Layers = glsp::call(&toggle, (Layers(0b10),))?;
+assert!(toggled == Layers(0b11));
+# Ok(()) }).unwrap();
+```
+*/
+#[cfg(doctest)]
+pub struct GlspBitsDoctests;
+
+/**
+```
+#![feature(min_specialization)]
+
+# use glsp::prelude::*;
+# use glsp::{Engine, GlspSym};
+#
+#[derive(Copy, Clone, Debug, PartialEq, GlspSym)]
+enum Direction {
+    North,
+    NorthEast,
+    #[glsp(rename = "nowhere")]
+    Stationary,
+}
+
+# Engine::new().run(|| {
+assert!(Direction::North.into_val()? == Val::Sym(sym!("north")));
+assert!((&Direction::NorthEast).into_val()? == Val::Sym(sym!("north-east")));
+assert!(Direction::Stationary.into_val()? == Val::Sym(sym!("nowhere")));
+
+assert!(Direction::from_val(&Val::Sym(sym!("north-east")))? == Direction::NorthEast);
+let err = Direction::from_val(&Val::Sym(sym!("stationary"))).unwrap_err();
+assert!(err.to_string().contains(
+    "expected a Direction, received the symbol stationary; \
+     valid symbols are north, north-east, nowhere"
+));
+assert!(Direction::from_val(&Val::Int(0)).is_err());
+
+let reverse = glsp::rfn(|dir: Direction| match dir {
+    Direction::North => Direction::Stationary,
+    _ => Direction::North,
+});
+let reversed: Sym = glsp::call(&reverse, (Direction::North,))?;
+assert!(reversed == sym!("nowhere"));
+# Ok(()) }).unwrap();
+```
+*/
+#[cfg(doctest)]
+pub struct GlspSymDoctests;
+
 pub mod prelude {
     /*!
     The prelude.
@@ -29,13 +104,14 @@ pub mod prelude {
 
     #[doc(no_inline)]
     pub use crate::{
-        arr, backquote, bail, bail_at, ensure, ensure_at, epr, eprn, error, macro_no_op, pr, prn,
-        quote, str, sym, syms, tab, try_arr, try_backquote, try_tab, Arr, Callable, CallableOps,
-        Class, Coro, CoroState, Deque, DequeAccess, DequeAccessRange, DequeOps, EnvMode, Expander,
-        Expansion, FromVal, GError, GFn, GIter, GIterLen, GResult, Gc, GcVal, GcVisitor, Hashable,
-        IntoVal, Iterable, IterableOps, Num, Obj, RClass, RClassBuilder, RData, RFn, RGc, RGlobal,
-        RGlobalRef, RGlobalRefMut, RRef, RRefMut, RRoot, Rest, Root, Runtime, RuntimeBuilder,
-        Splay, Str, Sym, Tab, ToSym, Val,
+        arr, backquote, bail, bail_at, bind_rfn, ensure, ensure_at, epr, eprn, error, macro_no_op,
+        pr, prn, quote, str, sym, syms, tab, tagged_enum, try_arr, try_backquote, try_tab, Arr,
+        Callable, CallableOps, Class, Coro, CoroState, CoroStatus, Deque, DequeAccess,
+        DequeAccessRange, DequeOps, Either, EnvMode, Expander, Expansion, FromVal, GError, GFn,
+        GIter, GIterLen, GResult, Gc, GcVal, GcVisitor, Hashable, HeapSerializer, IntoErrorVal,
+        IntoVal, IntoValExt, Iterable, IterableOps, Num, Obj, RClass, RClassBuilder, RData, RFn,
+        RGc, RGlobal, RGlobalRef, RGlobalRefMut, RRef, RRefMut, RRoot, RTrait, Rest, Root,
+        RootSet, RunOutcome, Runtime, RuntimeBuilder, Splay, Str, Sym, Tab, ToSym, Val,
     };
 
     #[cfg(feature = "compiler")]